@@ -0,0 +1,5 @@
+-- Per-account brute-force protection: consecutive failed logins and, once
+-- past the threshold, a lockout that grows with every further failure.
+ALTER TABLE users
+    ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN locked_until TIMESTAMPTZ;
@@ -0,0 +1,6 @@
+-- Self-service account deletion. The account is purged once this time passes,
+-- unless the user cancels first.
+ALTER TABLE users ADD COLUMN scheduled_deletion_at TIMESTAMPTZ;
+
+CREATE INDEX idx_users_scheduled_deletion ON users(scheduled_deletion_at)
+    WHERE scheduled_deletion_at IS NOT NULL;
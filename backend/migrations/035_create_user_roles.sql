@@ -0,0 +1,14 @@
+-- Staff roles replace the single is_admin flag. What each role may do is
+-- defined in code (models::Role); this table only records who holds which.
+CREATE TABLE user_roles (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(32) NOT NULL,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, role)
+);
+
+INSERT INTO user_roles (user_id, role, granted_at)
+SELECT id, 'admin', updated_at FROM users WHERE is_admin;
+
+ALTER TABLE users DROP COLUMN is_admin;
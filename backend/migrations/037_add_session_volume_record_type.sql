@@ -0,0 +1,3 @@
+-- Best total volume for an exercise within one workout. 'max-volume' is the
+-- best single set.
+ALTER TYPE record_type ADD VALUE 'max-session-volume' AFTER 'max-volume';
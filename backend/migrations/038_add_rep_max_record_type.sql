@@ -0,0 +1,3 @@
+-- Heaviest weight lifted for exactly `reps` reps (1-20), one record line per
+-- rep count.
+ALTER TYPE record_type ADD VALUE 'rep-max' AFTER 'estimated-1rm';
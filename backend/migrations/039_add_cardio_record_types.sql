@@ -0,0 +1,13 @@
+-- Category-aware records. Fastest times and best pace are in seconds (seconds
+-- per km for pace) and are better when lower; longest distance is in meters,
+-- longest hold in seconds and heaviest carry in the user's weight unit.
+ALTER TYPE record_type ADD VALUE 'fastest-1k' AFTER 'rep-max';
+ALTER TYPE record_type ADD VALUE 'fastest-5k' AFTER 'fastest-1k';
+ALTER TYPE record_type ADD VALUE 'fastest-10k' AFTER 'fastest-5k';
+ALTER TYPE record_type ADD VALUE 'longest-distance' AFTER 'fastest-10k';
+ALTER TYPE record_type ADD VALUE 'best-pace' AFTER 'longest-distance';
+ALTER TYPE record_type ADD VALUE 'longest-hold' AFTER 'best-pace';
+ALTER TYPE record_type ADD VALUE 'heaviest-carry' AFTER 'longest-hold';
+
+-- Distance of the set a record came from, where it matters (cardio and carries)
+ALTER TABLE personal_records ADD COLUMN distance_meters DOUBLE PRECISION;
@@ -0,0 +1,27 @@
+-- Each record row is either current or was superseded by a later, better one.
+-- previous_value is the best it beat, for showing the improvement.
+ALTER TABLE personal_records
+    ADD COLUMN previous_value DOUBLE PRECISION,
+    ADD COLUMN superseded_at TIMESTAMPTZ;
+
+-- Rows were only ever added when they beat the best so far, so within each
+-- record line (rep maxes have one per rep count) they are in order of achievement.
+WITH ordered AS (
+    SELECT id,
+           LAG(value) OVER w AS previous_value,
+           LEAD(achieved_at) OVER w AS superseded_at
+    FROM personal_records
+    WINDOW w AS (
+        PARTITION BY user_id, exercise_template_id, record_type,
+                     CASE WHEN record_type = 'rep-max' THEN reps END
+        ORDER BY achieved_at, id
+    )
+)
+UPDATE personal_records pr
+SET previous_value = ordered.previous_value, superseded_at = ordered.superseded_at
+FROM ordered
+WHERE ordered.id = pr.id;
+
+CREATE INDEX idx_personal_records_current
+    ON personal_records(user_id, exercise_template_id, record_type)
+    WHERE superseded_at IS NULL;
@@ -0,0 +1,5 @@
+-- Let users choose how estimated 1RMs are calculated
+CREATE TYPE one_rep_max_formula AS ENUM ('brzycki', 'epley', 'lombardi', 'oconner', 'wathan', 'mayhew', 'average');
+
+ALTER TABLE user_settings
+ADD COLUMN one_rep_max_formula one_rep_max_formula NOT NULL DEFAULT 'brzycki';
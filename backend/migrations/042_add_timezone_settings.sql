@@ -0,0 +1,6 @@
+-- Bucket statistics by the user's local day and week
+CREATE TYPE week_start AS ENUM ('monday', 'sunday');
+
+ALTER TABLE user_settings
+ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC',
+ADD COLUMN week_start week_start NOT NULL DEFAULT 'monday';
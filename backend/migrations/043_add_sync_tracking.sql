@@ -0,0 +1,97 @@
+-- Change tracking for the /sync delta feed. Each synced entity carries an
+-- updated_at that triggers keep current, including when a child row (an
+-- exercise or set) changes, so no write path can forget to bump it.
+ALTER TABLE workouts ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE workout_templates ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE body_measurements ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE user_settings ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE FUNCTION touch_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER workouts_touch BEFORE UPDATE ON workouts
+    FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER workout_templates_touch BEFORE UPDATE ON workout_templates
+    FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER body_measurements_touch BEFORE UPDATE ON body_measurements
+    FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+CREATE TRIGGER user_settings_touch BEFORE UPDATE ON user_settings
+    FOR EACH ROW EXECUTE FUNCTION touch_updated_at();
+
+CREATE FUNCTION touch_workout_from_exercise() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE workouts SET updated_at = NOW()
+    WHERE id = CASE WHEN TG_OP = 'DELETE' THEN OLD.workout_id ELSE NEW.workout_id END;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION touch_workout_from_set() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE workouts SET updated_at = NOW()
+    WHERE id = (
+        SELECT workout_id FROM workout_exercises
+        WHERE id = CASE WHEN TG_OP = 'DELETE' THEN OLD.workout_exercise_id ELSE NEW.workout_exercise_id END
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION touch_template_from_exercise() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE workout_templates SET updated_at = NOW()
+    WHERE id = CASE WHEN TG_OP = 'DELETE' THEN OLD.template_id ELSE NEW.template_id END;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION touch_template_from_set() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE workout_templates SET updated_at = NOW()
+    WHERE id = (
+        SELECT template_id FROM template_exercises
+        WHERE id = CASE WHEN TG_OP = 'DELETE' THEN OLD.template_exercise_id ELSE NEW.template_exercise_id END
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER workout_exercises_touch AFTER INSERT OR UPDATE OR DELETE ON workout_exercises
+    FOR EACH ROW EXECUTE FUNCTION touch_workout_from_exercise();
+CREATE TRIGGER workout_sets_touch AFTER INSERT OR UPDATE OR DELETE ON workout_sets
+    FOR EACH ROW EXECUTE FUNCTION touch_workout_from_set();
+CREATE TRIGGER template_exercises_touch AFTER INSERT OR UPDATE OR DELETE ON template_exercises
+    FOR EACH ROW EXECUTE FUNCTION touch_template_from_exercise();
+CREATE TRIGGER template_sets_touch AFTER INSERT OR UPDATE OR DELETE ON template_sets
+    FOR EACH ROW EXECUTE FUNCTION touch_template_from_set();
+
+-- Workouts and templates are soft-deleted, so their deletions show up through
+-- deleted_at. Measurements are deleted outright and leave a tombstone instead.
+CREATE TABLE sync_tombstones (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entity VARCHAR(32) NOT NULL,
+    entity_id UUID NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE FUNCTION record_measurement_tombstone() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO sync_tombstones (user_id, entity, entity_id)
+    SELECT OLD.user_id, 'measurement', OLD.id
+    WHERE EXISTS (SELECT 1 FROM users WHERE id = OLD.user_id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER body_measurements_tombstone AFTER DELETE ON body_measurements
+    FOR EACH ROW EXECUTE FUNCTION record_measurement_tombstone();
+
+CREATE INDEX idx_workouts_user_updated ON workouts(user_id, updated_at);
+CREATE INDEX idx_workout_templates_user_updated ON workout_templates(user_id, updated_at);
+CREATE INDEX idx_body_measurements_user_updated ON body_measurements(user_id, updated_at);
+CREATE INDEX idx_sync_tombstones_user_deleted ON sync_tombstones(user_id, deleted_at);
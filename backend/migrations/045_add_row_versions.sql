@@ -0,0 +1,30 @@
+-- Row versions for optimistic concurrency. Every update bumps the version, so
+-- a client's If-Match fails once anyone else has written. Workouts and
+-- templates are also bumped by the touch triggers when their exercises or sets
+-- change, because those are part of the same response.
+ALTER TABLE workouts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE workout_exercises ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE workout_sets ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE workout_templates ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE workout_programs ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE user_settings ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+CREATE FUNCTION bump_version() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.version = OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER workouts_version BEFORE UPDATE ON workouts
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+CREATE TRIGGER workout_exercises_version BEFORE UPDATE ON workout_exercises
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+CREATE TRIGGER workout_sets_version BEFORE UPDATE ON workout_sets
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+CREATE TRIGGER workout_templates_version BEFORE UPDATE ON workout_templates
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+CREATE TRIGGER workout_programs_version BEFORE UPDATE ON workout_programs
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+CREATE TRIGGER user_settings_version BEFORE UPDATE ON user_settings
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
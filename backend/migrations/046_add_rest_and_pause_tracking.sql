@@ -0,0 +1,32 @@
+-- Server-side session timing. A set's rest is the time since the previous
+-- completed set of the same workout exercise, less the set's own duration and
+-- any time the workout was paused in between. Pauses are kept as intervals so
+-- both rest and the workout's duration can leave them out.
+ALTER TABLE workout_sets ADD COLUMN rest_seconds_actual INTEGER;
+
+CREATE TABLE workout_pauses (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    workout_id UUID NOT NULL REFERENCES workouts(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_workout_pauses_workout ON workout_pauses(workout_id);
+-- At most one open pause per workout
+CREATE UNIQUE INDEX idx_workout_pauses_open ON workout_pauses(workout_id) WHERE ended_at IS NULL;
+
+-- Backfill rest for sets logged before it was recorded
+UPDATE workout_sets ws
+SET rest_seconds_actual = GREATEST(
+    0,
+    EXTRACT(EPOCH FROM (r.completed_at - r.previous_at))::int - COALESCE(r.duration_seconds, 0)
+)
+FROM (
+    SELECT id, completed_at, duration_seconds,
+           LAG(completed_at) OVER (
+               PARTITION BY workout_exercise_id ORDER BY completed_at, set_number
+           ) AS previous_at
+    FROM workout_sets
+    WHERE is_completed AND completed_at IS NOT NULL
+) r
+WHERE ws.id = r.id AND r.previous_at IS NOT NULL;
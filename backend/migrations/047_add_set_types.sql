@@ -0,0 +1,15 @@
+-- Set types. Drop, rest-pause and cluster sets are logged as a main set with
+-- linked sub-sets (the drops or mini-sets), which share the main set's type.
+-- Sub-sets add to volume but aren't counted as sets of their own, and only
+-- the main set can set a weight or rep record.
+CREATE TYPE set_type AS ENUM ('normal', 'amrap', 'drop', 'rest-pause', 'cluster', 'failure');
+
+ALTER TABLE workout_sets
+    ADD COLUMN set_type set_type NOT NULL DEFAULT 'normal',
+    ADD COLUMN parent_set_id UUID REFERENCES workout_sets(id) ON DELETE CASCADE;
+
+ALTER TABLE template_sets
+    ADD COLUMN set_type set_type NOT NULL DEFAULT 'normal',
+    ADD COLUMN parent_set_id UUID REFERENCES template_sets(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_workout_sets_parent ON workout_sets(parent_set_id) WHERE parent_set_id IS NOT NULL;
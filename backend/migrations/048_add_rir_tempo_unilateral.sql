@@ -0,0 +1,27 @@
+-- Reps in reserve, tempo and unilateral sets. Tempo is prescribed as four
+-- phases, e.g. '3-1-1-0' (eccentric, pause, concentric, pause). On a
+-- unilateral set `actual_reps` is per side, and the reps of each side can be
+-- logged separately.
+ALTER TABLE workout_sets
+    ADD COLUMN target_rir SMALLINT,
+    ADD COLUMN rir SMALLINT,
+    ADD COLUMN tempo VARCHAR(11),
+    ADD COLUMN is_unilateral BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN actual_reps_left INTEGER,
+    ADD COLUMN actual_reps_right INTEGER;
+
+-- Reps that count towards volume: both sides of a unilateral set, so a set
+-- of 10 per leg isn't counted as half the work of 20 bilateral reps
+ALTER TABLE workout_sets ADD COLUMN volume_reps INTEGER GENERATED ALWAYS AS (
+    CASE
+        WHEN NOT is_unilateral THEN actual_reps
+        WHEN actual_reps_left IS NOT NULL OR actual_reps_right IS NOT NULL
+            THEN COALESCE(actual_reps_left, 0) + COALESCE(actual_reps_right, 0)
+        ELSE actual_reps * 2
+    END
+) STORED;
+
+ALTER TABLE template_sets
+    ADD COLUMN target_rir SMALLINT,
+    ADD COLUMN tempo VARCHAR(11),
+    ADD COLUMN is_unilateral BOOLEAN NOT NULL DEFAULT false;
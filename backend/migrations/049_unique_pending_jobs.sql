@@ -0,0 +1,10 @@
+-- At most one pending copy of a job. Enqueueing relies on this index to
+-- drop duplicates, which a NOT EXISTS check can't do when two transactions
+-- enqueue the same job at once.
+DELETE FROM jobs j
+USING jobs keep
+WHERE j.status = 'pending' AND keep.status = 'pending'
+  AND j.kind = keep.kind AND j.payload = keep.payload
+  AND j.id > keep.id;
+
+CREATE UNIQUE INDEX idx_jobs_pending_unique ON jobs(kind, payload) WHERE status = 'pending';
@@ -0,0 +1,65 @@
+//! Rebuilds personal records from existing completed workouts, using the same
+//! rules as live detection. Safe to re-run; each user's records are replaced.
+//!
+//! Run with: cargo run --bin backfill_prs [-- --user <uuid>]
+
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+use fitness_tracker_api::services::PersonalRecordService;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+
+    let args: Vec<String> = std::env::args().collect();
+    let only_user = match args.iter().position(|arg| arg == "--user") {
+        Some(i) => Some(Uuid::parse_str(
+            args.get(i + 1).ok_or("--user requires a user ID")?,
+        )?),
+        None => None,
+    };
+
+    // Support both DATABASE_URL and DATABASE__URL (nested config format)
+    let database_url = std::env::var("DATABASE_URL")
+        .or_else(|_| std::env::var("DATABASE__URL"))
+        .expect("DATABASE_URL or DATABASE__URL must be set");
+
+    println!("Connecting to database...");
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&database_url)
+        .await?;
+
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    let user_ids: Vec<Uuid> = match only_user {
+        Some(id) => vec![id],
+        None => {
+            sqlx::query_scalar("SELECT id FROM users ORDER BY created_at")
+                .fetch_all(&pool)
+                .await?
+        }
+    };
+
+    let mut total = 0;
+    for (i, user_id) in user_ids.iter().enumerate() {
+        let inserted = PersonalRecordService::recompute_for_user(&pool, *user_id).await?;
+        total += inserted;
+        println!(
+            "[{}/{}] {}: {} records",
+            i + 1,
+            user_ids.len(),
+            user_id,
+            inserted
+        );
+    }
+
+    println!(
+        "\n✓ Rebuilt {} personal records for {} users",
+        total,
+        user_ids.len()
+    );
+
+    Ok(())
+}
@@ -196,8 +196,8 @@ async fn seed_demo_user(pool: &sqlx::PgPool) -> Result<(), Box<dyn std::error::E
             .map_err(|e| format!("Failed to hash password: {}", e))?;
 
         sqlx::query(
-            "INSERT INTO users (id, email, password_hash, created_at)
-             VALUES ($1, 'demo@example.com', $2, NOW() - INTERVAL '30 days')"
+            "INSERT INTO users (id, email, password_hash, email_verified_at, created_at)
+             VALUES ($1, 'demo@example.com', $2, NOW() - INTERVAL '30 days', NOW() - INTERVAL '30 days')"
         )
         .bind(user_id)
         .bind(&password_hash)
@@ -799,14 +799,22 @@ async fn seed_admin_user(pool: &sqlx::PgPool) -> Result<(), Box<dyn std::error::
         .map_err(|e| format!("Failed to hash password: {}", e))?;
 
     sqlx::query(
-        "INSERT INTO users (id, email, password_hash, is_admin, created_at, updated_at)
-         VALUES (gen_random_uuid(), 'admin@example.com', $1, true, NOW(), NOW())
-         ON CONFLICT (email) DO UPDATE SET is_admin = true"
+        "INSERT INTO users (id, email, password_hash, email_verified_at, created_at, updated_at)
+         VALUES (gen_random_uuid(), 'admin@example.com', $1, NOW(), NOW(), NOW())
+         ON CONFLICT (email) DO NOTHING"
     )
     .bind(&password_hash)
     .execute(pool)
     .await?;
 
+    sqlx::query(
+        "INSERT INTO user_roles (user_id, role, granted_at)
+         SELECT id, 'admin', NOW() FROM users WHERE email = 'admin@example.com'
+         ON CONFLICT (user_id, role) DO NOTHING"
+    )
+    .execute(pool)
+    .await?;
+
     Ok(())
 }
 
@@ -3,7 +3,7 @@ use std::sync::{OnceLock, RwLock};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::models::UserSettings;
+use crate::models::{Role, UserSettings};
 
 // --- Exercise name cache (never-expiring; exercise templates are static seed data) ---
 
@@ -54,3 +54,40 @@ pub fn invalidate_settings(user_id: Uuid) {
         cache.remove(&user_id);
     }
 }
+
+// --- User roles cache (30s TTL) ---
+//
+// Kept short because a revoked role must stop working on every instance, and
+// invalidation below only reaches the instance that made the change.
+
+const ROLES_TTL: Duration = Duration::from_secs(30);
+
+type RolesMap = HashMap<Uuid, (Vec<Role>, Instant)>;
+
+static ROLES_CACHE: OnceLock<RwLock<RolesMap>> = OnceLock::new();
+
+fn roles_cache() -> &'static RwLock<RolesMap> {
+    ROLES_CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+pub fn get_roles(user_id: Uuid) -> Option<Vec<Role>> {
+    let cache = roles_cache().read().ok()?;
+    let (roles, stored_at) = cache.get(&user_id)?;
+    if stored_at.elapsed() < ROLES_TTL {
+        Some(roles.clone())
+    } else {
+        None
+    }
+}
+
+pub fn set_roles(user_id: Uuid, roles: Vec<Role>) {
+    if let Ok(mut cache) = roles_cache().write() {
+        cache.insert(user_id, (roles, Instant::now()));
+    }
+}
+
+pub fn invalidate_roles(user_id: Uuid) {
+    if let Ok(mut cache) = roles_cache().write() {
+        cache.remove(&user_id);
+    }
+}
@@ -1,3 +1,6 @@
 mod settings;
 
-pub use settings::{CorsSettings, DatabaseSettings, JwtSettings, ServerSettings, Settings};
+pub use settings::{
+    AuthSettings, CorsSettings, DatabaseSettings, JobSettings, JwtSettings, MailSettings,
+    MailTransport, ServerSettings, Settings, SmtpSettings, SmtpTls, UnverifiedAccess,
+};
@@ -1,4 +1,5 @@
 use config::{Config, ConfigError, Environment};
+use ipnet::IpNet;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -7,6 +8,9 @@ pub struct Settings {
     pub jwt: JwtSettings,
     pub server: ServerSettings,
     pub cors: CorsSettings,
+    pub mail: MailSettings,
+    pub auth: AuthSettings,
+    pub jobs: JobSettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -21,7 +25,10 @@ pub struct DatabaseSettings {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct JwtSettings {
+    /// HS256 shared secret. Signs tokens when no key files are configured
     pub secret: String,
+    /// PEM private keys (Ed25519 or RSA); the first signs, all of them verify
+    pub key_files: Vec<String>,
     pub access_token_expiry_hours: i64,
     pub refresh_token_expiry_days: i64,
 }
@@ -30,6 +37,9 @@ pub struct JwtSettings {
 pub struct ServerSettings {
     pub host: String,
     pub port: u16,
+    /// Reverse proxies whose X-Forwarded-For / X-Real-IP headers are believed.
+    /// Requests from anywhere else are identified by their peer address.
+    pub trusted_proxies: Vec<IpNet>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -37,6 +47,82 @@ pub struct CorsSettings {
     pub allowed_origins: Vec<String>,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthSettings {
+    pub unverified_access: UnverifiedAccess,
+    /// Admin routes reject staff who haven't enrolled in two-factor authentication
+    pub require_admin_mfa: bool,
+    /// Consecutive failed logins before an account is temporarily locked
+    pub max_failed_logins: u32,
+    /// First lockout duration; doubles with every further failure
+    pub lockout_base_secs: u64,
+    pub lockout_max_secs: u64,
+    /// Days between a self-service deletion request and the hard purge
+    pub deletion_grace_days: i64,
+    /// How often an open event stream checks that its session or access token
+    /// hasn't been revoked
+    pub stream_recheck_secs: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JobSettings {
+    /// How long the worker sleeps when the queue is empty
+    pub poll_interval_ms: u64,
+    /// Attempts before a failing job is dead-lettered
+    pub max_attempts: i32,
+    /// A running job whose worker hasn't sent a heartbeat for this long is
+    /// assumed lost and handed out again
+    pub lock_timeout_secs: i64,
+}
+
+/// What an account may do before its email address is verified.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnverifiedAccess {
+    /// No restrictions
+    Full,
+    /// Workout logging (and the exercise library it needs) only
+    Workouts,
+    /// Account endpoints only
+    None,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailSettings {
+    pub transport: MailTransport,
+    /// Sender mailbox, e.g. `Fitness Tracker <no-reply@example.com>`
+    pub from: String,
+    /// Base URL of the frontend, used to build links in emails
+    pub app_url: String,
+    pub smtp: SmtpSettings,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    /// Write emails to the log instead of sending them (development)
+    Log,
+    Smtp,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub tls: SmtpTls,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Plaintext, for local catchers like Mailpit or MailHog
+    None,
+    StartTls,
+    Tls,
+}
+
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         let config = Config::builder()
@@ -45,17 +131,38 @@ impl Settings {
             .set_default("database.acquire_timeout_secs", 30)?
             .set_default("database.idle_timeout_secs", 600)?
             .set_default("database.max_lifetime_secs", 1800)?
+            .set_default("jwt.secret", "")?
+            .set_default("jwt.key_files", Vec::<String>::new())?
             .set_default("jwt.access_token_expiry_hours", 24)?
             .set_default("jwt.refresh_token_expiry_days", 7)?
             .set_default("server.host", "127.0.0.1")?
             .set_default("server.port", 3000)?
+            .set_default("server.trusted_proxies", Vec::<String>::new())?
             .set_default("cors.allowed_origins", vec!["http://localhost:4200"])?
+            .set_default("mail.transport", "log")?
+            .set_default("mail.from", "Fitness Tracker <no-reply@localhost>")?
+            .set_default("mail.app_url", "http://localhost:4200")?
+            .set_default("mail.smtp.host", "localhost")?
+            .set_default("mail.smtp.port", 1025)?
+            .set_default("mail.smtp.tls", "none")?
+            .set_default("auth.unverified_access", "full")?
+            .set_default("auth.require_admin_mfa", false)?
+            .set_default("auth.max_failed_logins", 5)?
+            .set_default("auth.lockout_base_secs", 30)?
+            .set_default("auth.lockout_max_secs", 3600)?
+            .set_default("auth.deletion_grace_days", 30)?
+            .set_default("auth.stream_recheck_secs", 30)?
+            .set_default("jobs.poll_interval_ms", 1000)?
+            .set_default("jobs.max_attempts", 5)?
+            .set_default("jobs.lock_timeout_secs", 300)?
             .add_source(
                 Environment::default()
                     .separator("__")
                     .try_parsing(true)
                     .list_separator(",")
-                    .with_list_parse_key("cors.allowed_origins"),
+                    .with_list_parse_key("cors.allowed_origins")
+                    .with_list_parse_key("jwt.key_files")
+                    .with_list_parse_key("server.trusted_proxies"),
             )
             .build()?;
 
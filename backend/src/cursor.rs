@@ -21,3 +21,21 @@ pub fn decode_cursor(s: &str) -> Option<(DateTime<Utc>, Uuid)> {
     let c: WorkoutCursor = serde_json::from_slice(&bytes).ok()?;
     Some((c.started_at, c.id))
 }
+
+#[derive(Serialize, Deserialize)]
+struct SyncToken {
+    since: DateTime<Utc>,
+}
+
+pub fn encode_sync_token(since: DateTime<Utc>) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(serde_json::to_vec(&SyncToken { since }).unwrap())
+}
+
+pub fn decode_sync_token(s: &str) -> Option<DateTime<Utc>> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(s)
+        .ok()?;
+    let t: SyncToken = serde_json::from_slice(&bytes).ok()?;
+    Some(t.since)
+}
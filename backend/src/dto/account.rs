@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccountRequest {
+    #[validate(length(max = 128))]
+    pub password: String,
+    /// TOTP or recovery code, required when two-factor authentication is enabled
+    #[validate(length(max = 32))]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDeletionResponse {
+    /// The account and all its data are purged after this time
+    pub scheduled_deletion_at: DateTime<Utc>,
+}
@@ -3,12 +3,16 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::{ExerciseCategory, Permission, Role};
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminUserResponse {
     pub id: Uuid,
     pub email: String,
     pub is_admin: bool,
+    pub roles: Vec<Role>,
+    pub email_verified_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub workout_count: i64,
     pub last_active: Option<DateTime<Utc>>,
@@ -27,6 +31,12 @@ pub struct AdminUserDetailResponse {
     pub id: Uuid,
     pub email: String,
     pub is_admin: bool,
+    pub roles: Vec<Role>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// Consecutive failed logins since the last successful one
+    pub failed_login_attempts: i32,
+    /// Set while the account is locked out after too many failed logins
+    pub locked_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub workout_count: i64,
     pub last_active: Option<DateTime<Utc>>,
@@ -39,6 +49,44 @@ pub struct SetAdminStatusRequest {
     pub is_admin: bool,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetUserRolesRequest {
+    /// The complete set of roles the user should hold afterwards
+    pub roles: Vec<Role>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleResponse {
+    pub name: Role,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleListResponse {
+    pub roles: Vec<RoleResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminExerciseResponse {
+    pub id: String,
+    pub name: String,
+    pub category: ExerciseCategory,
+    pub description: Option<String>,
+    pub user_id: Uuid,
+    pub owner_email: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminExerciseListResponse {
+    pub exercises: Vec<AdminExerciseResponse>,
+    pub total: i64,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AdminUserListQuery {
@@ -46,6 +94,13 @@ pub struct AdminUserListQuery {
     pub page_size: Option<i64>,
 }
 
+/// Number of personal record recompute jobs queued.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeQueuedResponse {
+    pub queued: i64,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyRegistration {
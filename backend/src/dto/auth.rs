@@ -1,22 +1,33 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use super::MfaChallengeResponse;
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct RegisterRequest {
     #[validate(email(message = "Invalid email address"), length(max = 254))]
     pub email: String,
     #[validate(length(min = 8, max = 128, message = "Password must be between 8 and 128 characters"))]
     pub password: String,
+    /// Human-friendly name for the session, e.g. "Gym kiosk"
+    #[validate(length(max = 100))]
+    pub device_label: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
     #[validate(email(message = "Invalid email address"), length(max = 254))]
     pub email: String,
     #[validate(length(max = 128))]
     pub password: String,
+    /// Human-friendly name for the session, e.g. "Gym kiosk"
+    #[validate(length(max = 100))]
+    pub device_label: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -27,12 +38,23 @@ pub struct AuthResponse {
     pub user: UserResponse,
 }
 
+/// Result of a password login: tokens, or a challenge when 2FA is enabled.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AuthResponse),
+    MfaRequired(MfaChallengeResponse),
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResponse {
     pub id: Uuid,
     pub email: String,
     pub is_admin: bool,
+    pub email_verified: bool,
+    /// When the account will be purged, if the user has asked for deletion
+    pub scheduled_deletion_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -49,6 +71,29 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "Invalid email address"), length(max = 254))]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub token: String,
+    #[validate(length(min = 8, max = 128, message = "Password must be between 8 and 128 characters"))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1, max = 128))]
+    pub token: String,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenResponse {
@@ -56,6 +101,25 @@ pub struct TokenResponse {
     pub refresh_token: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub device_label: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// True for the session the request was made with
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,6 +131,7 @@ mod tests {
         let request = RegisterRequest {
             email: "user@example.com".to_string(),
             password: "secure_password_123".to_string(),
+            device_label: None,
         };
 
         assert!(request.validate().is_ok());
@@ -77,6 +142,7 @@ mod tests {
         let request = RegisterRequest {
             email: "invalid-email".to_string(),
             password: "secure_password".to_string(),
+            device_label: None,
         };
 
         let result = request.validate();
@@ -91,6 +157,7 @@ mod tests {
         let request = RegisterRequest {
             email: "".to_string(),
             password: "secure_password".to_string(),
+            device_label: None,
         };
 
         let result = request.validate();
@@ -102,6 +169,7 @@ mod tests {
         let request = RegisterRequest {
             email: "user@example.com".to_string(),
             password: "short".to_string(), // Only 5 characters
+            device_label: None,
         };
 
         let result = request.validate();
@@ -116,6 +184,7 @@ mod tests {
         let request = RegisterRequest {
             email: "user@example.com".to_string(),
             password: "exactly8".to_string(), // Exactly 8 characters
+            device_label: None,
         };
 
         assert!(request.validate().is_ok());
@@ -126,6 +195,7 @@ mod tests {
         let request = RegisterRequest {
             email: "user@example.com".to_string(),
             password: "7chars!".to_string(), // Only 7 characters
+            device_label: None,
         };
 
         let result = request.validate();
@@ -139,6 +209,7 @@ mod tests {
         let request = LoginRequest {
             email: "user@example.com".to_string(),
             password: "any_password".to_string(),
+            device_label: None,
         };
 
         assert!(request.validate().is_ok());
@@ -149,6 +220,7 @@ mod tests {
         let request = LoginRequest {
             email: "not-an-email".to_string(),
             password: "password".to_string(),
+            device_label: None,
         };
 
         let result = request.validate();
@@ -161,6 +233,7 @@ mod tests {
         let request = LoginRequest {
             email: "user@example.com".to_string(),
             password: "a".to_string(), // Even 1 char is fine for login attempt
+            device_label: None,
         };
 
         assert!(request.validate().is_ok());
@@ -177,6 +250,8 @@ mod tests {
                 id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
                 email: "user@example.com".to_string(),
                 is_admin: false,
+                email_verified: true,
+                scheduled_deletion_at: None,
             },
         };
 
@@ -194,12 +269,15 @@ mod tests {
             id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap(),
             email: "test@example.com".to_string(),
             is_admin: false,
+            email_verified: false,
+            scheduled_deletion_at: None,
         };
 
         let json = serde_json::to_string(&user).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
 
         assert_eq!(parsed["email"], "test@example.com");
+        assert_eq!(parsed["emailVerified"], false);
         assert_eq!(parsed["id"], "550e8400-e29b-41d4-a716-446655440000");
     }
 
@@ -234,6 +312,30 @@ mod tests {
 
         assert_eq!(request.email, "user@example.com");
         assert_eq!(request.password, "mypassword");
+        assert!(request.device_label.is_none());
+    }
+
+    #[test]
+    fn test_login_request_device_label() {
+        let json = r#"{"email": "user@example.com", "password": "pw", "deviceLabel": "Gym kiosk"}"#;
+        let request: LoginRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.device_label.as_deref(), Some("Gym kiosk"));
+        assert!(request.validate().is_ok());
+
+        let request = LoginRequest {
+            device_label: Some("x".repeat(101)),
+            ..request
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_reset_password_request_password_too_short() {
+        let json = r#"{"token": "abc", "newPassword": "short"}"#;
+        let request: ResetPasswordRequest = serde_json::from_str(json).unwrap();
+
+        let errors = request.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("new_password"));
     }
 
     #[test]
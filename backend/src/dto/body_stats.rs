@@ -32,6 +32,8 @@ pub struct BodyMeasurementResponse {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMeasurementRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing measurement
+    pub id: Option<Uuid>,
     pub date: NaiveDate,
     #[validate(range(min = 0.1, max = 999.0))]
     pub weight: Option<f64>,
@@ -123,6 +125,8 @@ pub struct GoalResponse {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateGoalRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing goal
+    pub id: Option<Uuid>,
     #[serde(rename = "type")]
     pub type_: GoalType,
     pub measurement_type: Option<MeasurementType>,
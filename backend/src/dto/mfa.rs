@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpSetupResponse {
+    /// Base32 shared secret, for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpCodeRequest {
+    #[validate(length(min = 6, max = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    /// Shown once; each code can be used a single time instead of a TOTP code
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DisableTotpRequest {
+    #[validate(length(max = 128))]
+    pub password: String,
+    /// TOTP code or recovery code
+    #[validate(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaVerifyRequest {
+    pub mfa_token: String,
+    /// TOTP code or recovery code
+    #[validate(length(min = 6, max = 32))]
+    pub code: String,
+    #[validate(length(max = 100))]
+    pub device_label: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    /// Short-lived token to exchange at `/auth/mfa/verify` together with a code
+    pub mfa_token: String,
+}
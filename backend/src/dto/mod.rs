@@ -1,21 +1,29 @@
+mod access_token;
+mod account;
 mod admin;
 mod auth;
 mod body_stats;
 mod error;
 mod exercise;
+mod mfa;
 mod program;
 mod settings;
 mod statistics;
+mod sync;
 mod template;
 mod workout;
 
+pub use access_token::*;
+pub use account::*;
 pub use admin::*;
 pub use auth::*;
 pub use body_stats::*;
 pub use error::*;
 pub use exercise::*;
+pub use mfa::*;
 pub use program::*;
 pub use settings::*;
 pub use statistics::*;
+pub use sync::*;
 pub use template::*;
 pub use workout::*;
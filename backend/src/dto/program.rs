@@ -9,6 +9,8 @@ use validator::Validate;
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateProgramRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing program
+    pub id: Option<Uuid>,
     #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
     pub name: String,
     #[validate(length(max = 2000))]
@@ -64,6 +66,7 @@ pub struct ProgramResponse {
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub weeks: Vec<ProgramWeekResponse>,
+    pub version: i32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
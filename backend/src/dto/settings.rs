@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::models::{MeasurementUnit, PlateCalculatorSettings, Theme, WeightUnit};
+use crate::models::{
+    MeasurementUnit, OneRepMaxFormula, PlateCalculatorSettings, Theme, UserSettings, WeekStart,
+    WeightUnit,
+};
 
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +20,31 @@ pub struct SettingsResponse {
     pub sound_on_timer_end: bool,
     pub plate_calculator: PlateCalculatorSettings,
     pub compact_mode: bool,
+    pub one_rep_max_formula: OneRepMaxFormula,
+    pub timezone: String,
+    pub week_start: WeekStart,
+    pub version: i32,
+}
+
+impl From<UserSettings> for SettingsResponse {
+    fn from(settings: UserSettings) -> Self {
+        Self {
+            weight_unit: settings.weight_unit,
+            measurement_unit: settings.measurement_unit,
+            theme: settings.theme,
+            default_rest_timer: settings.default_rest_timer,
+            auto_start_rest_timer: settings.auto_start_rest_timer,
+            show_warmup_sets: settings.show_warmup_sets,
+            vibrate_on_timer_end: settings.vibrate_on_timer_end,
+            sound_on_timer_end: settings.sound_on_timer_end,
+            plate_calculator: settings.plate_calculator,
+            compact_mode: settings.compact_mode,
+            one_rep_max_formula: settings.one_rep_max_formula,
+            timezone: settings.timezone,
+            week_start: settings.week_start,
+            version: settings.version,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -33,4 +61,16 @@ pub struct UpdateSettingsRequest {
     pub sound_on_timer_end: Option<bool>,
     pub plate_calculator: Option<PlateCalculatorSettings>,
     pub compact_mode: Option<bool>,
+    pub one_rep_max_formula: Option<OneRepMaxFormula>,
+    /// IANA timezone name, e.g. `Europe/Berlin`
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+    pub week_start: Option<WeekStart>,
+}
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("unknown_timezone"))
 }
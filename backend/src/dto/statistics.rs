@@ -4,7 +4,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::models::{MuscleGroup, RecordType};
+use crate::models::{MuscleGroup, PersonalRecord, RecordType, SetType};
 
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -28,10 +28,67 @@ pub struct PersonalRecordResponse {
     pub exercise_name: String,
     #[serde(rename = "type")]
     pub type_: RecordType,
+    /// Weight, reps, meters, seconds or seconds per km depending on `type`;
+    /// times and paces are better when lower
     pub value: f64,
     pub reps: Option<i32>,
+    pub distance_meters: Option<f64>,
     pub achieved_at: DateTime<Utc>,
     pub workout_id: Uuid,
+    /// The best this record beat; absent for the first record of its kind
+    pub previous_value: Option<f64>,
+    /// `value - previousValue`, negative when a time or pace improved
+    pub delta: Option<f64>,
+    pub is_current: bool,
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
+impl From<PersonalRecord> for PersonalRecordResponse {
+    fn from(pr: PersonalRecord) -> Self {
+        Self {
+            id: pr.id,
+            exercise_template_id: pr.exercise_template_id,
+            exercise_name: pr.exercise_name,
+            type_: pr.type_,
+            value: pr.value,
+            reps: pr.reps,
+            distance_meters: pr.distance_meters,
+            achieved_at: pr.achieved_at,
+            workout_id: pr.workout_id,
+            previous_value: pr.previous_value,
+            delta: pr.previous_value.map(|previous| pr.value - previous),
+            is_current: pr.superseded_at.is_none(),
+            superseded_at: pr.superseded_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(rename_all = "camelCase")]
+pub struct PersonalRecordsQuery {
+    /// `true` for only current records, `false` for only superseded ones;
+    /// omit for both
+    pub current: Option<bool>,
+}
+
+/// How each record line for one exercise progressed, oldest record first.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTimelineResponse {
+    pub exercise_template_id: String,
+    pub exercise_name: String,
+    pub series: Vec<RecordTimelineSeries>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTimelineSeries {
+    #[serde(rename = "type")]
+    pub type_: RecordType,
+    /// Rep count, for rep maxes
+    pub reps: Option<i32>,
+    pub records: Vec<PersonalRecordResponse>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -81,6 +138,26 @@ pub struct ExerciseHistoryEntry {
     pub max_weight: Option<f64>,
     pub total_volume: f64,
     pub estimated_1rm: Option<f64>,
+    /// Average rest before working sets, in seconds
+    pub avg_rest_seconds: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepMaxTableResponse {
+    pub exercise_template_id: String,
+    pub exercise_name: String,
+    /// Ordered by rep count; rep counts never lifted are omitted
+    pub rep_maxes: Vec<RepMaxEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepMaxEntry {
+    pub reps: i32,
+    pub weight: f64,
+    pub achieved_at: DateTime<Utc>,
+    pub workout_id: Uuid,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -90,6 +167,11 @@ pub struct SetHistoryEntry {
     pub reps: Option<i32>,
     pub weight: Option<f64>,
     pub is_warmup: bool,
+    /// Rest taken before the set, in seconds
+    pub rest_seconds: Option<i32>,
+    pub set_type: SetType,
+    /// A drop or mini-set of the set before it
+    pub is_sub_set: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, IntoParams)]
@@ -150,6 +232,8 @@ pub struct ExerciseOverloadSuggestion {
     pub suggested_reps: Option<i32>,
     pub current_weight: f64,
     pub current_reps: i32,
+    /// Best estimated 1RM of the last session, by the user's formula
+    pub estimated_1rm: Option<f64>,
     pub reason: String,
     pub confidence: SuggestionConfidence,
 }
@@ -210,6 +294,10 @@ pub struct ExercisePlateauAlert {
     pub weeks_since_progress: i32,
     pub last_max_weight: f64,
     pub current_max_weight: f64,
+    /// Best estimated 1RMs of the two periods; progress is judged on these
+    /// when both exist, so more reps at the same weight isn't a plateau
+    pub last_estimated_1rm: Option<f64>,
+    pub current_estimated_1rm: Option<f64>,
     pub last_progress_date: Option<NaiveDate>,
     pub suggestion: String,
 }
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::dto::{
+    BodyMeasurementResponse, SettingsResponse, WorkoutResponse, WorkoutTemplateResponse,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SyncQuery {
+    /// Token from the previous sync; omit for a full snapshot
+    pub since: Option<String>,
+}
+
+/// Everything that changed since the token. Sections the access token has no
+/// read scope for are left empty.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResponse {
+    /// Pass as `since` on the next sync
+    pub token: String,
+    /// The response is a full snapshot rather than a delta, because no token
+    /// was given or it was too old. Anything the client holds that isn't in it
+    /// has been deleted.
+    pub full: bool,
+    pub workouts: Vec<WorkoutResponse>,
+    pub templates: Vec<WorkoutTemplateResponse>,
+    pub measurements: Vec<BodyMeasurementResponse>,
+    /// Only present when the settings changed
+    pub settings: Option<SettingsResponse>,
+    pub deleted: SyncDeletions,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDeletions {
+    pub workouts: Vec<Uuid>,
+    pub templates: Vec<Uuid>,
+    pub measurements: Vec<Uuid>,
+}
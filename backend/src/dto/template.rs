@@ -2,7 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::models::SetType;
 
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +18,8 @@ pub struct WorkoutTemplateResponse {
     pub last_used_at: Option<DateTime<Utc>>,
     pub usage_count: i32,
     pub tags: Option<Vec<String>>,
+    /// Bumped by every change to the template, including its exercises
+    pub version: i32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -39,11 +43,19 @@ pub struct TemplateSetResponse {
     pub is_warmup: bool,
     pub target_distance_meters: Option<i32>,
     pub target_duration_seconds: Option<i32>,
+    pub set_type: SetType,
+    /// Set number of the main set this drop or mini-set belongs to
+    pub parent_set_number: Option<i32>,
+    pub target_rir: Option<i16>,
+    pub tempo: Option<String>,
+    pub is_unilateral: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTemplateRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing template
+    pub id: Option<Uuid>,
     #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
     pub name: String,
     #[validate(length(max = 2000))]
@@ -57,6 +69,7 @@ pub struct CreateTemplateRequest {
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_sub_sets"))]
 pub struct CreateTemplateExerciseRequest {
     #[validate(length(min = 1, max = 200, message = "Exercise template ID is required"))]
     pub exercise_template_id: String,
@@ -84,6 +97,28 @@ pub struct CreateTemplateSetRequest {
     pub target_distance_meters: Option<i32>,
     #[validate(range(min = 0, max = 86400))]
     pub target_duration_seconds: Option<i32>,
+    #[serde(default)]
+    pub set_type: SetType,
+    /// Makes the set a drop or mini-set of an earlier drop, rest-pause or
+    /// cluster set, by its 1-based set number; it takes that set's type
+    pub parent_set_number: Option<i32>,
+    #[validate(range(min = 0, max = 10))]
+    pub target_rir: Option<i16>,
+    /// Four phases such as `3-1-1-0`
+    #[validate(custom(function = "crate::dto::validate_tempo"))]
+    pub tempo: Option<String>,
+    /// Target reps are per side
+    #[serde(default)]
+    pub is_unilateral: bool,
+}
+
+fn validate_sub_sets(exercise: &CreateTemplateExerciseRequest) -> Result<(), ValidationError> {
+    let sets = exercise
+        .sets
+        .iter()
+        .map(|s| (s.set_type, s.parent_set_number));
+    SetType::check_sub_sets(sets)
+        .map_err(|message| ValidationError::new("invalid_sub_sets").with_message(message.into()))
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -2,13 +2,15 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::models::{ExerciseCategory, WorkoutStatus};
+use crate::models::{ExerciseCategory, SetType, WorkoutStatus};
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWorkoutRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing workout
+    pub id: Option<Uuid>,
     #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
     pub name: String,
     pub template_id: Option<Uuid>,
@@ -37,11 +39,19 @@ pub struct WorkoutResponse {
     pub total_volume: f64,
     pub total_sets: i32,
     pub total_reps: i32,
+    /// Seconds from start to finish, not counting time paused
     pub duration: Option<i32>,
+    /// When the current pause began, while the workout is paused
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Time paused so far, including the current pause
+    pub paused_seconds: i32,
     pub status: WorkoutStatus,
     pub template_id: Option<Uuid>,
     pub notes: Option<String>,
     pub tags: Vec<String>,
+    /// Bumped by every change to the workout, its exercises or sets. Sent as
+    /// the ETag; pass it back in `If-Match` to make an update conditional.
+    pub version: i32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -54,6 +64,8 @@ pub struct WorkoutExerciseResponse {
     pub notes: Option<String>,
     pub superset_id: Option<Uuid>,
     pub exercise_category: Option<ExerciseCategory>,
+    /// Bumped by changes to the exercise itself; its sets have their own
+    pub version: i32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -74,11 +86,32 @@ pub struct WorkoutSetResponse {
     pub calories: Option<i32>,
     pub target_distance_meters: Option<i32>,
     pub target_duration_seconds: Option<i32>,
+    /// Rest taken before this set, measured from the previous completed set
+    /// of the exercise
+    pub rest_seconds_actual: Option<i32>,
+    pub set_type: SetType,
+    /// The main set this drop or mini-set belongs to. Sub-sets count towards
+    /// volume but not as sets of their own.
+    pub parent_set_id: Option<Uuid>,
+    /// Prescribed reps in reserve
+    pub target_rir: Option<i16>,
+    /// Reps in reserve, as rated after the set
+    pub rir: Option<i16>,
+    /// Eccentric, pause, concentric and pause seconds, e.g. `3-1-1-0`
+    pub tempo: Option<String>,
+    /// Each side is worked separately. `actualReps` is then per side, and
+    /// volume counts both.
+    pub is_unilateral: bool,
+    pub actual_reps_left: Option<i32>,
+    pub actual_reps_right: Option<i32>,
+    pub version: i32,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWorkoutExerciseRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing exercise
+    pub id: Option<Uuid>,
     #[validate(length(min = 1, max = 200, message = "Exercise template ID is required"))]
     pub exercise_template_id: String,
     #[validate(length(min = 1, max = 200))]
@@ -98,6 +131,8 @@ pub struct UpdateWorkoutExerciseRequest {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSetRequest {
+    /// Client-generated ID; a retry with the same ID returns the existing set
+    pub id: Option<Uuid>,
     #[validate(range(min = 0, max = 9999))]
     pub target_reps: Option<i32>,
     #[validate(range(min = 0.0, max = 9999.0))]
@@ -111,6 +146,17 @@ pub struct CreateSetRequest {
     pub target_distance_meters: Option<i32>,
     #[validate(range(min = 0, max = 86400))]
     pub target_duration_seconds: Option<i32>,
+    #[serde(default)]
+    pub set_type: SetType,
+    /// Logs the set as a drop or mini-set of an earlier drop, rest-pause or
+    /// cluster set of the exercise; it takes that set's type
+    pub parent_set_id: Option<Uuid>,
+    #[validate(range(min = 0, max = 10))]
+    pub target_rir: Option<i16>,
+    #[validate(custom(function = "validate_tempo"))]
+    pub tempo: Option<String>,
+    #[serde(default)]
+    pub is_unilateral: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -131,6 +177,38 @@ pub struct UpdateSetRequest {
     pub distance_meters: Option<f64>,
     pub duration_seconds: Option<i32>,
     pub calories: Option<i32>,
+    /// Changing a main set's type changes its sub-sets' too
+    pub set_type: Option<SetType>,
+    #[validate(range(min = 0, max = 10))]
+    pub target_rir: Option<i16>,
+    #[validate(range(min = 0, max = 10))]
+    pub rir: Option<i16>,
+    #[validate(custom(function = "validate_tempo"))]
+    pub tempo: Option<String>,
+    pub is_unilateral: Option<bool>,
+    /// Only for unilateral sets. Without `actualReps`, the weaker side's
+    /// reps are used for it.
+    #[validate(range(min = 0, max = 9999))]
+    pub actual_reps_left: Option<i32>,
+    #[validate(range(min = 0, max = 9999))]
+    pub actual_reps_right: Option<i32>,
+}
+
+/// Checks a tempo of four phases separated by dashes, each a number of
+/// seconds or `X` for explosive, e.g. `3-1-X-0`
+pub fn validate_tempo(tempo: &str) -> Result<(), ValidationError> {
+    let is_phase = |phase: &str| {
+        phase.eq_ignore_ascii_case("x")
+            || (matches!(phase.len(), 1..=2) && phase.bytes().all(|b| b.is_ascii_digit()))
+    };
+    let phases: Vec<&str> = tempo.split('-').collect();
+
+    if phases.len() == 4 && phases.into_iter().all(is_phase) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_tempo")
+            .with_message("Tempo must be four phases like 3-1-1-0".into()))
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -154,6 +232,8 @@ pub struct WorkoutSummaryResponse {
     pub status: WorkoutStatus,
     pub exercise_count: i32,
     pub tags: Vec<String>,
+    /// Average rest before working sets, in seconds
+    pub avg_rest_seconds: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Validate, IntoParams)]
@@ -191,3 +271,143 @@ pub struct ExerciseOrderUpdate {
 pub struct ReorderExercisesRequest {
     pub exercises: Vec<ExerciseOrderUpdate>,
 }
+
+/// A finished (or in-progress) workout logged elsewhere, shaped like
+/// `WorkoutResponse`. IDs, set numbers and totals in the document are ignored:
+/// rows get new IDs, sets are numbered in order and totals are recomputed.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportWorkoutRequest {
+    /// Client-generated ID; importing it again returns the existing workout
+    pub id: Option<Uuid>,
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Defaults to completed
+    pub status: Option<WorkoutStatus>,
+    /// Seconds; derived from the timestamps when omitted
+    #[validate(range(min = 0, max = 604800))]
+    pub duration: Option<i32>,
+    pub template_id: Option<Uuid>,
+    #[validate(length(max = 2000))]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[validate(nested)]
+    pub exercises: Vec<ImportWorkoutExercise>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_sub_sets"))]
+pub struct ImportWorkoutExercise {
+    #[validate(length(min = 1, max = 200, message = "Exercise template ID is required"))]
+    pub exercise_template_id: String,
+    #[validate(length(min = 1, max = 200))]
+    pub exercise_name: Option<String>,
+    #[validate(length(max = 2000))]
+    pub notes: Option<String>,
+    /// Exercises sharing a value are grouped into one new superset
+    pub superset_id: Option<Uuid>,
+    #[validate(nested)]
+    pub sets: Vec<ImportWorkoutSet>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_sides"))]
+pub struct ImportWorkoutSet {
+    #[validate(range(min = 0, max = 9999))]
+    pub target_reps: Option<i32>,
+    #[validate(range(min = 0, max = 9999))]
+    pub actual_reps: Option<i32>,
+    #[validate(range(min = 0.0, max = 9999.0))]
+    pub target_weight: Option<f64>,
+    #[validate(range(min = 0.0, max = 9999.0))]
+    pub actual_weight: Option<f64>,
+    #[serde(default)]
+    pub is_warmup: bool,
+    #[serde(default)]
+    pub is_completed: bool,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[validate(range(min = 1, max = 10))]
+    pub rpe: Option<i16>,
+    #[validate(range(min = 0.0))]
+    pub distance_meters: Option<f64>,
+    #[validate(range(min = 0))]
+    pub duration_seconds: Option<i32>,
+    #[validate(range(min = 0))]
+    pub calories: Option<i32>,
+    #[validate(range(min = 0, max = 100000))]
+    pub target_distance_meters: Option<i32>,
+    #[validate(range(min = 0, max = 86400))]
+    pub target_duration_seconds: Option<i32>,
+    #[serde(default)]
+    pub set_type: SetType,
+    /// Makes the set a drop or mini-set of an earlier drop, rest-pause or
+    /// cluster set, by its 1-based position; it takes that set's type
+    pub parent_set_number: Option<i32>,
+    #[validate(range(min = 0, max = 10))]
+    pub target_rir: Option<i16>,
+    #[validate(range(min = 0, max = 10))]
+    pub rir: Option<i16>,
+    #[validate(custom(function = "validate_tempo"))]
+    pub tempo: Option<String>,
+    #[serde(default)]
+    pub is_unilateral: bool,
+    #[validate(range(min = 0, max = 9999))]
+    pub actual_reps_left: Option<i32>,
+    #[validate(range(min = 0, max = 9999))]
+    pub actual_reps_right: Option<i32>,
+}
+
+fn validate_sides(set: &ImportWorkoutSet) -> Result<(), ValidationError> {
+    let has_sides = set.actual_reps_left.is_some() || set.actual_reps_right.is_some();
+    if has_sides && !set.is_unilateral {
+        return Err(ValidationError::new("sides_on_bilateral_set")
+            .with_message("Left and right reps are only logged on unilateral sets".into()));
+    }
+    Ok(())
+}
+
+fn validate_sub_sets(exercise: &ImportWorkoutExercise) -> Result<(), ValidationError> {
+    let sets = exercise
+        .sets
+        .iter()
+        .map(|s| (s.set_type, s.parent_set_number));
+    SetType::check_sub_sets(sets)
+        .map_err(|message| ValidationError::new("invalid_sub_sets").with_message(message.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo_has_four_phases() {
+        assert!(validate_tempo("3-1-1-0").is_ok());
+        assert!(validate_tempo("4-0-X-1").is_ok());
+        assert!(validate_tempo("10-2-1-0").is_ok());
+
+        assert!(validate_tempo("3-1-1").is_err());
+        assert!(validate_tempo("3-1-1-0-0").is_err());
+        assert!(validate_tempo("3--1-0").is_err());
+        assert!(validate_tempo("3-1-fast-0").is_err());
+        assert!(validate_tempo("100-1-1-0").is_err());
+    }
+
+    #[test]
+    fn test_update_set_checks_rir_and_tempo() {
+        let update = |body: serde_json::Value| {
+            serde_json::from_value::<UpdateSetRequest>(body)
+                .unwrap()
+                .validate()
+        };
+
+        assert!(update(serde_json::json!({ "rir": 2, "tempo": "3-1-1-0" })).is_ok());
+        assert!(update(serde_json::json!({ "rir": 11 })).is_err());
+        assert!(update(serde_json::json!({ "targetRir": -1 })).is_err());
+        assert!(update(serde_json::json!({ "tempo": "slow" })).is_err());
+    }
+}
@@ -3,6 +3,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde_json::json;
 use thiserror::Error;
 
@@ -17,6 +18,18 @@ pub enum AppError {
     #[error("Access denied")]
     Forbidden,
 
+    #[error("Email address not verified")]
+    EmailNotVerified,
+
+    #[error("Two-factor authentication required")]
+    MfaRequired,
+
+    #[error("Token is missing the '{0}' scope")]
+    InsufficientScope(String),
+
+    #[error("Too many failed login attempts, try again later")]
+    AccountLocked(DateTime<Utc>),
+
     #[error("{0}")]
     NotFound(String),
 
@@ -29,6 +42,9 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Resource has changed since it was read")]
+    PreconditionFailed,
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -45,10 +61,21 @@ impl IntoResponse for AppError {
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized", self.to_string()),
             AppError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "Unauthorized", self.to_string()),
             AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden", self.to_string()),
+            AppError::EmailNotVerified => (StatusCode::FORBIDDEN, "Forbidden", self.to_string()),
+            AppError::MfaRequired => (StatusCode::FORBIDDEN, "Forbidden", self.to_string()),
+            AppError::InsufficientScope(_) => (StatusCode::FORBIDDEN, "Forbidden", self.to_string()),
+            AppError::AccountLocked(_) => (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests", self.to_string()),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "Not Found", msg.clone()),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "Bad Request", msg.clone()),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, "Conflict", msg.clone()),
             AppError::Validation(msg) => (StatusCode::UNPROCESSABLE_ENTITY, "Unprocessable Entity", msg.clone()),
+            AppError::PreconditionFailed => (StatusCode::PRECONDITION_FAILED, "Precondition Failed", self.to_string()),
+            // Most often a client-generated ID that is already taken
+            AppError::Database(e)
+                if e.as_database_error().is_some_and(|d| d.is_unique_violation()) =>
+            {
+                (StatusCode::CONFLICT, "Conflict", "Resource already exists".to_string())
+            }
             AppError::Database(e) => {
                 tracing::error!("Database error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error", "Database error".to_string())
@@ -70,12 +97,21 @@ impl IntoResponse for AppError {
             "detail": detail,
         }));
 
-        (
+        let mut response = (
             status,
             [(header::CONTENT_TYPE, "application/problem+json")],
             body,
         )
-            .into_response()
+            .into_response();
+
+        if let AppError::AccountLocked(until) = &self {
+            let secs = (*until - Utc::now()).num_seconds().max(1);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, secs.into());
+        }
+
+        response
     }
 }
 
@@ -157,6 +193,44 @@ mod tests {
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
 
+    #[tokio::test]
+    async fn test_email_not_verified_status_code() {
+        let error = AppError::EmailNotVerified;
+        assert_eq!(error.to_string(), "Email address not verified");
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_mfa_required_status_code() {
+        let error = AppError::MfaRequired;
+        assert_eq!(error.to_string(), "Two-factor authentication required");
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_account_locked_sets_retry_after() {
+        let error = AppError::AccountLocked(Utc::now() + chrono::Duration::seconds(90));
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let retry_after: i64 = response.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((88..=90).contains(&retry_after));
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_scope_status_code() {
+        let error = AppError::InsufficientScope("workouts:write".to_string());
+        assert_eq!(error.to_string(), "Token is missing the 'workouts:write' scope");
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
     #[tokio::test]
     async fn test_not_found_status_code() {
         let error = AppError::NotFound("Resource not found".to_string());
@@ -185,6 +259,13 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[tokio::test]
+    async fn test_precondition_failed_status_code() {
+        let error = AppError::PreconditionFailed;
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
     #[tokio::test]
     async fn test_internal_status_code() {
         let error = AppError::Internal("Error".to_string());
@@ -1,6 +1,8 @@
 use axum::http::{header, HeaderMap};
 use sha2::{Digest, Sha256};
 
+use crate::error::AppError;
+
 pub fn compute_etag(bytes: &[u8]) -> String {
     format!("\"{}\"", hex::encode(Sha256::digest(bytes)))
 }
@@ -9,5 +11,74 @@ pub fn check_none_match(headers: &HeaderMap, etag: &str) -> bool {
     headers
         .get(header::IF_NONE_MATCH)
         .and_then(|v| v.to_str().ok())
-        .map_or(false, |v| v == etag)
+        .is_some_and(|v| v == etag)
+}
+
+/// ETag of a versioned row
+pub fn version_etag(version: i32) -> String {
+    format!("\"{version}\"")
+}
+
+/// The version a write is conditional on, from an `If-Match` header holding
+/// a `version_etag`. `None` when the header is absent or `*`. Any other
+/// value can never match, so it fails the precondition outright.
+pub fn if_match_version(headers: &HeaderMap) -> Result<Option<i32>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().map_err(|_| AppError::PreconditionFailed)?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .and_then(|v| v.parse().ok())
+        .map(Some)
+        .ok_or(AppError::PreconditionFailed)
+}
+
+/// Fails with 412 if the row is no longer at the version the client expects
+pub fn check_version(expected: Option<i32>, current: i32) -> Result<(), AppError> {
+    match expected {
+        Some(expected) if expected != current => Err(AppError::PreconditionFailed),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn if_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_if_match_reads_version_etag() {
+        assert_eq!(if_match_version(&if_match(&version_etag(7))).unwrap(), Some(7));
+        assert_eq!(if_match_version(&if_match("*")).unwrap(), None);
+        assert_eq!(if_match_version(&HeaderMap::new()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_if_match_rejects_foreign_etags() {
+        for value in ["7", "W/\"7\"", "\"abc\"", "\"7\", \"8\""] {
+            assert!(matches!(
+                if_match_version(&if_match(value)),
+                Err(AppError::PreconditionFailed)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert!(check_version(None, 3).is_ok());
+        assert!(check_version(Some(3), 3).is_ok());
+        assert!(matches!(check_version(Some(2), 3), Err(AppError::PreconditionFailed)));
+    }
 }
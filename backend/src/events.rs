@@ -0,0 +1,232 @@
+//! Live change events for a user's open clients.
+//!
+//! Events are published through Postgres `NOTIFY`, so they reach clients
+//! connected to any instance, whichever instance made the change or ran the
+//! job. Each instance holds one `LISTEN` connection and fans what it hears out
+//! to its own streams. Payloads carry IDs only: clients refetch what changed,
+//! and a notification must stay under Postgres's 8000-byte limit.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tokio::sync::{OnceCell, broadcast};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+const CHANNEL: &str = "live_events";
+
+/// Events an instance holds for streams that have fallen behind; a stream that
+/// falls further back is told to resync.
+const BUFFER: usize = 1024;
+
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LiveEvent {
+    Workout {
+        action: Action,
+        workout_id: Uuid,
+    },
+    Exercise {
+        action: Action,
+        workout_id: Uuid,
+        exercise_id: Uuid,
+    },
+    Set {
+        action: Action,
+        workout_id: Uuid,
+        exercise_id: Uuid,
+        set_id: Uuid,
+    },
+    /// New personal records found in a completed workout. Only their number
+    /// is sent, as a workout can set more than would fit in a notification.
+    PersonalRecords {
+        workout_id: Uuid,
+        record_count: usize,
+    },
+}
+
+impl LiveEvent {
+    /// Name sent as the SSE `event:` field, e.g. `set.updated`
+    pub fn name(&self) -> &'static str {
+        match self {
+            LiveEvent::Workout { action, .. } => match action {
+                Action::Created => "workout.created",
+                Action::Updated => "workout.updated",
+                Action::Deleted => "workout.deleted",
+            },
+            LiveEvent::Exercise { action, .. } => match action {
+                Action::Created => "exercise.created",
+                Action::Updated => "exercise.updated",
+                Action::Deleted => "exercise.deleted",
+            },
+            LiveEvent::Set { action, .. } => match action {
+                Action::Created => "set.created",
+                Action::Updated => "set.updated",
+                Action::Deleted => "set.deleted",
+            },
+            LiveEvent::PersonalRecords { .. } => "personal-records.detected",
+        }
+    }
+}
+
+/// An event with the user whose clients should receive it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEvent {
+    pub user_id: Uuid,
+    #[serde(flatten)]
+    pub event: LiveEvent,
+}
+
+/// Sends an event to every client of the user. Called after the change is
+/// committed; a failure is logged rather than failing a change that was made.
+pub async fn publish(pool: &PgPool, user_id: Uuid, event: LiveEvent) {
+    let payload = match serde_json::to_string(&UserEvent { user_id, event }) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::error!("Failed to serialize live event: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANNEL)
+        .bind(payload)
+        .execute(pool)
+        .await
+    {
+        tracing::warn!("Failed to publish live event: {}", e);
+    }
+}
+
+/// This instance's side of the event channel. The `LISTEN` connection is
+/// opened by the first subscriber, so instances nobody streams from hold none.
+#[derive(Clone)]
+pub struct EventBus {
+    pool: PgPool,
+    sender: Arc<OnceCell<broadcast::Sender<UserEvent>>>,
+}
+
+impl EventBus {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            sender: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Receives every user's events from the moment this returns; callers
+    /// filter for their own user.
+    pub async fn subscribe(&self) -> Result<broadcast::Receiver<UserEvent>, AppError> {
+        let sender = self
+            .sender
+            .get_or_try_init(|| async {
+                let mut listener = PgListener::connect_with(&self.pool).await?;
+                listener.listen(CHANNEL).await?;
+
+                let (sender, _) = broadcast::channel(BUFFER);
+                tokio::spawn(forward(listener, sender.clone()));
+                Ok::<_, AppError>(sender)
+            })
+            .await?;
+
+        Ok(sender.subscribe())
+    }
+}
+
+async fn forward(mut listener: PgListener, sender: broadcast::Sender<UserEvent>) {
+    loop {
+        // `recv` reconnects and listens again by itself; an error means that
+        // failed, and the next call tries again
+        let notification = match listener.recv().await {
+            Ok(notification) => notification,
+            Err(e) => {
+                tracing::warn!("Live event listener disconnected: {}", e);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<UserEvent>(notification.payload()) {
+            // No receivers just means no one is streaming right now
+            Ok(event) => {
+                let _ = sender.send(event);
+            }
+            Err(e) => tracing::warn!("Ignoring malformed live event: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_event_round_trips_as_flat_json() {
+        let user_id = Uuid::new_v4();
+        let workout_id = Uuid::new_v4();
+        let exercise_id = Uuid::new_v4();
+        let set_id = Uuid::new_v4();
+        let event = UserEvent {
+            user_id,
+            event: LiveEvent::Set {
+                action: Action::Updated,
+                workout_id,
+                exercise_id,
+                set_id,
+            },
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "userId": user_id,
+                "type": "set",
+                "action": "updated",
+                "workoutId": workout_id,
+                "exerciseId": exercise_id,
+                "setId": set_id,
+            })
+        );
+        assert_eq!(serde_json::from_value::<UserEvent>(json).unwrap(), event);
+    }
+
+    #[test]
+    fn test_event_names() {
+        let workout_id = Uuid::new_v4();
+        let deleted = LiveEvent::Workout {
+            action: Action::Deleted,
+            workout_id,
+        };
+        let records = LiveEvent::PersonalRecords {
+            workout_id,
+            record_count: 0,
+        };
+
+        assert_eq!(deleted.name(), "workout.deleted");
+        assert_eq!(records.name(), "personal-records.detected");
+        assert_eq!(
+            serde_json::to_value(&records).unwrap()["type"],
+            "personalRecords"
+        );
+    }
+}
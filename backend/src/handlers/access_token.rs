@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::dto::{
+    AccessTokenListResponse, CreateAccessTokenRequest, CreatedAccessTokenResponse, ErrorResponse,
+};
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::services::AccessTokenService;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/tokens",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Active personal access tokens", body = AccessTokenListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_access_tokens(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<AccessTokenListResponse>, AppError> {
+    let response = AccessTokenService::list(&pool, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/tokens",
+    tag = "Auth",
+    request_body = CreateAccessTokenRequest,
+    responses(
+        (status = 200, description = "Token created; the full token is only shown once", body = CreatedAccessTokenResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_access_token(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<CreateAccessTokenRequest>,
+) -> Result<Json<CreatedAccessTokenResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response = AccessTokenService::create(&pool, auth_user.user_id, &req).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/tokens/{id}",
+    tag = "Auth",
+    params(
+        ("id" = Uuid, Path, description = "Access token ID")
+    ),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Token not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_access_token(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    AccessTokenService::revoke(&pool, auth_user.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::config::Settings;
+use crate::dto::{AccountDeletionResponse, DeleteAccountRequest, ErrorResponse};
+use crate::error::AppError;
+use crate::mail::Mailer;
+use crate::middleware::AuthUser;
+use crate::services::AccountService;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me/export",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "All of the user's data as a JSON document", body = Object, content_type = "application/json"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_account(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let document = AccountService::export(&pool, auth_user.user_id).await?;
+    let disposition = format!(
+        "attachment; filename=\"fitness-tracker-export-{}.json\"",
+        Utc::now().format("%Y-%m-%d")
+    );
+
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(document)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/me",
+    tag = "Auth",
+    request_body = DeleteAccountRequest,
+    responses(
+        (status = 200, description = "Account scheduled for deletion and signed out everywhere", body = AccountDeletionResponse),
+        (status = 400, description = "Wrong password or 2FA code", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Deletion already scheduled", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_account(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<DeleteAccountRequest>,
+) -> Result<Json<AccountDeletionResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response = AccountService::request_deletion(
+        &pool,
+        &settings,
+        mailer,
+        auth_user.user_id,
+        &req.password,
+        req.code.as_deref(),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/me/cancel-deletion",
+    tag = "Auth",
+    responses(
+        (status = 204, description = "Scheduled deletion cancelled"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "No deletion scheduled", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cancel_account_deletion(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<StatusCode, AppError> {
+    AccountService::cancel_deletion(&pool, auth_user.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
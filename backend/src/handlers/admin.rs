@@ -1,20 +1,23 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Extension, Json,
+    Json,
 };
 use serde::Deserialize;
 use sqlx::PgPool;
 use utoipa::IntoParams;
 use uuid::Uuid;
 
+use crate::cache;
 use crate::dto::{
-    AdminMetricsResponse, AdminUserDetailResponse, AdminUserListResponse, AdminUserResponse,
-    SetAdminStatusRequest,
+    AdminExerciseListResponse, AdminMetricsResponse, AdminUserDetailResponse,
+    AdminUserListResponse, AdminUserResponse, RecomputeQueuedResponse, RoleListResponse,
+    RoleResponse, SetAdminStatusRequest, SetUserRolesRequest,
 };
 use crate::error::AppError;
-use crate::middleware::AuthUser;
-use crate::repositories::AdminRepository;
+use crate::middleware::{perm, Authorized};
+use crate::models::{Job, Role};
+use crate::repositories::{AdminRepository, JobRepository, RoleRepository, UserRepository};
 
 #[derive(Debug, Deserialize, IntoParams)]
 #[into_params(rename_all = "camelCase")]
@@ -24,6 +27,14 @@ pub struct AdminUsersQuery {
     pub page_size: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(rename_all = "camelCase")]
+pub struct AdminExercisesQuery {
+    pub page: Option<i64>,
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<i64>,
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/admin/users",
@@ -32,13 +43,13 @@ pub struct AdminUsersQuery {
     responses(
         (status = 200, description = "List of users", body = AdminUserListResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - admin only"),
+        (status = 403, description = "Forbidden - missing permission"),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn list_admin_users(
     State(pool): State<PgPool>,
-    Extension(_auth_user): Extension<AuthUser>,
+    _auth: Authorized<perm::UsersRead>,
     Query(query): Query<AdminUsersQuery>,
 ) -> Result<Json<AdminUserListResponse>, AppError> {
     let page = query.page.unwrap_or(1).max(1);
@@ -59,14 +70,14 @@ pub async fn list_admin_users(
     responses(
         (status = 200, description = "User detail", body = AdminUserDetailResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - admin only"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found"),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_admin_user(
     State(pool): State<PgPool>,
-    Extension(_auth_user): Extension<AuthUser>,
+    _auth: Authorized<perm::UsersRead>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<AdminUserDetailResponse>, AppError> {
     let user = AdminRepository::get_user(&pool, id)
@@ -86,17 +97,17 @@ pub async fn get_admin_user(
     responses(
         (status = 204, description = "User deleted"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - admin only"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found"),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn delete_admin_user(
     State(pool): State<PgPool>,
-    Extension(auth_user): Extension<AuthUser>,
+    auth: Authorized<perm::UsersDelete>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
-    if id == auth_user.user_id {
+    if id == auth.user.user_id {
         return Err(AppError::BadRequest("Cannot delete your own account".to_string()));
     }
 
@@ -117,26 +128,200 @@ pub async fn delete_admin_user(
         (status = 200, description = "Updated user", body = AdminUserResponse),
         (status = 400, description = "Cannot demote yourself"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - admin only"),
+        (status = 403, description = "Forbidden - missing permission"),
         (status = 404, description = "User not found"),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn set_admin_status(
     State(pool): State<PgPool>,
-    Extension(auth_user): Extension<AuthUser>,
+    auth: Authorized<perm::RolesManage>,
     Path(id): Path<Uuid>,
     Json(body): Json<SetAdminStatusRequest>,
 ) -> Result<Json<AdminUserResponse>, AppError> {
-    if id == auth_user.user_id && !body.is_admin {
+    if id == auth.user.user_id && !body.is_admin {
         return Err(AppError::BadRequest("Cannot revoke your own admin status".to_string()));
     }
 
-    let user = AdminRepository::set_admin_status(&pool, id, body.is_admin).await?;
+    if AdminRepository::find_user(&pool, id).await?.is_none() {
+        return Err(AppError::NotFound(format!("User {} not found", id)));
+    }
+
+    if body.is_admin {
+        RoleRepository::grant(&pool, id, Role::Admin, auth.user.user_id).await?;
+    } else {
+        RoleRepository::revoke(&pool, id, Role::Admin).await?;
+    }
+    cache::invalidate_roles(id);
+
+    let user = AdminRepository::find_user(&pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
 
     Ok(Json(user))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{id}/roles",
+    tag = "Admin",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = SetUserRolesRequest,
+    responses(
+        (status = 200, description = "Updated user", body = AdminUserResponse),
+        (status = 400, description = "Cannot remove your own admin role"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - requires roles:manage"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_user_roles(
+    State(pool): State<PgPool>,
+    auth: Authorized<perm::RolesManage>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SetUserRolesRequest>,
+) -> Result<Json<AdminUserResponse>, AppError> {
+    if id == auth.user.user_id && !body.roles.contains(&Role::Admin) {
+        return Err(AppError::BadRequest("Cannot remove your own admin role".to_string()));
+    }
+
+    let mut roles = body.roles;
+    roles.sort_by_key(|r| r.as_str());
+    roles.dedup();
+
+    if !RoleRepository::set_for_user(&pool, id, &roles, auth.user.user_id).await? {
+        return Err(AppError::NotFound(format!("User {} not found", id)));
+    }
+    cache::invalidate_roles(id);
+
+    let user = AdminRepository::find_user(&pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+
+    Ok(Json(user))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/roles",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "Available roles and the permissions each grants", body = RoleListResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - requires roles:manage"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_roles(
+    _auth: Authorized<perm::RolesManage>,
+) -> Result<Json<RoleListResponse>, AppError> {
+    let roles = Role::ALL
+        .iter()
+        .map(|role| RoleResponse {
+            name: *role,
+            permissions: role.permissions().to_vec(),
+        })
+        .collect();
+
+    Ok(Json(RoleListResponse { roles }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/unlock",
+    tag = "Admin",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "Failed login count cleared and lockout lifted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn unlock_admin_user(
+    State(pool): State<PgPool>,
+    _auth: Authorized<perm::UsersManage>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    if !UserRepository::clear_failed_logins(&pool, id).await? {
+        return Err(AppError::NotFound(format!("User {} not found", id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/personal-records/recompute",
+    tag = "Admin",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 202, description = "Recompute of the user's personal records queued", body = RecomputeQueuedResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+        (status = 404, description = "User not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn recompute_user_personal_records(
+    State(pool): State<PgPool>,
+    _auth: Authorized<perm::UsersManage>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<RecomputeQueuedResponse>), AppError> {
+    if UserRepository::find_by_id(&pool, id).await?.is_none() {
+        return Err(AppError::NotFound(format!("User {} not found", id)));
+    }
+
+    let queued = queue_record_recompute(&pool, &[id]).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(RecomputeQueuedResponse { queued })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/personal-records/recompute",
+    tag = "Admin",
+    responses(
+        (status = 202, description = "Recompute of every user's personal records queued", body = RecomputeQueuedResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - missing permission"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn recompute_all_personal_records(
+    State(pool): State<PgPool>,
+    _auth: Authorized<perm::UsersManage>,
+) -> Result<(StatusCode, Json<RecomputeQueuedResponse>), AppError> {
+    let user_ids = UserRepository::list_ids(&pool).await?;
+    let queued = queue_record_recompute(&pool, &user_ids).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(RecomputeQueuedResponse { queued })))
+}
+
+/// One job per user; users with a recompute already pending are skipped.
+async fn queue_record_recompute(pool: &PgPool, user_ids: &[Uuid]) -> Result<i64, AppError> {
+    let mut queued = 0;
+    for user_id in user_ids {
+        let job = Job::RecomputePersonalRecords {
+            user_id: *user_id,
+            exercise_template_id: None,
+        };
+        if JobRepository::enqueue(pool, &job).await? {
+            queued += 1;
+        }
+    }
+
+    Ok(queued)
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/admin/metrics",
@@ -144,14 +329,67 @@ pub async fn set_admin_status(
     responses(
         (status = 200, description = "Platform metrics", body = AdminMetricsResponse),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden - admin only"),
+        (status = 403, description = "Forbidden - missing permission"),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_admin_metrics(
     State(pool): State<PgPool>,
-    Extension(_auth_user): Extension<AuthUser>,
+    _auth: Authorized<perm::MetricsRead>,
 ) -> Result<Json<AdminMetricsResponse>, AppError> {
     let metrics = AdminRepository::get_metrics(&pool).await?;
     Ok(Json(metrics))
 }
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/exercises",
+    tag = "Admin",
+    params(AdminExercisesQuery),
+    responses(
+        (status = 200, description = "Custom exercises created by users", body = AdminExerciseListResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - requires exercises:moderate"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_admin_exercises(
+    State(pool): State<PgPool>,
+    _auth: Authorized<perm::ExercisesModerate>,
+    Query(query): Query<AdminExercisesQuery>,
+) -> Result<Json<AdminExerciseListResponse>, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let (exercises, total) =
+        AdminRepository::list_custom_exercises(&pool, page, page_size).await?;
+
+    Ok(Json(AdminExerciseListResponse { exercises, total }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/exercises/{id}",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "Custom exercise ID")
+    ),
+    responses(
+        (status = 204, description = "Custom exercise deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - requires exercises:moderate"),
+        (status = 404, description = "Custom exercise not found"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_admin_exercise(
+    State(pool): State<PgPool>,
+    _auth: Authorized<perm::ExercisesModerate>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !AdminRepository::delete_custom_exercise(&pool, &id).await? {
+        return Err(AppError::NotFound(format!("Exercise {} not found", id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
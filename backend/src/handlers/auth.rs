@@ -1,14 +1,23 @@
-use axum::{extract::State, http::StatusCode, Extension, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
 use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::config::Settings;
 use crate::dto::{
-    AuthResponse, ChangePasswordRequest, ErrorResponse, LoginRequest, RefreshRequest,
-    RegisterRequest, TokenResponse, UserResponse,
+    AuthResponse, ChangePasswordRequest, ErrorResponse, ForgotPasswordRequest, LoginRequest,
+    LoginResponse, RefreshRequest, RegisterRequest, ResetPasswordRequest, SessionListResponse, TokenResponse,
+    UserResponse, VerifyEmailRequest,
 };
 use crate::error::AppError;
-use crate::middleware::AuthUser;
+use crate::jwt::JwtKeys;
+use crate::mail::Mailer;
+use crate::middleware::{AuthUser, ClientInfo};
 use crate::services::AuthService;
 
 #[utoipa::path(
@@ -25,12 +34,23 @@ use crate::services::AuthService;
 pub async fn register(
     State(pool): State<PgPool>,
     State(settings): State<Settings>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    client: ClientInfo,
     Json(req): Json<RegisterRequest>,
 ) -> Result<Json<AuthResponse>, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let response = AuthService::register(&pool, &settings, &req.email, &req.password).await?;
+    let response = AuthService::register(
+        &pool,
+        &settings,
+        &jwt_keys,
+        mailer,
+        &client,
+        &req,
+    )
+    .await?;
     Ok(Json(response))
 }
 
@@ -40,7 +60,7 @@ pub async fn register(
     tag = "Auth",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 200, description = "Login successful, or a 2FA challenge if the account has 2FA enabled", body = LoginResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
     )
@@ -48,12 +68,23 @@ pub async fn register(
 pub async fn login(
     State(pool): State<PgPool>,
     State(settings): State<Settings>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
+    client: ClientInfo,
     Json(req): Json<LoginRequest>,
-) -> Result<Json<AuthResponse>, AppError> {
+) -> Result<Json<LoginResponse>, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let response = AuthService::login(&pool, &settings, &req.email, &req.password).await?;
+    let response = AuthService::login(
+        &pool,
+        &settings,
+        &jwt_keys,
+        &client,
+        &req.email,
+        &req.password,
+        req.device_label.as_deref(),
+    )
+    .await?;
     Ok(Json(response))
 }
 
@@ -70,12 +101,180 @@ pub async fn login(
 pub async fn refresh(
     State(pool): State<PgPool>,
     State(settings): State<Settings>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
     Json(req): Json<RefreshRequest>,
 ) -> Result<Json<TokenResponse>, AppError> {
-    let response = AuthService::refresh(&pool, &settings, &req.refresh_token).await?;
+    let response = AuthService::refresh(&pool, &settings, &jwt_keys, &req.refresh_token).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "Auth",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 204, description = "Reset email sent if the account exists"),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+    )
+)]
+pub async fn forgot_password(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Json(req): Json<ForgotPasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    AuthService::forgot_password(&pool, &settings, mailer, &req.email).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "Auth",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 204, description = "Password reset, all sessions signed out"),
+        (status = 400, description = "Validation error or invalid/expired token", body = ErrorResponse),
+    )
+)]
+pub async fn reset_password(
+    State(pool): State<PgPool>,
+    Json(req): Json<ResetPasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    AuthService::reset_password(&pool, &req.token, &req.new_password).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email",
+    tag = "Auth",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 204, description = "Email address verified"),
+        (status = 400, description = "Validation error or invalid/expired token", body = ErrorResponse),
+    )
+)]
+pub async fn verify_email(
+    State(pool): State<PgPool>,
+    Json(req): Json<VerifyEmailRequest>,
+) -> Result<StatusCode, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    AuthService::verify_email(&pool, &req.token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email/resend",
+    tag = "Auth",
+    responses(
+        (status = 204, description = "Verification email sent"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Email already verified", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn resend_verification_email(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<StatusCode, AppError> {
+    AuthService::resend_verification_email(&pool, &settings, mailer, auth_user.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "Auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 204, description = "Session of the refresh token revoked"),
+        (status = 401, description = "Unauthorized or invalid refresh token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn logout(
+    State(pool): State<PgPool>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<StatusCode, AppError> {
+    AuthService::logout(&pool, &jwt_keys, auth_user.user_id, &req.refresh_token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout-all",
+    tag = "Auth",
+    responses(
+        (status = 204, description = "All sessions for the user revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn logout_all(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<StatusCode, AppError> {
+    AuthService::logout_all(&pool, auth_user.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Active sessions for the current user", body = SessionListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_sessions(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<SessionListResponse>, AppError> {
+    let response =
+        AuthService::list_sessions(&pool, auth_user.user_id, auth_user.session_id()?).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{id}",
+    tag = "Auth",
+    params(
+        ("id" = Uuid, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_session(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    AuthService::revoke_session(&pool, auth_user.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/auth/me",
@@ -117,6 +316,7 @@ pub async fn change_password(
     AuthService::change_password(
         &pool,
         auth_user.user_id,
+        auth_user.session_id()?,
         &req.current_password,
         &req.new_password,
     )
@@ -26,6 +26,7 @@ use crate::repositories::BodyStatsRepository;
     responses(
         (status = 200, description = "Measurement created", body = BodyMeasurementResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID or date already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -37,26 +38,19 @@ pub async fn create_measurement(
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
+    // A retried create returns what the first attempt made
+    if let Some(id) = req.id {
+        let existing =
+            BodyStatsRepository::find_measurement_by_id(&pool, id, auth_user.user_id).await?;
+        if let Some(measurement) = existing {
+            return Ok(Json(measurement_to_response(measurement)));
+        }
+    }
+
     let measurement = BodyStatsRepository::create_measurement(
         &pool,
         auth_user.user_id,
-        req.date,
-        req.weight,
-        req.body_fat_percentage,
-        req.chest,
-        req.waist,
-        req.hips,
-        req.left_bicep,
-        req.right_bicep,
-        req.left_thigh,
-        req.right_thigh,
-        req.neck,
-        req.shoulders,
-        req.left_calf,
-        req.right_calf,
-        req.left_forearm,
-        req.right_forearm,
-        req.notes.as_deref(),
+        &req,
     )
     .await?;
 
@@ -142,23 +136,7 @@ pub async fn update_measurement(
         &pool,
         id,
         auth_user.user_id,
-        req.date,
-        req.weight,
-        req.body_fat_percentage,
-        req.chest,
-        req.waist,
-        req.hips,
-        req.left_bicep,
-        req.right_bicep,
-        req.left_thigh,
-        req.right_thigh,
-        req.neck,
-        req.shoulders,
-        req.left_calf,
-        req.right_calf,
-        req.left_forearm,
-        req.right_forearm,
-        req.notes.as_deref(),
+        &req,
     )
     .await?;
 
@@ -194,6 +172,7 @@ pub async fn delete_measurement(
     responses(
         (status = 200, description = "Goal created", body = GoalResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -205,17 +184,23 @@ pub async fn create_goal(
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let goal = BodyStatsRepository::create_goal(
-        &pool,
-        auth_user.user_id,
-        &req.type_,
-        req.measurement_type.as_ref(),
-        req.target_value,
-        req.start_value,
-        req.start_date,
-        req.target_date,
-    )
-    .await?;
+    // A retried create returns what the first attempt made
+    let existing = match req.id {
+        Some(id) => BodyStatsRepository::find_goal_by_id(&pool, id, auth_user.user_id).await?,
+        None => None,
+    };
+
+    let goal = match existing {
+        Some(goal) => goal,
+        None => {
+            BodyStatsRepository::create_goal(
+                &pool,
+                auth_user.user_id,
+                &req,
+            )
+            .await?
+        }
+    };
 
     Ok(Json(GoalResponse {
         id: goal.id,
@@ -434,7 +419,7 @@ pub async fn get_goal_progress(
     }))
 }
 
-fn measurement_to_response(m: BodyMeasurement) -> BodyMeasurementResponse {
+pub(super) fn measurement_to_response(m: BodyMeasurement) -> BodyMeasurementResponse {
     BodyMeasurementResponse {
         id: m.id,
         date: m.date,
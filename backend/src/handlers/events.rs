@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use axum::{
+    Extension,
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use sqlx::PgPool;
+use tokio::time::{Instant, interval_at};
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream, errors::BroadcastStreamRecvError};
+use tokio_stream::{Stream, StreamExt};
+
+use crate::config::Settings;
+use crate::dto::ErrorResponse;
+use crate::error::AppError;
+use crate::events::{EventBus, LiveEvent};
+use crate::middleware::AuthUser;
+use crate::models::ApiResource;
+
+/// Streams the user's workout changes as server-sent events while the
+/// connection stays open. Each event is named like `set.updated` and carries
+/// the IDs of what changed as JSON. A `resync` event means some were missed
+/// and the client should refetch. The stream ends once the session or access
+/// token it was opened with is revoked.
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "Events",
+    responses(
+        (status = 200, description = "Event stream", content_type = "text/event-stream"),
+        (status = 403, description = "Token lacks workouts:read", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn stream_events(
+    State(bus): State<EventBus>,
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
+    let user_id = auth_user.user_id;
+    let records_visible = auth_user.can_access(ApiResource::Stats, false);
+    let receiver = bus.subscribe().await?;
+
+    let events = BroadcastStream::new(receiver).filter_map(move |received| match received {
+        Ok(user_event) if user_event.user_id == user_id => {
+            if !records_visible && matches!(user_event.event, LiveEvent::PersonalRecords { .. }) {
+                return None;
+            }
+            Some(Some(
+                Event::default()
+                    .event(user_event.event.name())
+                    .json_data(&user_event.event),
+            ))
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(_)) => {
+            Some(Some(Ok(Event::default().event("resync").data("{}"))))
+        }
+    });
+
+    // The credential was checked when the stream opened; re-check it now and
+    // then, and end the stream with a `None` once it is no longer valid
+    let period = Duration::from_secs(settings.auth.stream_recheck_secs);
+    let revoked = IntervalStream::new(interval_at(Instant::now() + period, period))
+        .then(move |_| {
+            let pool = pool.clone();
+            let auth_user = auth_user.clone();
+            async move {
+                // A failed check keeps the stream open; the next one may succeed
+                auth_user.is_active(&pool).await.unwrap_or_else(|e| {
+                    tracing::warn!("Failed to re-check event stream credentials: {}", e);
+                    true
+                })
+            }
+        })
+        .filter_map(|active| (!active).then_some(None));
+
+    let stream = events.merge(revoked).map_while(|event| event);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -122,12 +122,7 @@ pub async fn create_custom_exercise(
         &pool,
         auth_user.user_id,
         &id,
-        &req.name,
-        &req.muscle_groups,
-        &req.category,
-        &req.equipment,
-        req.description.as_deref(),
-        req.instructions.as_deref(),
+        &req,
     )
     .await?;
 
@@ -168,12 +163,7 @@ pub async fn update_custom_exercise(
         &pool,
         &id,
         auth_user.user_id,
-        req.name.as_deref(),
-        req.muscle_groups.as_deref(),
-        req.category.as_ref(),
-        req.equipment.as_deref(),
-        req.description.as_deref(),
-        req.instructions.as_deref(),
+        &req,
     )
     .await?;
 
@@ -0,0 +1,19 @@
+use axum::{extract::State, http::header, response::IntoResponse, Json};
+use std::sync::Arc;
+
+use crate::jwt::JwtKeys;
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Public keys that verify our access tokens, by kid (empty when tokens are signed with a shared secret)", body = Object, content_type = "application/json"),
+    )
+)]
+pub async fn jwks(State(jwt_keys): State<Arc<JwtKeys>>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(jwt_keys.jwks().clone()),
+    )
+}
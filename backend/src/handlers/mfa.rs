@@ -0,0 +1,160 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use sqlx::PgPool;
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::config::Settings;
+use crate::dto::{
+    AuthResponse, DisableTotpRequest, ErrorResponse, MfaStatusResponse, MfaVerifyRequest,
+    RecoveryCodesResponse, TotpCodeRequest, TotpSetupResponse,
+};
+use crate::error::AppError;
+use crate::jwt::JwtKeys;
+use crate::middleware::{AuthUser, ClientInfo};
+use crate::services::{AuthService, MfaService};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/mfa/verify",
+    tag = "Auth",
+    request_body = MfaVerifyRequest,
+    responses(
+        (status = 200, description = "Second factor accepted", body = AuthResponse),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Invalid or expired challenge, or wrong code", body = ErrorResponse),
+    )
+)]
+pub async fn verify_mfa(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
+    client: ClientInfo,
+    Json(req): Json<MfaVerifyRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response = AuthService::verify_mfa_login(
+        &pool,
+        &settings,
+        &jwt_keys,
+        &client,
+        &req.mfa_token,
+        &req.code,
+        req.device_label.as_deref(),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/mfa",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Two-factor authentication status", body = MfaStatusResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_mfa_status(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<MfaStatusResponse>, AppError> {
+    let response = MfaService::status(&pool, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/mfa/totp/setup",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Pending TOTP secret generated", body = TotpSetupResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Two-factor authentication already enabled", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn setup_totp(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<TotpSetupResponse>, AppError> {
+    let response = MfaService::setup_totp(&pool, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/mfa/totp/confirm",
+    tag = "Auth",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "TOTP enabled; recovery codes returned once", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code or no pending enrollment", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_totp(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response = MfaService::confirm_totp(&pool, auth_user.user_id, &req.code).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/mfa/totp/disable",
+    tag = "Auth",
+    request_body = DisableTotpRequest,
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 400, description = "Wrong password or code, or 2FA is mandatory", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_totp(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<DisableTotpRequest>,
+) -> Result<StatusCode, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    MfaService::disable_totp(&pool, &settings, auth_user.user_id, &req.password, &req.code)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/mfa/recovery-codes",
+    tag = "Auth",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "New recovery codes; previous ones are invalidated", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code or 2FA not enabled", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn regenerate_recovery_codes(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<Json<RecoveryCodesResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response =
+        MfaService::regenerate_recovery_codes(&pool, auth_user.user_id, &req.code).await?;
+    Ok(Json(response))
+}
@@ -1,5 +1,7 @@
 use axum::{
     extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
     Extension, Json,
 };
 use sqlx::PgPool;
@@ -12,6 +14,7 @@ use crate::dto::{
     WorkoutResponse,
 };
 use crate::error::AppError;
+use crate::etag::{if_match_version, version_etag};
 use crate::middleware::AuthUser;
 use crate::repositories::ProgramRepository;
 use crate::services::WorkoutService;
@@ -24,6 +27,7 @@ use crate::services::WorkoutService;
     responses(
         (status = 200, description = "Program created", body = ProgramResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -35,9 +39,18 @@ pub async fn create_program(
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
+    // A retried create returns what the first attempt made
+    if let Some(id) = req.id
+        && let Some(program) = ProgramRepository::find_by_id(&pool, id, auth_user.user_id).await?
+    {
+        let workouts = ProgramRepository::find_workouts(&pool, id).await?;
+        return Ok(Json(build_program_response(program, workouts)));
+    }
+
     let program = ProgramRepository::create(
         &pool,
         auth_user.user_id,
+        req.id,
         &req.name,
         req.description.as_deref(),
         req.duration_weeks,
@@ -49,12 +62,7 @@ pub async fn create_program(
         ProgramRepository::add_workout(
             &pool,
             program.id,
-            workout_req.week_number,
-            workout_req.day_number,
-            &workout_req.name,
-            workout_req.template_id,
-            workout_req.is_rest_day,
-            workout_req.notes.as_deref(),
+            workout_req,
         )
         .await?;
     }
@@ -135,25 +143,30 @@ pub async fn get_program(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
-) -> Result<Json<ProgramResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let program = ProgramRepository::find_by_id(&pool, id, auth_user.user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
 
     let workouts = ProgramRepository::find_workouts(&pool, program.id).await?;
 
-    Ok(Json(build_program_response(program, workouts)))
+    let etag = version_etag(program.version);
+    Ok(([(header::ETAG, etag)], Json(build_program_response(program, workouts))))
 }
 
 #[utoipa::path(
     patch,
     path = "/api/v1/programs/{id}",
     tag = "Programs",
-    params(("id" = Uuid, Path, description = "Program ID")),
+    params(
+        ("id" = Uuid, Path, description = "Program ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
+    ),
     request_body = UpdateProgramRequest,
     responses(
         (status = 200, description = "Program updated", body = ProgramResponse),
         (status = 404, description = "Program not found", body = ErrorResponse),
+        (status = 412, description = "Program has changed since it was read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -161,8 +174,9 @@ pub async fn update_program(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<UpdateProgramRequest>,
-) -> Result<Json<ProgramResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
@@ -170,6 +184,7 @@ pub async fn update_program(
         &pool,
         id,
         auth_user.user_id,
+        if_match_version(&headers)?,
         req.name.as_deref(),
         req.description.as_deref(),
         req.duration_weeks,
@@ -183,12 +198,7 @@ pub async fn update_program(
             ProgramRepository::add_workout(
                 &pool,
                 program.id,
-                workout_req.week_number,
-                workout_req.day_number,
-                &workout_req.name,
-                workout_req.template_id,
-                workout_req.is_rest_day,
-                workout_req.notes.as_deref(),
+                workout_req,
             )
             .await?;
         }
@@ -196,7 +206,8 @@ pub async fn update_program(
 
     let workouts = ProgramRepository::find_workouts(&pool, program.id).await?;
 
-    Ok(Json(build_program_response(program, workouts)))
+    let etag = version_etag(program.version);
+    Ok(([(header::ETAG, etag)], Json(build_program_response(program, workouts))))
 }
 
 #[utoipa::path(
@@ -354,5 +365,6 @@ fn build_program_response(
                 workouts,
             })
             .collect(),
+        version: program.version,
     }
 }
@@ -10,9 +10,10 @@ use validator::Validate;
 
 use crate::dto::{ErrorResponse, SettingsResponse, UpdateSettingsRequest};
 use crate::error::AppError;
-use crate::etag::{check_none_match, compute_etag};
+use crate::etag::{check_none_match, if_match_version, version_etag};
 use crate::middleware::AuthUser;
-use crate::repositories::SettingsRepository;
+use crate::models::Job;
+use crate::repositories::{JobRepository, SettingsRepository};
 
 #[utoipa::path(
     get,
@@ -37,76 +38,71 @@ pub async fn get_settings(
         s
     };
 
-    let response = SettingsResponse {
-        weight_unit: settings.weight_unit,
-        measurement_unit: settings.measurement_unit,
-        theme: settings.theme,
-        default_rest_timer: settings.default_rest_timer,
-        auto_start_rest_timer: settings.auto_start_rest_timer,
-        show_warmup_sets: settings.show_warmup_sets,
-        vibrate_on_timer_end: settings.vibrate_on_timer_end,
-        sound_on_timer_end: settings.sound_on_timer_end,
-        plate_calculator: settings.plate_calculator,
-        compact_mode: settings.compact_mode,
-    };
-
-    let body = serde_json::to_vec(&response).unwrap();
-    let etag = compute_etag(&body);
+    let etag = version_etag(settings.version);
 
     if check_none_match(&headers, &etag) {
         return Ok(StatusCode::NOT_MODIFIED.into_response());
     }
 
-    Ok(([(header::ETAG, etag)], Json(response)).into_response())
+    Ok(([(header::ETAG, etag)], Json(SettingsResponse::from(settings))).into_response())
 }
 
 #[utoipa::path(
     put,
     path = "/api/v1/settings",
     tag = "Settings",
+    params(
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
+    ),
     request_body = UpdateSettingsRequest,
     responses(
         (status = 200, description = "Settings updated", body = SettingsResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 412, description = "Settings have changed since they were read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn update_settings(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: HeaderMap,
     Json(req): Json<UpdateSettingsRequest>,
-) -> Result<Json<SettingsResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
+    let formula_changed = match req.one_rep_max_formula {
+        Some(formula) => {
+            SettingsRepository::get_or_create(&pool, auth_user.user_id)
+                .await?
+                .one_rep_max_formula
+                != formula
+        }
+        None => false,
+    };
+
     let settings = SettingsRepository::update(
         &pool,
         auth_user.user_id,
-        req.weight_unit.as_ref(),
-        req.measurement_unit.as_ref(),
-        req.theme.as_ref(),
-        req.default_rest_timer,
-        req.auto_start_rest_timer,
-        req.show_warmup_sets,
-        req.vibrate_on_timer_end,
-        req.sound_on_timer_end,
-        req.plate_calculator.as_ref(),
-        req.compact_mode,
+        if_match_version(&headers)?,
+        &req,
     )
     .await?;
 
     crate::cache::set_settings(auth_user.user_id, settings.clone());
 
-    Ok(Json(SettingsResponse {
-        weight_unit: settings.weight_unit,
-        measurement_unit: settings.measurement_unit,
-        theme: settings.theme,
-        default_rest_timer: settings.default_rest_timer,
-        auto_start_rest_timer: settings.auto_start_rest_timer,
-        show_warmup_sets: settings.show_warmup_sets,
-        vibrate_on_timer_end: settings.vibrate_on_timer_end,
-        sound_on_timer_end: settings.sound_on_timer_end,
-        plate_calculator: settings.plate_calculator,
-        compact_mode: settings.compact_mode,
-    }))
+    // Estimated 1RM records were computed with the old formula
+    if formula_changed {
+        JobRepository::enqueue(
+            &pool,
+            &Job::RecomputePersonalRecords {
+                user_id: auth_user.user_id,
+                exercise_template_id: None,
+            },
+        )
+        .await?;
+    }
+
+    let etag = version_etag(settings.version);
+    Ok(([(header::ETAG, etag)], Json(SettingsResponse::from(settings))))
 }
@@ -14,7 +14,8 @@ use crate::dto::{
     ExerciseProgressResponse, ExercisesWithHistoryResponse, ExerciseWithHistorySummary,
     HeatmapQuery, MuscleGroupDistribution, MuscleHeatmapResponse, MuscleHeatmapRow,
     OverloadSuggestionsResponse, PersonalRecordResponse, PersonalRecordsListResponse,
-    PlateauAlertResponse, StatisticsQuery, WeeklyVolumeResponse,
+    PersonalRecordsQuery, PlateauAlertResponse, RecordTimelineResponse, RepMaxTableResponse,
+    StatisticsQuery, WeeklyVolumeResponse,
 };
 use crate::error::AppError;
 use crate::middleware::AuthUser;
@@ -117,12 +118,55 @@ pub async fn get_exercise_progress(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/statistics/exercises/{exercise_id}/rep-maxes",
+    tag = "Statistics",
+    params(("exercise_id" = String, Path, description = "Exercise template ID")),
+    responses(
+        (status = 200, description = "Heaviest weight lifted for each rep count from 1 to 20", body = RepMaxTableResponse),
+        (status = 404, description = "Exercise not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_rep_maxes(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(exercise_id): Path<String>,
+) -> Result<Json<RepMaxTableResponse>, AppError> {
+    let response =
+        StatisticsService::get_rep_maxes(&pool, auth_user.user_id, &exercise_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/statistics/exercises/{exercise_id}/record-timeline",
+    tag = "Statistics",
+    params(("exercise_id" = String, Path, description = "Exercise template ID")),
+    responses(
+        (status = 200, description = "Every record set for the exercise, grouped by type", body = RecordTimelineResponse),
+        (status = 404, description = "Exercise not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_record_timeline(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(exercise_id): Path<String>,
+) -> Result<Json<RecordTimelineResponse>, AppError> {
+    let response =
+        StatisticsService::get_record_timeline(&pool, auth_user.user_id, &exercise_id).await?;
+    Ok(Json(response))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/personal-records",
     tag = "Personal Records",
+    params(PersonalRecordsQuery),
     responses(
-        (status = 200, description = "All personal records", body = PersonalRecordsListResponse),
+        (status = 200, description = "Personal records, newest first", body = PersonalRecordsListResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -130,23 +174,16 @@ pub async fn get_exercise_progress(
 pub async fn get_personal_records(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<PersonalRecordsQuery>,
 ) -> Result<Json<PersonalRecordsListResponse>, AppError> {
     info!("Fetching all personal records");
 
-    let records = PersonalRecordRepository::find_all(&pool, auth_user.user_id).await?;
+    let records =
+        PersonalRecordRepository::find_all(&pool, auth_user.user_id, query.current).await?;
 
     let records = records
         .into_iter()
-        .map(|pr| PersonalRecordResponse {
-            id: pr.id,
-            exercise_template_id: pr.exercise_template_id,
-            exercise_name: pr.exercise_name,
-            type_: pr.type_,
-            value: pr.value,
-            reps: pr.reps,
-            achieved_at: pr.achieved_at,
-            workout_id: pr.workout_id,
-        })
+        .map(PersonalRecordResponse::from)
         .collect();
 
     Ok(Json(PersonalRecordsListResponse { records }))
@@ -272,21 +309,28 @@ pub async fn get_muscle_heatmap(
 
     let monthly = query.monthly.unwrap_or(false);
     let count = query.count.unwrap_or(8);
+    let settings = StatisticsService::settings(&pool, auth_user.user_id).await?;
+    let today = settings.today();
 
     let start_date = if monthly {
-        chrono::Utc::now().date_naive() - Duration::weeks(count as i64 * 4)
+        today - Duration::weeks(count as i64 * 4)
     } else {
-        chrono::Utc::now().date_naive() - Duration::weeks(count as i64)
+        today - Duration::weeks(count as i64)
     };
 
     let trunc = if monthly { "month" } else { "week" };
+    let offset_days = if monthly {
+        0
+    } else {
+        settings.week_start.sql_offset_days()
+    };
 
     let sql = format!(
         r#"
         SELECT
-            DATE_TRUNC('{}', w.started_at)::date as period_start,
+            DATE_TRUNC('{}', (w.started_at AT TIME ZONE $3) + make_interval(days => $4))::date - $4 as period_start,
             emg.muscle_group,
-            COUNT(DISTINCT ws.id)::bigint as set_count
+            COUNT(DISTINCT ws.id) FILTER (WHERE ws.parent_set_id IS NULL)::bigint as set_count
         FROM workouts w
         JOIN workout_exercises we ON we.workout_id = w.id
         JOIN workout_sets ws ON ws.workout_exercise_id = we.id
@@ -295,7 +339,7 @@ pub async fn get_muscle_heatmap(
             AND w.status = 'completed'
             AND ws.is_completed = true
             AND ws.is_warmup = false
-            AND DATE(w.started_at) >= $2
+            AND (w.started_at AT TIME ZONE $3)::date >= $2
         GROUP BY period_start, emg.muscle_group
         ORDER BY period_start, emg.muscle_group
         "#,
@@ -305,6 +349,8 @@ pub async fn get_muscle_heatmap(
     let rows = sqlx::query_as::<_, HeatmapRow>(&sql)
         .bind(auth_user.user_id)
         .bind(start_date)
+        .bind(settings.tz().name())
+        .bind(offset_days)
         .fetch_all(&pool)
         .await?;
 
@@ -339,23 +385,25 @@ pub async fn get_consistency_heatmap(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
 ) -> Result<Json<ConsistencyHeatmapResponse>, AppError> {
-    let start_date = chrono::Utc::now().date_naive() - Duration::weeks(52);
+    let settings = StatisticsService::settings(&pool, auth_user.user_id).await?;
+    let start_date = settings.today() - Duration::weeks(52);
 
     let rows = sqlx::query_as::<_, ConsistencyRow>(
         r#"
         SELECT
-            DATE(w.started_at) as workout_date,
+            (w.started_at AT TIME ZONE $3)::date as workout_date,
             COUNT(*)::bigint as count
         FROM workouts w
         WHERE w.user_id = $1
             AND w.status = 'completed'
-            AND DATE(w.started_at) >= $2
-        GROUP BY DATE(w.started_at)
+            AND (w.started_at AT TIME ZONE $3)::date >= $2
+        GROUP BY workout_date
         ORDER BY workout_date
         "#,
     )
     .bind(auth_user.user_id)
     .bind(start_date)
+    .bind(settings.tz().name())
     .fetch_all(&pool)
     .await?;
 
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use chrono::Duration;
+use sqlx::PgPool;
+
+use crate::cursor::{decode_sync_token, encode_sync_token};
+use crate::dto::{ErrorResponse, SettingsResponse, SyncDeletions, SyncQuery, SyncResponse};
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::ApiResource;
+use crate::repositories::{
+    SettingsRepository, SyncRepository, TemplateRepository, TOMBSTONE_RETENTION_DAYS,
+};
+use crate::services::WorkoutService;
+
+use super::body_stats::measurement_to_response;
+use super::template::template_response;
+
+/// Rows are stamped when their transaction starts but only become visible when
+/// it commits, so each token reaches back this far to catch writes that were
+/// still in flight. Clients see some changes twice and apply them as upserts.
+const SYNC_OVERLAP_SECONDS: i64 = 30;
+
+const SYNCED: [ApiResource; 4] = [
+    ApiResource::Workouts,
+    ApiResource::Templates,
+    ApiResource::BodyStats,
+    ApiResource::Settings,
+];
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync",
+    tag = "Sync",
+    params(SyncQuery),
+    responses(
+        (status = 200, description = "Changes since the token", body = SyncResponse),
+        (status = 400, description = "Invalid sync token", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn sync(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<SyncQuery>,
+) -> Result<Json<SyncResponse>, AppError> {
+    if !SYNCED.iter().any(|r| auth_user.can_access(*r, false)) {
+        return Err(AppError::InsufficientScope("workouts:read".to_string()));
+    }
+
+    let since = match query.since.as_deref() {
+        Some(token) => Some(
+            decode_sync_token(token)
+                .ok_or_else(|| AppError::BadRequest("Invalid sync token".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let now = SyncRepository::now(&pool).await?;
+    // Deletions older than the tombstones can't be replayed, so start over
+    let since = since.filter(|s| *s > now - Duration::days(TOMBSTONE_RETENTION_DAYS));
+    let user_id = auth_user.user_id;
+
+    let mut response = SyncResponse {
+        token: encode_sync_token(now - Duration::seconds(SYNC_OVERLAP_SECONDS)),
+        full: since.is_none(),
+        workouts: Vec::new(),
+        templates: Vec::new(),
+        measurements: Vec::new(),
+        settings: None,
+        deleted: SyncDeletions::default(),
+    };
+
+    if auth_user.can_access(ApiResource::Workouts, false) {
+        for (id, deleted) in SyncRepository::workout_changes(&pool, user_id, since).await? {
+            if deleted {
+                response.deleted.workouts.push(id);
+                continue;
+            }
+            // Deleted since the change list was read
+            match WorkoutService::get_workout_with_exercises(&pool, id, user_id).await {
+                Ok(workout) => response.workouts.push(workout),
+                Err(AppError::NotFound(_)) => response.deleted.workouts.push(id),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    if auth_user.can_access(ApiResource::Templates, false) {
+        for (id, deleted) in SyncRepository::template_changes(&pool, user_id, since).await? {
+            let template = if deleted {
+                None
+            } else {
+                TemplateRepository::find_by_id(&pool, id, user_id).await?
+            };
+            match template {
+                Some(template) => {
+                    let exercises = TemplateRepository::get_exercises_with_sets(&pool, id).await?;
+                    response.templates.push(template_response(template, exercises));
+                }
+                None => response.deleted.templates.push(id),
+            }
+        }
+    }
+
+    if auth_user.can_access(ApiResource::BodyStats, false) {
+        response.measurements = SyncRepository::changed_measurements(&pool, user_id, since)
+            .await?
+            .into_iter()
+            .map(measurement_to_response)
+            .collect();
+        if let Some(since) = since {
+            response.deleted.measurements =
+                SyncRepository::deleted_measurements(&pool, user_id, since).await?;
+        }
+    }
+
+    if auth_user.can_access(ApiResource::Settings, false) {
+        let changed = match since {
+            Some(since) => SyncRepository::settings_updated_at(&pool, user_id)
+                .await?
+                .is_some_and(|updated_at| updated_at > since),
+            None => true,
+        };
+        if changed {
+            let settings = SettingsRepository::get_or_create(&pool, user_id).await?;
+            response.settings = Some(SettingsResponse::from(settings));
+        }
+    }
+
+    Ok(Json(response))
+}
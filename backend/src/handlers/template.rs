@@ -1,5 +1,7 @@
 use axum::{
     extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
     Extension, Json,
 };
 use sqlx::PgPool;
@@ -12,7 +14,9 @@ use crate::dto::{
     WorkoutTemplateResponse,
 };
 use crate::error::AppError;
+use crate::etag::{if_match_version, version_etag};
 use crate::middleware::AuthUser;
+use crate::models::{TemplateExercise, WorkoutTemplate};
 use crate::repositories::TemplateRepository;
 use crate::services::WorkoutService;
 
@@ -66,46 +70,15 @@ pub async fn get_template(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
-) -> Result<Json<WorkoutTemplateResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let template = TemplateRepository::find_by_id(&pool, id, auth_user.user_id)
         .await?
         .ok_or_else(|| AppError::NotFound("Template not found".to_string()))?;
 
     let exercises = TemplateRepository::get_exercises_with_sets(&pool, id).await?;
 
-    Ok(Json(WorkoutTemplateResponse {
-        id: template.id,
-        name: template.name,
-        description: template.description,
-        exercises: exercises
-            .into_iter()
-            .map(|e| TemplateExerciseResponse {
-                id: e.id,
-                exercise_template_id: e.exercise_template_id,
-                exercise_name: e.exercise_name,
-                sets: e
-                    .sets
-                    .into_iter()
-                    .map(|s| TemplateSetResponse {
-                        set_number: s.set_number,
-                        target_reps: s.target_reps,
-                        target_weight: s.target_weight,
-                        is_warmup: s.is_warmup,
-                        target_distance_meters: s.target_distance_meters,
-                        target_duration_seconds: s.target_duration_seconds,
-                    })
-                    .collect(),
-                notes: e.notes,
-                rest_seconds: e.rest_seconds,
-                superset_id: e.superset_id,
-            })
-            .collect(),
-        estimated_duration: template.estimated_duration,
-        created_at: template.created_at,
-        last_used_at: template.last_used_at,
-        usage_count: template.usage_count,
-        tags: template.tags,
-    }))
+    let etag = version_etag(template.version);
+    Ok(([(header::ETAG, etag)], Json(template_response(template, exercises))))
 }
 
 #[utoipa::path(
@@ -116,6 +89,7 @@ pub async fn get_template(
     responses(
         (status = 200, description = "Template created", body = WorkoutTemplateResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -127,63 +101,40 @@ pub async fn create_template(
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
+    // A retried create returns what the first attempt made
+    if let Some(id) = req.id {
+        let existing = TemplateRepository::find_by_id(&pool, id, auth_user.user_id).await?;
+        if let Some(template) = existing {
+            let exercises = TemplateRepository::get_exercises_with_sets(&pool, id).await?;
+            return Ok(Json(template_response(template, exercises)));
+        }
+    }
+
     let template = TemplateRepository::create(
         &pool,
         auth_user.user_id,
-        &req.name,
-        req.description.as_deref(),
-        &req.exercises,
-        req.estimated_duration,
-        req.tags.as_deref(),
+        &req,
     )
     .await?;
 
     let exercises = TemplateRepository::get_exercises_with_sets(&pool, template.id).await?;
 
-    Ok(Json(WorkoutTemplateResponse {
-        id: template.id,
-        name: template.name,
-        description: template.description,
-        exercises: exercises
-            .into_iter()
-            .map(|e| TemplateExerciseResponse {
-                id: e.id,
-                exercise_template_id: e.exercise_template_id,
-                exercise_name: e.exercise_name,
-                sets: e
-                    .sets
-                    .into_iter()
-                    .map(|s| TemplateSetResponse {
-                        set_number: s.set_number,
-                        target_reps: s.target_reps,
-                        target_weight: s.target_weight,
-                        is_warmup: s.is_warmup,
-                        target_distance_meters: s.target_distance_meters,
-                        target_duration_seconds: s.target_duration_seconds,
-                    })
-                    .collect(),
-                notes: e.notes,
-                rest_seconds: e.rest_seconds,
-                superset_id: e.superset_id,
-            })
-            .collect(),
-        estimated_duration: template.estimated_duration,
-        created_at: template.created_at,
-        last_used_at: template.last_used_at,
-        usage_count: template.usage_count,
-        tags: template.tags,
-    }))
+    Ok(Json(template_response(template, exercises)))
 }
 
 #[utoipa::path(
     patch,
     path = "/api/v1/templates/{id}",
     tag = "Templates",
-    params(("id" = Uuid, Path, description = "Template ID")),
+    params(
+        ("id" = Uuid, Path, description = "Template ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
+    ),
     request_body = UpdateTemplateRequest,
     responses(
         (status = 200, description = "Template updated", body = WorkoutTemplateResponse),
         (status = 404, description = "Template not found", body = ErrorResponse),
+        (status = 412, description = "Template has changed since it was read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -191,8 +142,9 @@ pub async fn update_template(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<UpdateTemplateRequest>,
-) -> Result<Json<WorkoutTemplateResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
@@ -200,49 +152,15 @@ pub async fn update_template(
         &pool,
         id,
         auth_user.user_id,
-        req.name.as_deref(),
-        req.description.as_deref(),
-        req.exercises.as_deref(),
-        req.estimated_duration,
-        req.tags.as_deref(),
+        if_match_version(&headers)?,
+        &req,
     )
     .await?;
 
     let exercises = TemplateRepository::get_exercises_with_sets(&pool, template.id).await?;
 
-    Ok(Json(WorkoutTemplateResponse {
-        id: template.id,
-        name: template.name,
-        description: template.description,
-        exercises: exercises
-            .into_iter()
-            .map(|e| TemplateExerciseResponse {
-                id: e.id,
-                exercise_template_id: e.exercise_template_id,
-                exercise_name: e.exercise_name,
-                sets: e
-                    .sets
-                    .into_iter()
-                    .map(|s| TemplateSetResponse {
-                        set_number: s.set_number,
-                        target_reps: s.target_reps,
-                        target_weight: s.target_weight,
-                        is_warmup: s.is_warmup,
-                        target_distance_meters: s.target_distance_meters,
-                        target_duration_seconds: s.target_duration_seconds,
-                    })
-                    .collect(),
-                notes: e.notes,
-                rest_seconds: e.rest_seconds,
-                superset_id: e.superset_id,
-            })
-            .collect(),
-        estimated_duration: template.estimated_duration,
-        created_at: template.created_at,
-        last_used_at: template.last_used_at,
-        usage_count: template.usage_count,
-        tags: template.tags,
-    }))
+    let etag = version_etag(template.version);
+    Ok(([(header::ETAG, etag)], Json(template_response(template, exercises))))
 }
 
 #[utoipa::path(
@@ -283,7 +201,34 @@ pub async fn restore_template(
     let template = TemplateRepository::restore(&pool, id, auth_user.user_id).await?;
     let exercises = TemplateRepository::get_exercises_with_sets(&pool, id).await?;
 
-    Ok(Json(WorkoutTemplateResponse {
+    Ok(Json(template_response(template, exercises)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/templates/{id}/start",
+    tag = "Templates",
+    params(("id" = Uuid, Path, description = "Template ID")),
+    responses(
+        (status = 200, description = "Workout started from template", body = WorkoutResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn start_workout_from_template(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkoutResponse>, AppError> {
+    let response = WorkoutService::start_from_template(&pool, auth_user.user_id, id).await?;
+    Ok(Json(response))
+}
+
+pub(super) fn template_response(
+    template: WorkoutTemplate,
+    exercises: Vec<TemplateExercise>,
+) -> WorkoutTemplateResponse {
+    WorkoutTemplateResponse {
         id: template.id,
         name: template.name,
         description: template.description,
@@ -293,9 +238,6 @@ pub async fn restore_template(
                 id: e.id,
                 exercise_template_id: e.exercise_template_id,
                 exercise_name: e.exercise_name,
-                notes: e.notes,
-                rest_seconds: e.rest_seconds,
-                superset_id: e.superset_id,
                 sets: e
                     .sets
                     .into_iter()
@@ -306,8 +248,16 @@ pub async fn restore_template(
                         is_warmup: s.is_warmup,
                         target_distance_meters: s.target_distance_meters,
                         target_duration_seconds: s.target_duration_seconds,
+                        set_type: s.set_type,
+                        parent_set_number: s.parent_set_number,
+                        target_rir: s.target_rir,
+                        tempo: s.tempo,
+                        is_unilateral: s.is_unilateral,
                     })
                     .collect(),
+                notes: e.notes,
+                rest_seconds: e.rest_seconds,
+                superset_id: e.superset_id,
             })
             .collect(),
         estimated_duration: template.estimated_duration,
@@ -315,25 +265,6 @@ pub async fn restore_template(
         last_used_at: template.last_used_at,
         usage_count: template.usage_count,
         tags: template.tags,
-    }))
-}
-
-#[utoipa::path(
-    post,
-    path = "/api/v1/templates/{id}/start",
-    tag = "Templates",
-    params(("id" = Uuid, Path, description = "Template ID")),
-    responses(
-        (status = 200, description = "Workout started from template", body = WorkoutResponse),
-        (status = 404, description = "Template not found", body = ErrorResponse),
-    ),
-    security(("bearer_auth" = []))
-)]
-pub async fn start_workout_from_template(
-    State(pool): State<PgPool>,
-    Extension(auth_user): Extension<AuthUser>,
-    Path(id): Path<Uuid>,
-) -> Result<Json<WorkoutResponse>, AppError> {
-    let response = WorkoutService::start_from_template(&pool, auth_user.user_id, id).await?;
-    Ok(Json(response))
+        version: template.version,
+    }
 }
@@ -1,6 +1,8 @@
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
+    http::{HeaderMap, header},
+    response::IntoResponse,
 };
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -10,15 +12,16 @@ use crate::cache;
 use crate::cursor::encode_cursor;
 use crate::dto::{
     CreateSetRequest, CreateSupersetRequest, CreateWorkoutExerciseRequest, CreateWorkoutRequest,
-    ErrorResponse, ReorderExercisesRequest, SupersetResponse, UpdateSetRequest,
-    UpdateWorkoutExerciseRequest, UpdateWorkoutRequest, WorkoutExerciseResponse,
+    ErrorResponse, ImportWorkoutRequest, ReorderExercisesRequest, SupersetResponse,
+    UpdateSetRequest, UpdateWorkoutExerciseRequest, UpdateWorkoutRequest, WorkoutExerciseResponse,
     WorkoutListResponse, WorkoutQuery, WorkoutResponse, WorkoutSetResponse, WorkoutSummaryResponse,
 };
 use crate::error::AppError;
+use crate::etag::{if_match_version, version_etag};
+use crate::events::{self, Action, LiveEvent};
 use crate::middleware::AuthUser;
-use crate::repositories::{ProgramRepository, WorkoutRepository};
-use crate::routes::AppState;
-use crate::services::{PrJob, WorkoutService};
+use crate::repositories::{NewSet, WorkoutRepository};
+use crate::services::WorkoutService;
 
 // Workout handlers
 
@@ -30,6 +33,7 @@ use crate::services::{PrJob, WorkoutService};
     responses(
         (status = 200, description = "Workout created", body = WorkoutResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -41,20 +45,72 @@ pub async fn create_workout(
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
+    // A retried create returns what the first attempt made
+    if let Some(id) = req.id
+        && WorkoutRepository::find_by_id(&pool, id, auth_user.user_id).await?.is_some()
+    {
+        let response =
+            WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
+        return Ok(Json(response));
+    }
+
     let workout = WorkoutRepository::create(
         &pool,
         auth_user.user_id,
+        req.id,
         &req.name,
         req.template_id,
         req.notes.as_deref(),
     )
     .await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Created,
+            workout_id: workout.id,
+        },
+    )
+    .await;
 
     let response =
         WorkoutService::get_workout_with_exercises(&pool, workout.id, auth_user.user_id).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/workouts/import",
+    tag = "Workouts",
+    request_body = ImportWorkoutRequest,
+    responses(
+        (status = 200, description = "Workout imported", body = WorkoutResponse),
+        (status = 422, description = "Validation error", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn import_workout(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Json(req): Json<ImportWorkoutRequest>,
+) -> Result<Json<WorkoutResponse>, AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let response = WorkoutService::import_workout(&pool, auth_user.user_id, &req).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Created,
+            workout_id: response.id,
+        },
+    )
+    .await;
+    Ok(Json(response))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/workouts",
@@ -98,6 +154,7 @@ pub async fn list_workouts(
                 status: w.status,
                 exercise_count: w.exercise_count,
                 tags: w.tags,
+                avg_rest_seconds: w.avg_rest_seconds,
             })
             .collect(),
         next_cursor,
@@ -119,20 +176,24 @@ pub async fn get_workout(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
-) -> Result<Json<WorkoutResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
-    Ok(Json(response))
+    Ok(([(header::ETAG, version_etag(response.version))], Json(response)))
 }
 
 #[utoipa::path(
     patch,
     path = "/api/v1/workouts/{id}",
     tag = "Workouts",
-    params(("id" = Uuid, Path, description = "Workout ID")),
+    params(
+        ("id" = Uuid, Path, description = "Workout ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
+    ),
     request_body = UpdateWorkoutRequest,
     responses(
         (status = 200, description = "Workout updated", body = WorkoutResponse),
         (status = 404, description = "Workout not found", body = ErrorResponse),
+        (status = 412, description = "Workout has changed since it was read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -140,8 +201,9 @@ pub async fn update_workout(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<UpdateWorkoutRequest>,
-) -> Result<Json<WorkoutResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
@@ -149,14 +211,24 @@ pub async fn update_workout(
         &pool,
         id,
         auth_user.user_id,
+        if_match_version(&headers)?,
         req.name.as_deref(),
         req.notes.as_deref(),
         req.tags.as_deref(),
     )
     .await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id: id,
+        },
+    )
+    .await;
 
     let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
-    Ok(Json(response))
+    Ok(([(header::ETAG, version_etag(response.version))], Json(response)))
 }
 
 #[utoipa::path(
@@ -175,7 +247,17 @@ pub async fn delete_workout(
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<(), AppError> {
-    WorkoutRepository::delete(&pool, id, auth_user.user_id).await
+    WorkoutRepository::delete(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Deleted,
+            workout_id: id,
+        },
+    )
+    .await;
+    Ok(())
 }
 
 #[utoipa::path(
@@ -195,6 +277,16 @@ pub async fn restore_workout(
     Path(id): Path<Uuid>,
 ) -> Result<Json<WorkoutResponse>, AppError> {
     WorkoutRepository::restore(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Created,
+            workout_id: id,
+        },
+    )
+    .await;
+
     let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
     Ok(Json(response))
 }
@@ -211,21 +303,23 @@ pub async fn restore_workout(
     security(("bearer_auth" = []))
 )]
 pub async fn complete_workout(
-    State(state): State<AppState>,
+    State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<WorkoutResponse>, AppError> {
-    let (_, newly_completed) =
-        WorkoutRepository::complete(&state.pool, id, auth_user.user_id).await?;
-    if newly_completed {
-        let _ = state.pr_tx.try_send(PrJob {
-            pool: state.pool.clone(),
+    WorkoutRepository::complete(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
             workout_id: id,
-            user_id: auth_user.user_id,
-        });
-    }
+        },
+    )
+    .await;
+
     let response =
-        WorkoutService::get_workout_with_exercises(&state.pool, id, auth_user.user_id).await?;
+        WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
     Ok(Json(response))
 }
 
@@ -246,6 +340,80 @@ pub async fn cancel_workout(
     Path(id): Path<Uuid>,
 ) -> Result<Json<WorkoutResponse>, AppError> {
     WorkoutRepository::cancel(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id: id,
+        },
+    )
+    .await;
+
+    let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/workouts/{id}/pause",
+    tag = "Workouts",
+    params(("id" = Uuid, Path, description = "Workout ID")),
+    responses(
+        (status = 200, description = "Workout paused", body = WorkoutResponse),
+        (status = 404, description = "Workout not found", body = ErrorResponse),
+        (status = 409, description = "Workout is not in progress", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn pause_workout(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkoutResponse>, AppError> {
+    WorkoutRepository::pause(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id: id,
+        },
+    )
+    .await;
+
+    let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/workouts/{id}/resume",
+    tag = "Workouts",
+    params(("id" = Uuid, Path, description = "Workout ID")),
+    responses(
+        (status = 200, description = "Workout resumed", body = WorkoutResponse),
+        (status = 404, description = "Workout not found", body = ErrorResponse),
+        (status = 409, description = "Workout is not in progress", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn resume_workout(
+    State(pool): State<PgPool>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkoutResponse>, AppError> {
+    WorkoutRepository::resume(&pool, id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id: id,
+        },
+    )
+    .await;
+
     let response = WorkoutService::get_workout_with_exercises(&pool, id, auth_user.user_id).await?;
     Ok(Json(response))
 }
@@ -262,6 +430,7 @@ pub async fn cancel_workout(
         (status = 200, description = "Exercise added to workout", body = WorkoutExerciseResponse),
         (status = 400, description = "Validation error", body = ErrorResponse),
         (status = 404, description = "Workout not found", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -279,28 +448,54 @@ pub async fn add_exercise(
         .await?
         .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
 
-    // Resolve exercise name: use provided value, fall back to cache, then DB
-    let exercise_name = if let Some(ref name) = req.exercise_name {
-        name.clone()
-    } else if let Some(cached) = cache::get_exercise_name(&req.exercise_template_id) {
-        cached
-    } else {
-        sqlx::query_scalar::<_, String>("SELECT name FROM exercise_templates WHERE id = $1")
-            .bind(&req.exercise_template_id)
-            .fetch_optional(&pool)
+    // A retried add returns the exercise the first attempt made
+    let existing = match req.id {
+        Some(id) => WorkoutRepository::get_exercises(&pool, workout_id)
             .await?
-            .ok_or_else(|| AppError::NotFound("Exercise template not found".to_string()))?
+            .into_iter()
+            .find(|e| e.id == id),
+        None => None,
     };
 
-    let exercise = WorkoutRepository::add_exercise(
-        &pool,
-        workout_id,
-        &req.exercise_template_id,
-        &exercise_name,
-        req.notes.as_deref(),
-        req.superset_id,
-    )
-    .await?;
+    let exercise = match existing {
+        Some(exercise) => exercise,
+        None => {
+            // Resolve exercise name: use provided value, fall back to cache, then DB
+            let exercise_name = if let Some(ref name) = req.exercise_name {
+                name.clone()
+            } else if let Some(cached) = cache::get_exercise_name(&req.exercise_template_id) {
+                cached
+            } else {
+                sqlx::query_scalar::<_, String>("SELECT name FROM exercise_templates WHERE id = $1")
+                    .bind(&req.exercise_template_id)
+                    .fetch_optional(&pool)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("Exercise template not found".to_string()))?
+            };
+
+            let exercise = WorkoutRepository::add_exercise(
+                &pool,
+                workout_id,
+                req.id,
+                &req.exercise_template_id,
+                &exercise_name,
+                req.notes.as_deref(),
+                req.superset_id,
+            )
+            .await?;
+            events::publish(
+                &pool,
+                auth_user.user_id,
+                LiveEvent::Exercise {
+                    action: Action::Created,
+                    workout_id,
+                    exercise_id: exercise.id,
+                },
+            )
+            .await;
+            exercise
+        }
+    };
 
     let (sets, exercise_category) = tokio::join!(
         WorkoutRepository::get_sets(&pool, exercise.id),
@@ -331,11 +526,22 @@ pub async fn add_exercise(
                 calories: s.calories,
                 target_distance_meters: s.target_distance_meters,
                 target_duration_seconds: s.target_duration_seconds,
+                rest_seconds_actual: s.rest_seconds_actual,
+                set_type: s.set_type,
+                parent_set_id: s.parent_set_id,
+                target_rir: s.target_rir,
+                rir: s.rir,
+                tempo: s.tempo,
+                is_unilateral: s.is_unilateral,
+                actual_reps_left: s.actual_reps_left,
+                actual_reps_right: s.actual_reps_right,
+                version: s.version,
             })
             .collect(),
         notes: exercise.notes,
         superset_id: exercise.superset_id,
         exercise_category,
+        version: exercise.version,
     }))
 }
 
@@ -346,24 +552,46 @@ pub async fn add_exercise(
     params(
         ("workout_id" = Uuid, Path, description = "Workout ID"),
         ("exercise_id" = Uuid, Path, description = "Exercise ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
     ),
     request_body = UpdateWorkoutExerciseRequest,
     responses(
         (status = 200, description = "Exercise updated", body = WorkoutExerciseResponse),
         (status = 404, description = "Exercise not found", body = ErrorResponse),
+        (status = 412, description = "Exercise has changed since it was read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn update_exercise(
     State(pool): State<PgPool>,
-    Path((_workout_id, exercise_id)): Path<(Uuid, Uuid)>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((workout_id, exercise_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
     Json(req): Json<UpdateWorkoutExerciseRequest>,
-) -> Result<Json<WorkoutExerciseResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let exercise =
-        WorkoutRepository::update_exercise(&pool, exercise_id, req.notes.as_deref()).await?;
+    let exercise = WorkoutRepository::update_exercise(
+        &pool,
+        workout_id,
+        exercise_id,
+        auth_user.user_id,
+        if_match_version(&headers)?,
+        req.notes.as_deref(),
+    )
+    .await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Exercise {
+            action: Action::Updated,
+            workout_id,
+            exercise_id,
+        },
+    )
+    .await;
+
     let (sets, exercise_category) = tokio::join!(
         WorkoutRepository::get_sets(&pool, exercise_id),
         WorkoutRepository::get_exercise_category(&pool, &exercise.exercise_template_id),
@@ -371,7 +599,8 @@ pub async fn update_exercise(
     let sets = sets?;
     let exercise_category = exercise_category?;
 
-    Ok(Json(WorkoutExerciseResponse {
+    let etag = version_etag(exercise.version);
+    Ok(([(header::ETAG, etag)], Json(WorkoutExerciseResponse {
         id: exercise.id,
         exercise_template_id: exercise.exercise_template_id,
         exercise_name: exercise.exercise_name,
@@ -393,12 +622,23 @@ pub async fn update_exercise(
                 calories: s.calories,
                 target_distance_meters: s.target_distance_meters,
                 target_duration_seconds: s.target_duration_seconds,
+                rest_seconds_actual: s.rest_seconds_actual,
+                set_type: s.set_type,
+                parent_set_id: s.parent_set_id,
+                target_rir: s.target_rir,
+                rir: s.rir,
+                tempo: s.tempo,
+                is_unilateral: s.is_unilateral,
+                actual_reps_left: s.actual_reps_left,
+                actual_reps_right: s.actual_reps_right,
+                version: s.version,
             })
             .collect(),
         notes: exercise.notes,
         superset_id: exercise.superset_id,
         exercise_category,
-    }))
+        version: exercise.version,
+    })))
 }
 
 #[utoipa::path(
@@ -417,9 +657,21 @@ pub async fn update_exercise(
 )]
 pub async fn delete_exercise(
     State(pool): State<PgPool>,
-    Path((_workout_id, exercise_id)): Path<(Uuid, Uuid)>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((workout_id, exercise_id)): Path<(Uuid, Uuid)>,
 ) -> Result<(), AppError> {
-    WorkoutRepository::delete_exercise(&pool, exercise_id).await
+    WorkoutRepository::delete_exercise(&pool, exercise_id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Exercise {
+            action: Action::Deleted,
+            workout_id,
+            exercise_id,
+        },
+    )
+    .await;
+    Ok(())
 }
 
 // Set handlers
@@ -435,31 +687,80 @@ pub async fn delete_exercise(
     request_body = CreateSetRequest,
     responses(
         (status = 200, description = "Set added", body = WorkoutSetResponse),
-        (status = 404, description = "Exercise not found", body = ErrorResponse),
+        (status = 404, description = "Exercise or parent set not found", body = ErrorResponse),
+        (status = 409, description = "ID already in use", body = ErrorResponse),
+        (status = 422, description = "Parent set can't have sub-sets", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn add_set(
     State(pool): State<PgPool>,
-    Path((_workout_id, exercise_id)): Path<(Uuid, Uuid)>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((workout_id, exercise_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<CreateSetRequest>,
 ) -> Result<Json<WorkoutSetResponse>, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    let set = WorkoutRepository::add_set(
-        &pool,
-        exercise_id,
-        req.target_reps,
-        req.target_weight,
-        req.is_warmup,
-        req.distance_meters,
-        req.duration_seconds,
-        req.calories,
-        req.target_distance_meters,
-        req.target_duration_seconds,
-    )
-    .await?;
+    // Verify the exercise is in a workout that belongs to user
+    WorkoutRepository::find_by_id(&pool, workout_id, auth_user.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+    if !WorkoutRepository::get_exercises(&pool, workout_id)
+        .await?
+        .iter()
+        .any(|e| e.id == exercise_id)
+    {
+        return Err(AppError::NotFound("Exercise not found".to_string()));
+    }
+
+    // A retried add returns the set the first attempt made
+    let existing = match req.id {
+        Some(id) => WorkoutRepository::get_sets(&pool, exercise_id)
+            .await?
+            .into_iter()
+            .find(|s| s.id == id),
+        None => None,
+    };
+
+    let set = match existing {
+        Some(set) => set,
+        None => {
+            let set = WorkoutRepository::add_set(
+                &pool,
+                exercise_id,
+                NewSet {
+                    id: req.id,
+                    target_reps: req.target_reps,
+                    target_weight: req.target_weight,
+                    is_warmup: req.is_warmup,
+                    distance_meters: req.distance_meters,
+                    duration_seconds: req.duration_seconds,
+                    calories: req.calories,
+                    target_distance_meters: req.target_distance_meters,
+                    target_duration_seconds: req.target_duration_seconds,
+                    set_type: req.set_type,
+                    parent_set_id: req.parent_set_id,
+                    target_rir: req.target_rir,
+                    tempo: req.tempo.as_deref(),
+                    is_unilateral: req.is_unilateral,
+                },
+            )
+            .await?;
+            events::publish(
+                &pool,
+                auth_user.user_id,
+                LiveEvent::Set {
+                    action: Action::Created,
+                    workout_id,
+                    exercise_id,
+                    set_id: set.id,
+                },
+            )
+            .await;
+            set
+        }
+    };
 
     Ok(Json(WorkoutSetResponse {
         id: set.id,
@@ -477,6 +778,16 @@ pub async fn add_set(
         calories: set.calories,
         target_distance_meters: set.target_distance_meters,
         target_duration_seconds: set.target_duration_seconds,
+        rest_seconds_actual: set.rest_seconds_actual,
+        set_type: set.set_type,
+        parent_set_id: set.parent_set_id,
+        target_rir: set.target_rir,
+        rir: set.rir,
+        tempo: set.tempo,
+        is_unilateral: set.is_unilateral,
+        actual_reps_left: set.actual_reps_left,
+        actual_reps_right: set.actual_reps_right,
+        version: set.version,
     }))
 }
 
@@ -488,39 +799,50 @@ pub async fn add_set(
         ("workout_id" = Uuid, Path, description = "Workout ID"),
         ("exercise_id" = Uuid, Path, description = "Exercise ID"),
         ("set_id" = Uuid, Path, description = "Set ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
     ),
     request_body = UpdateSetRequest,
     responses(
         (status = 200, description = "Set updated", body = WorkoutSetResponse),
         (status = 404, description = "Set not found", body = ErrorResponse),
+        (status = 409, description = "Workout was cancelled", body = ErrorResponse),
+        (status = 412, description = "Set has changed since it was read", body = ErrorResponse),
+        (status = 422, description = "Set type doesn't fit the set's sub-sets", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn update_set(
     State(pool): State<PgPool>,
-    Path((_workout_id, _exercise_id, set_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((workout_id, exercise_id, set_id)): Path<(Uuid, Uuid, Uuid)>,
+    headers: HeaderMap,
     Json(req): Json<UpdateSetRequest>,
-) -> Result<Json<WorkoutSetResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     req.validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
     let set = WorkoutRepository::update_set(
         &pool,
         set_id,
-        req.target_reps,
-        req.actual_reps,
-        req.target_weight,
-        req.actual_weight,
-        req.is_warmup,
-        req.is_completed,
-        req.rpe,
-        req.distance_meters,
-        req.duration_seconds,
-        req.calories,
+        auth_user.user_id,
+        if_match_version(&headers)?,
+        &req,
     )
     .await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Set {
+            action: Action::Updated,
+            workout_id,
+            exercise_id,
+            set_id,
+        },
+    )
+    .await;
 
-    Ok(Json(WorkoutSetResponse {
+    let etag = version_etag(set.version);
+    Ok(([(header::ETAG, etag)], Json(WorkoutSetResponse {
         id: set.id,
         set_number: set.set_number,
         target_reps: set.target_reps,
@@ -536,7 +858,17 @@ pub async fn update_set(
         calories: set.calories,
         target_distance_meters: set.target_distance_meters,
         target_duration_seconds: set.target_duration_seconds,
-    }))
+        rest_seconds_actual: set.rest_seconds_actual,
+        set_type: set.set_type,
+        parent_set_id: set.parent_set_id,
+        target_rir: set.target_rir,
+        rir: set.rir,
+        tempo: set.tempo,
+        is_unilateral: set.is_unilateral,
+        actual_reps_left: set.actual_reps_left,
+        actual_reps_right: set.actual_reps_right,
+        version: set.version,
+    })))
 }
 
 #[utoipa::path(
@@ -556,9 +888,22 @@ pub async fn update_set(
 )]
 pub async fn delete_set(
     State(pool): State<PgPool>,
-    Path((_workout_id, _exercise_id, set_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path((workout_id, exercise_id, set_id)): Path<(Uuid, Uuid, Uuid)>,
 ) -> Result<(), AppError> {
-    WorkoutRepository::delete_set(&pool, set_id).await
+    WorkoutRepository::delete_set(&pool, set_id, auth_user.user_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Set {
+            action: Action::Deleted,
+            workout_id,
+            exercise_id,
+            set_id,
+        },
+    )
+    .await;
+    Ok(())
 }
 
 // Superset handlers
@@ -592,6 +937,15 @@ pub async fn create_superset(
 
     let superset_id =
         WorkoutRepository::create_superset(&pool, workout_id, &req.exercise_ids).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id,
+        },
+    )
+    .await;
 
     Ok(Json(SupersetResponse {
         superset_id,
@@ -623,19 +977,33 @@ pub async fn remove_superset(
         .await?
         .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
 
-    WorkoutRepository::remove_superset(&pool, workout_id, superset_id).await
+    WorkoutRepository::remove_superset(&pool, workout_id, superset_id).await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id,
+        },
+    )
+    .await;
+    Ok(())
 }
 
 #[utoipa::path(
     patch,
     path = "/api/v1/workouts/{workout_id}/exercises/reorder",
     tag = "Workout Exercises",
-    params(("workout_id" = Uuid, Path, description = "Workout ID")),
+    params(
+        ("workout_id" = Uuid, Path, description = "Workout ID"),
+        ("If-Match" = Option<String>, Header, description = "Update only if the ETag still matches"),
+    ),
     request_body = ReorderExercisesRequest,
     responses(
         (status = 200, description = "Exercises reordered"),
         (status = 400, description = "Validation error", body = ErrorResponse),
         (status = 404, description = "Workout not found", body = ErrorResponse),
+        (status = 412, description = "Workout has changed since it was read", body = ErrorResponse),
     ),
     security(("bearer_auth" = []))
 )]
@@ -643,12 +1011,25 @@ pub async fn reorder_exercises(
     State(pool): State<PgPool>,
     Extension(auth_user): Extension<AuthUser>,
     Path(workout_id): Path<Uuid>,
+    headers: HeaderMap,
     Json(req): Json<ReorderExercisesRequest>,
 ) -> Result<(), AppError> {
-    // Verify workout belongs to user
-    WorkoutRepository::find_by_id(&pool, workout_id, auth_user.user_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
-
-    WorkoutRepository::reorder_exercises(&pool, workout_id, &req.exercises).await
+    WorkoutRepository::reorder_exercises(
+        &pool,
+        workout_id,
+        auth_user.user_id,
+        if_match_version(&headers)?,
+        &req.exercises,
+    )
+    .await?;
+    events::publish(
+        &pool,
+        auth_user.user_id,
+        LiveEvent::Workout {
+            action: Action::Updated,
+            workout_id,
+        },
+    )
+    .await;
+    Ok(())
 }
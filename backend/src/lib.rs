@@ -5,10 +5,15 @@ pub mod db;
 pub mod dto;
 pub mod error;
 pub mod etag;
+pub mod events;
 pub mod handlers;
+pub mod jwt;
+pub mod mail;
 pub mod middleware;
 pub mod models;
+pub mod one_rep_max;
 pub mod openapi;
 pub mod repositories;
 pub mod routes;
 pub mod services;
+pub mod totp;
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+
+use super::{EmailMessage, Mailer};
+use crate::error::AppError;
+
+/// Development transport: writes every message to the log instead of sending it.
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, message: EmailMessage) -> Result<(), AppError> {
+        tracing::info!(
+            to = %message.to,
+            subject = %message.subject,
+            body = %message.body,
+            "Email not sent (log transport)"
+        );
+        Ok(())
+    }
+}
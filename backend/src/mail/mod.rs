@@ -0,0 +1,44 @@
+mod log;
+mod smtp;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::{MailSettings, MailTransport};
+use crate::error::AppError;
+
+pub use log::LogMailer;
+pub use smtp::SmtpMailer;
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outbound email transport. Implementations must be cheap to share across requests.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: EmailMessage) -> Result<(), AppError>;
+}
+
+/// Sends off the request path, logging failures. Used where the response must not
+/// wait on (or reveal anything through) the mail server.
+pub fn send_in_background(mailer: Arc<dyn Mailer>, message: EmailMessage) {
+    tokio::spawn(async move {
+        let subject = message.subject.clone();
+        if let Err(e) = mailer.send(message).await {
+            tracing::error!(subject = %subject, "Failed to send email: {e}");
+        }
+    });
+}
+
+/// Builds the transport selected by `mail.transport`.
+pub fn build_mailer(settings: &MailSettings) -> Result<Arc<dyn Mailer>, AppError> {
+    match settings.transport {
+        MailTransport::Log => Ok(Arc::new(LogMailer)),
+        MailTransport::Smtp => Ok(Arc::new(SmtpMailer::new(settings)?)),
+    }
+}
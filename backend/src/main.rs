@@ -3,8 +3,13 @@ use std::time::Duration;
 
 use fitness_tracker_api::config::Settings;
 use fitness_tracker_api::db::create_pool;
+use fitness_tracker_api::repositories::{
+    AccessTokenRepository, EmailVerificationTokenRepository, IdempotencyKeyRepository,
+    PasswordResetTokenRepository, RefreshTokenRepository, SessionRepository, SyncRepository,
+    UserRepository,
+};
 use fitness_tracker_api::routes::create_router;
-use fitness_tracker_api::services::{pr_worker, PrJob};
+use fitness_tracker_api::services::JobWorker;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -39,9 +44,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     sqlx::migrate!("./migrations").run(&pool).await?;
     tracing::info!("Migrations applied");
 
-    // Spawn background PR detection worker
-    let (pr_tx, pr_rx) = tokio::sync::mpsc::channel::<PrJob>(256);
-    tokio::spawn(pr_worker(pr_rx));
+    // Spawn background job worker (PR detection etc.)
+    let job_worker = JobWorker::spawn(pool.clone(), settings.jobs.clone());
 
     // Spawn DB pool metrics poller (every 15s)
     let pool_metrics = pool.clone();
@@ -54,8 +58,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    // Spawn expired session/token cleanup and scheduled account purges (hourly)
+    let pool_cleanup = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            match RefreshTokenRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired refresh tokens", n),
+                Err(e) => tracing::error!("Refresh token cleanup failed: {e}"),
+            }
+            match SessionRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired sessions", n),
+                Err(e) => tracing::error!("Session cleanup failed: {e}"),
+            }
+            match PasswordResetTokenRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired password reset tokens", n),
+                Err(e) => tracing::error!("Password reset token cleanup failed: {e}"),
+            }
+            match EmailVerificationTokenRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired email verification tokens", n),
+                Err(e) => tracing::error!("Email verification token cleanup failed: {e}"),
+            }
+            match AccessTokenRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired personal access tokens", n),
+                Err(e) => tracing::error!("Personal access token cleanup failed: {e}"),
+            }
+            match IdempotencyKeyRepository::delete_expired(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired idempotency keys", n),
+                Err(e) => tracing::error!("Idempotency key cleanup failed: {e}"),
+            }
+            match SyncRepository::delete_expired_tombstones(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Deleted {} expired sync tombstones", n),
+                Err(e) => tracing::error!("Sync tombstone cleanup failed: {e}"),
+            }
+            match UserRepository::purge_scheduled_deletions(&pool_cleanup).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("Purged {} accounts past their deletion grace period", n),
+                Err(e) => tracing::error!("Account purge failed: {e}"),
+            }
+        }
+    });
+
     // Create router
-    let app = create_router(pool.clone(), settings.clone(), pr_tx);
+    let app = create_router(pool.clone(), settings.clone());
 
     // Start server
     let addr = SocketAddr::new(
@@ -65,8 +118,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Server starting on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal(job_worker))
         .await?;
 
     pool.close().await;
@@ -75,7 +128,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn shutdown_signal() {
+async fn shutdown_signal(job_worker: JobWorker) {
     let ctrl_c = async {
         tokio::signal::ctrl_c().await.unwrap();
     };
@@ -96,5 +149,8 @@ async fn shutdown_signal() {
         _ = sigterm => {},
     }
 
-    tracing::info!("Shutdown signal received, draining in-flight requests...");
+    tracing::info!("Shutdown signal received, draining in-flight requests and jobs...");
+
+    // Unfinished jobs stay queued in the database for the next start
+    job_worker.shutdown().await;
 }
@@ -1,28 +1,78 @@
 use axum::{
     extract::{Request, State},
-    http::header::AUTHORIZATION,
+    http::{header::AUTHORIZATION, HeaderMap},
     middleware::Next,
     response::Response,
 };
+use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::config::Settings;
 use crate::error::AppError;
-use crate::services::{AuthService, TokenType};
+use crate::jwt::JwtKeys;
+use crate::models::{ApiResource, TokenScope};
+use crate::repositories::{AccessTokenRepository, SessionRepository, UserRepository};
+use crate::services::{AccessTokenService, AuthService, TokenType, ACCESS_TOKEN_PREFIX};
 
 #[derive(Debug, Clone)]
 pub struct AuthUser {
     pub user_id: Uuid,
     pub email: String,
+    pub credential: Credential,
 }
 
-pub async fn auth_middleware(
-    State(settings): State<Settings>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, AppError> {
-    let auth_header = request
-        .headers()
+/// How the request was authenticated.
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// JWT access token belonging to an interactive login session
+    Session(Uuid),
+    /// Personal access token, limited to its scopes
+    AccessToken { id: Uuid, scopes: Vec<TokenScope> },
+}
+
+impl AuthUser {
+    /// The caller's login session. Personal access tokens have none.
+    pub fn session_id(&self) -> Result<Uuid, AppError> {
+        match self.credential {
+            Credential::Session(id) => Ok(id),
+            Credential::AccessToken { .. } => Err(AppError::Forbidden),
+        }
+    }
+
+    pub fn is_access_token(&self) -> bool {
+        matches!(self.credential, Credential::AccessToken { .. })
+    }
+
+    /// Whether the session or access token is still usable. Long-lived
+    /// connections call this to notice when it has been revoked.
+    pub async fn is_active(&self, pool: &PgPool) -> Result<bool, AppError> {
+        match &self.credential {
+            Credential::Session(id) => {
+                SessionRepository::touch_active(pool, *id, self.user_id).await
+            }
+            Credential::AccessToken { id, .. } => AccessTokenRepository::is_active(pool, *id).await,
+        }
+    }
+
+    /// Sessions may do anything the user can; access tokens need a matching scope.
+    pub fn can_access(&self, resource: ApiResource, write: bool) -> bool {
+        match &self.credential {
+            Credential::Session(_) => true,
+            Credential::AccessToken { scopes, .. } => {
+                scopes.iter().any(|s| s.grants(resource, write))
+            }
+        }
+    }
+}
+
+/// Validates the bearer credential: either a JWT access token whose session is
+/// still active, or an unrevoked personal access token.
+pub(crate) async fn authenticate(
+    pool: &PgPool,
+    jwt_keys: &JwtKeys,
+    headers: &HeaderMap,
+) -> Result<AuthUser, AppError> {
+    let auth_header = headers
         .get(AUTHORIZATION)
         .and_then(|h| h.to_str().ok())
         .ok_or(AppError::Unauthorized)?;
@@ -31,16 +81,50 @@ pub async fn auth_middleware(
         .strip_prefix("Bearer ")
         .ok_or(AppError::Unauthorized)?;
 
-    let claims = AuthService::verify_token(token, &settings.jwt.secret)?;
+    if token.starts_with(ACCESS_TOKEN_PREFIX) {
+        return authenticate_access_token(pool, token).await;
+    }
+
+    let claims = AuthService::verify_token(token, jwt_keys)?;
 
     if claims.token_type != TokenType::Access {
         return Err(AppError::Unauthorized);
     }
 
-    let auth_user = AuthUser {
+    if !SessionRepository::touch_active(pool, claims.sid, claims.sub).await? {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(AuthUser {
         user_id: claims.sub,
         email: claims.email,
-    };
+        credential: Credential::Session(claims.sid),
+    })
+}
+
+async fn authenticate_access_token(pool: &PgPool, token: &str) -> Result<AuthUser, AppError> {
+    let stored = AccessTokenService::authenticate(pool, token).await?;
+    let user = UserRepository::find_by_id(pool, stored.user_id)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    Ok(AuthUser {
+        user_id: user.id,
+        email: user.email,
+        credential: Credential::AccessToken {
+            scopes: stored.parsed_scopes(),
+            id: stored.id,
+        },
+    })
+}
+
+pub async fn auth_middleware(
+    State(pool): State<PgPool>,
+    State(jwt_keys): State<Arc<JwtKeys>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let auth_user = authenticate(&pool, &jwt_keys, request.headers()).await?;
 
     request.extensions_mut().insert(auth_user);
 
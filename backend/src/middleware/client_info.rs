@@ -0,0 +1,156 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{header::USER_AGENT, request::Parts, Extensions, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::config::Settings;
+
+/// The client address for this request, resolved once by `client_ip_middleware`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// Resolves the client address before rate limiting and handlers see the request.
+pub async fn client_ip_middleware(
+    State(settings): State<Settings>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|c| c.0.ip());
+    let ip = resolve_client_ip(request.headers(), peer, &settings.server.trusted_proxies);
+    request.extensions_mut().insert(ClientIp(ip));
+
+    next.run(request).await
+}
+
+/// Forwarded headers are only believed when the peer is a trusted proxy.
+/// X-Forwarded-For is read right to left, skipping further trusted hops, so
+/// entries a client prepends itself are never reached.
+pub fn resolve_client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(ip));
+
+    // No connection info (e.g. the router driven directly in tests)
+    let Some(peer) = peer else {
+        return IpAddr::V4(Ipv4Addr::LOCALHOST);
+    };
+
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    if let Some(forwarded) = headers.get("x-forwarded-for").and_then(|h| h.to_str().ok()) {
+        for hop in forwarded.rsplit(',') {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) if is_trusted(&ip) => continue,
+                Ok(ip) => return ip,
+                Err(_) => break,
+            }
+        }
+    }
+
+    if let Some(ip) = headers
+        .get("x-real-ip")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+    {
+        return ip;
+    }
+
+    peer
+}
+
+/// Client IP for rate limiting and session tracking.
+pub fn client_ip(extensions: &Extensions) -> IpAddr {
+    extensions
+        .get::<ClientIp>()
+        .map(|c| c.0)
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+/// Where a request came from, recorded against new sessions.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip_address: String,
+    pub user_agent: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(|ua| ua.chars().take(512).collect());
+
+        Ok(ClientInfo {
+            ip_address: client_ip(&parts.extensions).to_string(),
+            user_agent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_forwarded_headers_ignored_from_untrusted_peer() {
+        let h = headers(&[("x-forwarded-for", "1.2.3.4"), ("x-real-ip", "5.6.7.8")]);
+
+        assert_eq!(resolve_client_ip(&h, Some(ip("203.0.113.9")), &[]), ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn test_forwarded_for_skips_trusted_hops_from_the_right() {
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+        // The client spoofed 1.1.1.1; the proxy appended the real address
+        let h = headers(&[("x-forwarded-for", "1.1.1.1, 198.51.100.7, 10.0.0.2")]);
+
+        assert_eq!(
+            resolve_client_ip(&h, Some(ip("10.0.0.1")), &trusted),
+            ip("198.51.100.7")
+        );
+    }
+
+    #[test]
+    fn test_trusted_peer_falls_back_to_real_ip_then_peer() {
+        let trusted: Vec<IpNet> = vec!["127.0.0.1/32".parse().unwrap()];
+
+        let h = headers(&[("x-real-ip", "198.51.100.7")]);
+        assert_eq!(resolve_client_ip(&h, Some(ip("127.0.0.1")), &trusted), ip("198.51.100.7"));
+
+        let h = headers(&[("x-forwarded-for", "garbage")]);
+        assert_eq!(resolve_client_ip(&h, Some(ip("127.0.0.1")), &trusted), ip("127.0.0.1"));
+    }
+
+    #[test]
+    fn test_missing_peer_defaults_to_localhost() {
+        let h = headers(&[("x-forwarded-for", "1.2.3.4")]);
+
+        assert_eq!(resolve_client_ip(&h, None, &[]), IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+}
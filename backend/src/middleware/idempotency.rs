@@ -0,0 +1,124 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::repositories::IdempotencyKeyRepository;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+const MAX_KEY_LENGTH: usize = 255;
+/// Same cap as the router's `DefaultBodyLimit`
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Makes writes sent with an `Idempotency-Key` header safe to retry: the first
+/// successful response is stored and replayed for any repeat with the same
+/// key, so a client retrying over a bad connection can't apply a change
+/// twice. Failed requests don't keep their key. Must be layered inside
+/// `auth_middleware`.
+pub async fn idempotency_middleware(
+    State(pool): State<PgPool>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(next.run(request).await);
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|k| !k.is_empty() && k.len() <= MAX_KEY_LENGTH)
+        .ok_or_else(|| AppError::BadRequest("Invalid Idempotency-Key header".to_string()))?
+        .to_string();
+    let user_id = request
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or(AppError::Unauthorized)?
+        .user_id;
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::BadRequest("Request body too large".to_string()))?;
+    let method = parts.method.as_str().to_string();
+    let path = parts.uri.path().to_string();
+    let request_hash = hex::encode(Sha256::digest(&body));
+
+    let existing =
+        IdempotencyKeyRepository::reserve(&pool, user_id, &key, &method, &path, &request_hash)
+            .await?;
+
+    if let Some(existing) = existing {
+        let same_request = existing.method == method
+            && existing.path == path
+            && existing.request_hash == request_hash;
+        if !same_request {
+            return Err(AppError::Validation(
+                "Idempotency-Key was already used for a different request".to_string(),
+            ));
+        }
+        let (Some(status_code), Some(response_body)) =
+            (existing.status_code, existing.response_body)
+        else {
+            return Err(AppError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            ));
+        };
+
+        let mut response = Response::new(Body::from(response_body));
+        *response.status_mut() =
+            StatusCode::from_u16(status_code as u16).unwrap_or(StatusCode::OK);
+        if let Some(value) = existing.content_type.and_then(|c| HeaderValue::from_str(&c).ok()) {
+            response.headers_mut().insert(header::CONTENT_TYPE, value);
+        }
+        response
+            .headers_mut()
+            .insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+        return Ok(response);
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if !response.status().is_success() {
+        if let Err(e) = IdempotencyKeyRepository::release(&pool, user_id, &key).await {
+            tracing::error!("Failed to release idempotency key: {e}");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read response body: {e}")))?;
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+
+    // The change itself went through; a retry after a failed save here is
+    // refused as in progress until the key is considered abandoned
+    if let Err(e) = IdempotencyKeyRepository::complete(
+        &pool,
+        user_id,
+        &key,
+        parts.status.as_u16() as i16,
+        content_type,
+        &body,
+    )
+    .await
+    {
+        tracing::error!("Failed to store idempotent response: {e}");
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
@@ -1,9 +1,17 @@
-mod admin;
 mod auth;
+mod client_info;
+mod idempotency;
+mod permission;
 mod rate_limit;
 pub mod request_id;
+mod scope;
+mod verified_email;
 
-pub use admin::admin_middleware;
-pub use auth::{AuthUser, auth_middleware};
+pub use auth::{AuthUser, Credential, auth_middleware};
+pub use client_info::{ClientInfo, client_ip_middleware};
+pub use idempotency::{idempotency_middleware, IDEMPOTENCY_KEY_HEADER};
+pub use permission::{perm, Authorized, RequiredPermission};
 pub use rate_limit::{auth_rate_limiter, general_rate_limiter};
 pub use request_id::request_id_middleware;
+pub use scope::{require_scope, require_session};
+pub use verified_email::{require_verified_email, require_verified_email_for_workouts};
@@ -0,0 +1,107 @@
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use sqlx::PgPool;
+use std::marker::PhantomData;
+
+use crate::cache;
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::{Permission, Role};
+use crate::repositories::{MfaRepository, RoleRepository};
+
+/// Names the permission an [`Authorized`] handler requires.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+/// Marker types for [`Authorized`], one per [`Permission`].
+pub mod perm {
+    use super::RequiredPermission;
+    use crate::models::Permission;
+
+    macro_rules! permission_markers {
+        ($($name:ident),* $(,)?) => {
+            $(
+                pub struct $name;
+
+                impl RequiredPermission for $name {
+                    const PERMISSION: Permission = Permission::$name;
+                }
+            )*
+        };
+    }
+
+    permission_markers!(
+        UsersRead,
+        UsersManage,
+        UsersDelete,
+        RolesManage,
+        MetricsRead,
+        ExercisesModerate,
+    );
+}
+
+/// Handler guard for staff endpoints: `Authorized<perm::UsersDelete>` only
+/// extracts if one of the caller's roles grants that permission. Must run
+/// inside `auth_middleware`.
+///
+/// Personal access tokens are always refused, and with `auth.require_admin_mfa`
+/// so is any caller who hasn't enrolled in two-factor authentication.
+pub struct Authorized<P> {
+    pub user: AuthUser,
+    pub roles: Vec<Role>,
+    _permission: PhantomData<P>,
+}
+
+impl<S, P> FromRequestParts<S> for Authorized<P>
+where
+    S: Send + Sync,
+    P: RequiredPermission,
+    PgPool: FromRef<S>,
+    Settings: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = parts
+            .extensions
+            .get::<AuthUser>()
+            .cloned()
+            .ok_or(AppError::Unauthorized)?;
+
+        if user.is_access_token() {
+            return Err(AppError::Forbidden);
+        }
+
+        let pool = PgPool::from_ref(state);
+        let roles = roles_for_user(&pool, &user).await?;
+        if !roles.iter().any(|r| r.grants(P::PERMISSION)) {
+            return Err(AppError::Forbidden);
+        }
+
+        if Settings::from_ref(state).auth.require_admin_mfa
+            && !MfaRepository::is_enabled(&pool, user.user_id).await?
+        {
+            return Err(AppError::MfaRequired);
+        }
+
+        Ok(Self {
+            user,
+            roles,
+            _permission: PhantomData,
+        })
+    }
+}
+
+async fn roles_for_user(pool: &PgPool, user: &AuthUser) -> Result<Vec<Role>, AppError> {
+    if let Some(cached) = cache::get_roles(user.user_id) {
+        return Ok(cached);
+    }
+
+    let roles = RoleRepository::find_for_user(pool, user.user_id).await?;
+    cache::set_roles(user.user_id, roles.clone());
+    Ok(roles)
+}
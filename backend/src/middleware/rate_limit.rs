@@ -1,13 +1,14 @@
 use axum::http;
 use governor::middleware::NoOpMiddleware;
-use std::net::SocketAddr;
 use tower_governor::{
     governor::GovernorConfigBuilder,
     key_extractor::KeyExtractor,
     GovernorError, GovernorLayer,
 };
 
-/// Custom key extractor that uses X-Forwarded-For header, peer IP, or falls back to localhost
+use crate::middleware::client_info::client_ip;
+
+/// Custom key extractor keyed on the client IP resolved by `client_ip_middleware`
 #[derive(Clone)]
 pub struct SmartIpKeyExtractor;
 
@@ -15,31 +16,7 @@ impl KeyExtractor for SmartIpKeyExtractor {
     type Key = String;
 
     fn extract<T>(&self, req: &http::Request<T>) -> Result<Self::Key, GovernorError> {
-        // Try X-Forwarded-For header first (for reverse proxy setups)
-        if let Some(forwarded) = req.headers().get("x-forwarded-for") {
-            if let Ok(value) = forwarded.to_str() {
-                if let Some(ip) = value.split(',').next() {
-                    let ip_str: String = ip.trim().to_string();
-                    return Ok(ip_str);
-                }
-            }
-        }
-
-        // Try X-Real-IP header
-        if let Some(real_ip) = req.headers().get("x-real-ip") {
-            if let Ok(value) = real_ip.to_str() {
-                let ip_str: String = value.trim().to_string();
-                return Ok(ip_str);
-            }
-        }
-
-        // Try to get peer IP from connection info
-        if let Some(addr) = req.extensions().get::<axum::extract::ConnectInfo<SocketAddr>>() {
-            return Ok(addr.0.ip().to_string());
-        }
-
-        // Fallback to localhost for local development
-        Ok("127.0.0.1".to_string())
+        Ok(client_ip(req.extensions()).to_string())
     }
 }
 
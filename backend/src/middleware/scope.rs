@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::models::ApiResource;
+
+/// Checks that a personal access token carries a scope for the route group's
+/// resource: `read` for GET/HEAD, `write` for anything else. Session logins
+/// pass through. Must be layered inside `auth_middleware`.
+pub async fn require_scope(
+    State(resource): State<ApiResource>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let write = !matches!(*request.method(), Method::GET | Method::HEAD);
+    let auth_user = request
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or(AppError::Unauthorized)?;
+
+    if !auth_user.can_access(resource, write) {
+        return Err(AppError::InsufficientScope(format!(
+            "{}:{}",
+            resource.as_str(),
+            if write { "write" } else { "read" }
+        )));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Keeps personal access tokens out of account management (passwords,
+/// sessions, 2FA and the tokens themselves).
+pub async fn require_session(request: Request, next: Next) -> Result<Response, AppError> {
+    let is_access_token = request
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or(AppError::Unauthorized)?
+        .is_access_token();
+
+    if is_access_token {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(next.run(request).await)
+}
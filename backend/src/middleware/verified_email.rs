@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::{Settings, UnverifiedAccess};
+use crate::error::AppError;
+use crate::middleware::AuthUser;
+use crate::repositories::UserRepository;
+
+/// Blocks unverified accounts unless `auth.unverified_access` is `full`.
+/// Must be layered inside `auth_middleware`.
+pub async fn require_verified_email(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if settings.auth.unverified_access != UnverifiedAccess::Full {
+        ensure_verified(&pool, auth_user_id(&request)?).await?;
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Workout logging stays open to unverified accounts unless access is `none`.
+pub async fn require_verified_email_for_workouts(
+    State(pool): State<PgPool>,
+    State(settings): State<Settings>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if settings.auth.unverified_access == UnverifiedAccess::None {
+        ensure_verified(&pool, auth_user_id(&request)?).await?;
+    }
+
+    Ok(next.run(request).await)
+}
+
+fn auth_user_id(request: &Request) -> Result<Uuid, AppError> {
+    request
+        .extensions()
+        .get::<AuthUser>()
+        .map(|u| u.user_id)
+        .ok_or(AppError::Unauthorized)
+}
+
+async fn ensure_verified(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+    if !UserRepository::is_email_verified(pool, user_id).await? {
+        return Err(AppError::EmailNotVerified);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl PersonalAccessToken {
+    /// Stored scopes, skipping any that are no longer recognised.
+    pub fn parsed_scopes(&self) -> Vec<TokenScope> {
+        self.scopes.iter().filter_map(|s| TokenScope::parse(s)).collect()
+    }
+}
+
+/// Areas of the API a personal access token can be scoped to. Each one is a
+/// route group in `routes::api::create_router`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiResource {
+    Workouts,
+    Exercises,
+    Templates,
+    Programs,
+    BodyStats,
+    Stats,
+    Settings,
+}
+
+impl ApiResource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiResource::Workouts => "workouts",
+            ApiResource::Exercises => "exercises",
+            ApiResource::Templates => "templates",
+            ApiResource::Programs => "programs",
+            ApiResource::BodyStats => "body-stats",
+            ApiResource::Stats => "stats",
+            ApiResource::Settings => "settings",
+        }
+    }
+}
+
+/// A `resource:access` grant. Write scopes also allow reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum TokenScope {
+    #[serde(rename = "workouts:read")]
+    WorkoutsRead,
+    #[serde(rename = "workouts:write")]
+    WorkoutsWrite,
+    #[serde(rename = "exercises:read")]
+    ExercisesRead,
+    #[serde(rename = "exercises:write")]
+    ExercisesWrite,
+    #[serde(rename = "templates:read")]
+    TemplatesRead,
+    #[serde(rename = "templates:write")]
+    TemplatesWrite,
+    #[serde(rename = "programs:read")]
+    ProgramsRead,
+    #[serde(rename = "programs:write")]
+    ProgramsWrite,
+    #[serde(rename = "body-stats:read")]
+    BodyStatsRead,
+    #[serde(rename = "body-stats:write")]
+    BodyStatsWrite,
+    #[serde(rename = "stats:read")]
+    StatsRead,
+    #[serde(rename = "settings:read")]
+    SettingsRead,
+    #[serde(rename = "settings:write")]
+    SettingsWrite,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 13] = [
+        TokenScope::WorkoutsRead,
+        TokenScope::WorkoutsWrite,
+        TokenScope::ExercisesRead,
+        TokenScope::ExercisesWrite,
+        TokenScope::TemplatesRead,
+        TokenScope::TemplatesWrite,
+        TokenScope::ProgramsRead,
+        TokenScope::ProgramsWrite,
+        TokenScope::BodyStatsRead,
+        TokenScope::BodyStatsWrite,
+        TokenScope::StatsRead,
+        TokenScope::SettingsRead,
+        TokenScope::SettingsWrite,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::WorkoutsRead => "workouts:read",
+            TokenScope::WorkoutsWrite => "workouts:write",
+            TokenScope::ExercisesRead => "exercises:read",
+            TokenScope::ExercisesWrite => "exercises:write",
+            TokenScope::TemplatesRead => "templates:read",
+            TokenScope::TemplatesWrite => "templates:write",
+            TokenScope::ProgramsRead => "programs:read",
+            TokenScope::ProgramsWrite => "programs:write",
+            TokenScope::BodyStatsRead => "body-stats:read",
+            TokenScope::BodyStatsWrite => "body-stats:write",
+            TokenScope::StatsRead => "stats:read",
+            TokenScope::SettingsRead => "settings:read",
+            TokenScope::SettingsWrite => "settings:write",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    pub fn resource(&self) -> ApiResource {
+        match self {
+            TokenScope::WorkoutsRead | TokenScope::WorkoutsWrite => ApiResource::Workouts,
+            TokenScope::ExercisesRead | TokenScope::ExercisesWrite => ApiResource::Exercises,
+            TokenScope::TemplatesRead | TokenScope::TemplatesWrite => ApiResource::Templates,
+            TokenScope::ProgramsRead | TokenScope::ProgramsWrite => ApiResource::Programs,
+            TokenScope::BodyStatsRead | TokenScope::BodyStatsWrite => ApiResource::BodyStats,
+            TokenScope::StatsRead => ApiResource::Stats,
+            TokenScope::SettingsRead | TokenScope::SettingsWrite => ApiResource::Settings,
+        }
+    }
+
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            TokenScope::WorkoutsWrite
+                | TokenScope::ExercisesWrite
+                | TokenScope::TemplatesWrite
+                | TokenScope::ProgramsWrite
+                | TokenScope::BodyStatsWrite
+                | TokenScope::SettingsWrite
+        )
+    }
+
+    pub fn grants(&self, resource: ApiResource, write: bool) -> bool {
+        self.resource() == resource && (self.is_write() || !write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_strings_round_trip() {
+        for scope in TokenScope::ALL {
+            assert_eq!(TokenScope::parse(scope.as_str()), Some(scope));
+            assert_eq!(
+                serde_json::to_string(&scope).unwrap(),
+                format!("\"{}\"", scope.as_str())
+            );
+            assert!(scope.as_str().starts_with(scope.resource().as_str()));
+        }
+        assert_eq!(TokenScope::parse("workouts:admin"), None);
+    }
+
+    #[test]
+    fn test_write_scope_implies_read() {
+        assert!(TokenScope::BodyStatsWrite.grants(ApiResource::BodyStats, false));
+        assert!(TokenScope::BodyStatsWrite.grants(ApiResource::BodyStats, true));
+        assert!(TokenScope::BodyStatsRead.grants(ApiResource::BodyStats, false));
+        assert!(!TokenScope::BodyStatsRead.grants(ApiResource::BodyStats, true));
+        assert!(!TokenScope::WorkoutsWrite.grants(ApiResource::Templates, false));
+    }
+}
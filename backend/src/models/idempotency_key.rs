@@ -0,0 +1,15 @@
+use sqlx::FromRow;
+
+/// A write sent with an `Idempotency-Key` header and, once it finished, the
+/// response to replay.
+#[derive(Debug, Clone, FromRow)]
+pub struct IdempotencyKey {
+    pub method: String,
+    pub path: String,
+    /// SHA-256 of the request body, hex-encoded
+    pub request_hash: String,
+    /// `None` while the first request is still running
+    pub status_code: Option<i16>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+}
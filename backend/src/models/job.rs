@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Every kind of background job the worker knows how to run. The variant name
+/// is stored as the job's `kind` and its fields as the JSON `payload`, so adding
+/// a variant (and its arm in the worker) is all it takes to register a new job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum Job {
+    DetectPersonalRecords {
+        workout_id: Uuid,
+        user_id: Uuid,
+    },
+    /// Rebuilds records from history; `None` means every exercise the user has
+    RecomputePersonalRecords {
+        user_id: Uuid,
+        exercise_template_id: Option<String>,
+    },
+}
+
+impl Job {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Job::DetectPersonalRecords { .. } => "detect_personal_records",
+            Job::RecomputePersonalRecords { .. } => "recompute_personal_records",
+        }
+    }
+
+    /// Splits the job into its stored `(kind, payload)` columns.
+    pub fn to_parts(&self) -> serde_json::Result<(&'static str, serde_json::Value)> {
+        let mut value = serde_json::to_value(self)?;
+        let payload = value
+            .get_mut("payload")
+            .map(serde_json::Value::take)
+            .unwrap_or(serde_json::Value::Null);
+        Ok((self.kind(), payload))
+    }
+
+    /// Rebuilds a job from its stored columns. Fails for kinds this build
+    /// doesn't know about or payloads that no longer match the variant.
+    pub fn from_parts(kind: &str, payload: serde_json::Value) -> serde_json::Result<Self> {
+        serde_json::from_value(serde_json::json!({ "kind": kind, "payload": payload }))
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct JobRecord {
+    pub id: i64,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Includes the current attempt
+    pub attempts: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_round_trips_through_parts() {
+        let job = Job::DetectPersonalRecords {
+            workout_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+        };
+
+        let (kind, payload) = job.to_parts().unwrap();
+        assert_eq!(kind, "detect_personal_records");
+        assert!(payload.get("workout_id").is_some());
+        assert_eq!(Job::from_parts(kind, payload).unwrap(), job);
+    }
+
+    #[test]
+    fn test_kind_matches_serialized_tag() {
+        let jobs = [
+            Job::DetectPersonalRecords {
+                workout_id: Uuid::nil(),
+                user_id: Uuid::nil(),
+            },
+            Job::RecomputePersonalRecords {
+                user_id: Uuid::nil(),
+                exercise_template_id: None,
+            },
+        ];
+
+        for job in jobs {
+            assert_eq!(serde_json::to_value(&job).unwrap()["kind"], job.kind());
+        }
+    }
+
+    #[test]
+    fn test_unknown_kind_is_rejected() {
+        assert!(Job::from_parts("send_newsletter", serde_json::json!({})).is_err());
+        assert!(Job::from_parts("detect_personal_records", serde_json::json!({})).is_err());
+    }
+}
@@ -1,17 +1,40 @@
+mod access_token;
 mod body_stats;
+mod email_verification_token;
 mod exercise;
+mod idempotency_key;
+mod job;
+mod mfa;
+mod password_reset_token;
 mod personal_record;
 mod program;
+mod refresh_token;
+mod role;
+mod session;
 mod settings;
 mod template;
 mod user;
 mod workout;
 
+pub use access_token::{ApiResource, PersonalAccessToken, TokenScope};
 pub use body_stats::{BodyMeasurement, BodyStatsGoal, GoalType, MeasurementType};
+pub use email_verification_token::EmailVerificationToken;
 pub use exercise::{Equipment, ExerciseCategory, ExerciseTemplate, MuscleGroup};
-pub use personal_record::{PersonalRecord, RecordType};
+pub use idempotency_key::IdempotencyKey;
+pub use job::{Job, JobRecord};
+pub use mfa::UserTotp;
+pub use password_reset_token::PasswordResetToken;
+pub use personal_record::{NewPersonalRecord, PersonalRecord, RecordType};
 pub use program::{ProgramWorkout, WorkoutProgram};
-pub use settings::{BarbellType, MeasurementUnit, PlateCalculatorSettings, PlateConfig, Theme, UserSettings, WeightUnit};
+pub use refresh_token::RefreshToken;
+pub use role::{Permission, Role};
+pub use session::Session;
+pub use settings::{
+    BarbellType, MeasurementUnit, OneRepMaxFormula, PlateCalculatorSettings, PlateConfig, Theme,
+    UserSettings, WeekStart, WeightUnit,
+};
 pub use template::{TemplateExercise, TemplateExerciseRow, TemplateSet, WorkoutTemplate};
 pub use user::User;
-pub use workout::{Workout, WorkoutExercise, WorkoutSet, WorkoutStatus};
+pub use workout::{
+    ExerciseHistorySet, SetType, Workout, WorkoutExercise, WorkoutSet, WorkoutStatus,
+};
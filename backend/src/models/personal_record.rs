@@ -4,16 +4,66 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "record_type", rename_all = "kebab-case")]
 #[serde(rename_all = "kebab-case")]
 pub enum RecordType {
     MaxWeight,
     MaxReps,
+    /// Best single set by weight x reps
     MaxVolume,
+    /// Best total weight x reps for the exercise across one workout
+    MaxSessionVolume,
     #[sqlx(rename = "estimated-1rm")]
     #[serde(rename = "estimated-1rm")]
     Estimated1rm,
+    /// Heaviest weight for exactly `reps` reps
+    RepMax,
+    /// Fastest time over 1 km, pro-rated from any set at least that long
+    #[sqlx(rename = "fastest-1k")]
+    #[serde(rename = "fastest-1k")]
+    Fastest1k,
+    #[sqlx(rename = "fastest-5k")]
+    #[serde(rename = "fastest-5k")]
+    Fastest5k,
+    #[sqlx(rename = "fastest-10k")]
+    #[serde(rename = "fastest-10k")]
+    Fastest10k,
+    LongestDistance,
+    /// Seconds per km
+    BestPace,
+    /// Longest timed hold, e.g. a plank
+    LongestHold,
+    /// Heaviest weight carried over any distance
+    HeaviestCarry,
+}
+
+impl RecordType {
+    /// Rep-max records are tracked separately for each rep count, so a 3RM
+    /// never competes with a 5RM.
+    pub fn is_per_rep_count(self) -> bool {
+        matches!(self, RecordType::RepMax)
+    }
+
+    /// Times and paces improve by going down.
+    pub fn lower_is_better(self) -> bool {
+        matches!(
+            self,
+            RecordType::Fastest1k
+                | RecordType::Fastest5k
+                | RecordType::Fastest10k
+                | RecordType::BestPace
+        )
+    }
+
+    /// Whether `value` is a new record over `current`.
+    pub fn beats(self, value: f64, current: f64) -> bool {
+        if self.lower_is_better() {
+            value < current
+        } else {
+            value > current
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -27,6 +77,45 @@ pub struct PersonalRecord {
     pub type_: RecordType,
     pub value: f64,
     pub reps: Option<i32>,
+    pub distance_meters: Option<f64>,
     pub achieved_at: DateTime<Utc>,
     pub workout_id: Uuid,
+    /// The best this record beat, if there was one
+    pub previous_value: Option<f64>,
+    /// When a better record replaced this one; `None` while it is current
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
+/// A record derived from workout history, before it is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewPersonalRecord {
+    pub exercise_name: String,
+    pub record_type: RecordType,
+    pub value: f64,
+    pub reps: Option<i32>,
+    pub distance_meters: Option<f64>,
+    pub achieved_at: DateTime<Utc>,
+    pub workout_id: Uuid,
+    pub previous_value: Option<f64>,
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_times_and_paces_improve_downwards() {
+        assert!(RecordType::Fastest5k.beats(1500.0, 1520.0));
+        assert!(!RecordType::BestPace.beats(300.0, 290.0));
+        assert!(RecordType::LongestHold.beats(90.0, 60.0));
+        assert!(!RecordType::MaxWeight.beats(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_record_type_serializes_kebab_case() {
+        assert_eq!(serde_json::to_value(RecordType::Fastest10k).unwrap(), "fastest-10k");
+        assert_eq!(serde_json::to_value(RecordType::HeaviestCarry).unwrap(), "heaviest-carry");
+        assert_eq!(serde_json::to_value(RecordType::Estimated1rm).unwrap(), "estimated-1rm");
+    }
 }
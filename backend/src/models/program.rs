@@ -17,6 +17,7 @@ pub struct WorkoutProgram {
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub version: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Something a staff member can do outside their own account. Checked by
+/// handlers through the `Authorized` extractor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Permission {
+    /// List users and view their account details
+    #[serde(rename = "users:read")]
+    UsersRead,
+    /// Support actions on an account, such as lifting a login lockout
+    #[serde(rename = "users:manage")]
+    UsersManage,
+    #[serde(rename = "users:delete")]
+    UsersDelete,
+    /// Grant and revoke roles
+    #[serde(rename = "roles:manage")]
+    RolesManage,
+    #[serde(rename = "metrics:read")]
+    MetricsRead,
+    /// Review and remove users' custom exercises
+    #[serde(rename = "exercises:moderate")]
+    ExercisesModerate,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 6] = [
+        Permission::UsersRead,
+        Permission::UsersManage,
+        Permission::UsersDelete,
+        Permission::RolesManage,
+        Permission::MetricsRead,
+        Permission::ExercisesModerate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::UsersRead => "users:read",
+            Permission::UsersManage => "users:manage",
+            Permission::UsersDelete => "users:delete",
+            Permission::RolesManage => "roles:manage",
+            Permission::MetricsRead => "metrics:read",
+            Permission::ExercisesModerate => "exercises:moderate",
+        }
+    }
+}
+
+/// A named bundle of permissions that can be granted to a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Everything
+    Admin,
+    /// Helps users with their accounts, but can't delete them or hand out roles
+    Support,
+    /// Keeps the exercise library clean
+    Moderator,
+    /// Marks coaching accounts; grants no staff-area permissions of its own
+    Coach,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Support, Role::Moderator, Role::Coach];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Support => "support",
+            Role::Moderator => "moderator",
+            Role::Coach => "coach",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == value)
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Admin => &Permission::ALL,
+            Role::Support => &[
+                Permission::UsersRead,
+                Permission::UsersManage,
+                Permission::MetricsRead,
+            ],
+            Role::Moderator => &[Permission::ExercisesModerate],
+            Role::Coach => &[],
+        }
+    }
+
+    pub fn grants(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+
+    /// Whether the role opens any staff endpoint, which is what
+    /// `auth.require_admin_mfa` guards.
+    pub fn is_staff(&self) -> bool {
+        Permission::ALL.iter().any(|p| self.grants(*p))
+    }
+
+    /// Stored role names, skipping any that are no longer recognised.
+    pub fn parse_all(values: &[String]) -> Vec<Role> {
+        values.iter().filter_map(|v| Role::parse(v)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_names_round_trip() {
+        for role in Role::ALL {
+            assert_eq!(Role::parse(role.as_str()), Some(role));
+            assert_eq!(
+                serde_json::to_value(role).unwrap(),
+                serde_json::json!(role.as_str())
+            );
+        }
+        assert_eq!(Role::parse("superuser"), None);
+    }
+
+    #[test]
+    fn test_permission_names_match_serde() {
+        for permission in Permission::ALL {
+            assert_eq!(
+                serde_json::to_value(permission).unwrap(),
+                serde_json::json!(permission.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_support_can_view_but_not_delete_users() {
+        assert!(Role::Support.grants(Permission::UsersRead));
+        assert!(Role::Support.grants(Permission::UsersManage));
+        assert!(!Role::Support.grants(Permission::UsersDelete));
+        assert!(!Role::Support.grants(Permission::RolesManage));
+        assert!(!Role::Moderator.grants(Permission::UsersRead));
+        assert!(Permission::ALL.iter().all(|p| Role::Admin.grants(*p)));
+    }
+
+    #[test]
+    fn test_coach_is_not_staff() {
+        assert!(Role::Admin.is_staff());
+        assert!(Role::Support.is_staff());
+        assert!(Role::Moderator.is_staff());
+        assert!(!Role::Coach.is_staff());
+    }
+}
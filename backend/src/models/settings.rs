@@ -1,3 +1,5 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
@@ -82,6 +84,50 @@ pub enum Theme {
     System,
 }
 
+/// Formula used for estimated 1RMs; `Average` is the mean of the others.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "one_rep_max_formula", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OneRepMaxFormula {
+    Brzycki,
+    Epley,
+    Lombardi,
+    #[sqlx(rename = "oconner")]
+    #[serde(rename = "oconner")]
+    OConner,
+    Wathan,
+    Mayhew,
+    Average,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "week_start", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// First day of the week containing `date`.
+    pub fn week_of(self, date: NaiveDate) -> NaiveDate {
+        let days_in = match self {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        };
+        date - Duration::days(days_in as i64)
+    }
+
+    /// Days to shift a date forward so Postgres' Monday-based
+    /// `DATE_TRUNC('week', ...)` lands on this week start once shifted back.
+    pub fn sql_offset_days(self) -> i32 {
+        match self {
+            WeekStart::Monday => 0,
+            WeekStart::Sunday => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct UserSettings {
@@ -97,6 +143,23 @@ pub struct UserSettings {
     #[sqlx(json)]
     pub plate_calculator: PlateCalculatorSettings,
     pub compact_mode: bool,
+    pub one_rep_max_formula: OneRepMaxFormula,
+    /// IANA timezone name, e.g. `America/Los_Angeles`
+    pub timezone: String,
+    pub week_start: WeekStart,
+    pub version: i32,
+}
+
+impl UserSettings {
+    /// The user's timezone; UTC if the stored name is no longer recognised.
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// Today's date where the user is.
+    pub fn today(&self) -> NaiveDate {
+        Utc::now().with_timezone(&self.tz()).date_naive()
+    }
 }
 
 impl Default for UserSettings {
@@ -113,6 +176,10 @@ impl Default for UserSettings {
             sound_on_timer_end: true,
             plate_calculator: PlateCalculatorSettings::default(),
             compact_mode: false,
+            one_rep_max_formula: OneRepMaxFormula::Brzycki,
+            timezone: "UTC".to_string(),
+            week_start: WeekStart::Monday,
+            version: 1,
         }
     }
 }
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::models::SetType;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TemplateSet {
@@ -12,6 +14,12 @@ pub struct TemplateSet {
     pub is_warmup: bool,
     pub target_distance_meters: Option<i32>,
     pub target_duration_seconds: Option<i32>,
+    pub set_type: SetType,
+    /// Set number of the main set this drop or mini-set belongs to
+    pub parent_set_number: Option<i32>,
+    pub target_rir: Option<i16>,
+    pub tempo: Option<String>,
+    pub is_unilateral: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -50,6 +58,7 @@ pub struct WorkoutTemplate {
     pub created_at: DateTime<Utc>,
     pub last_used_at: Option<DateTime<Utc>>,
     pub usage_count: i32,
+    pub version: i32,
     #[sqlx(skip)]
     pub tags: Option<Vec<String>>,
 }
@@ -15,6 +15,53 @@ pub enum WorkoutStatus {
     Cancelled,
 }
 
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema,
+)]
+#[sqlx(type_name = "set_type", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum SetType {
+    #[default]
+    Normal,
+    /// As many reps as possible
+    Amrap,
+    Drop,
+    RestPause,
+    Cluster,
+    Failure,
+}
+
+impl SetType {
+    /// Whether the type is logged as a main set with linked sub-sets
+    pub fn has_sub_sets(self) -> bool {
+        matches!(self, SetType::Drop | SetType::RestPause | SetType::Cluster)
+    }
+
+    /// Checks the sub-set links of one exercise's sets, given in order as each
+    /// set's type and its parent's 1-based position. A sub-set must come after
+    /// its parent, which must be a main set of a type with sub-sets.
+    pub fn check_sub_sets(
+        sets: impl IntoIterator<Item = (SetType, Option<i32>)>,
+    ) -> Result<(), &'static str> {
+        let sets: Vec<(SetType, Option<i32>)> = sets.into_iter().collect();
+        for (index, (_, parent)) in sets.iter().enumerate() {
+            let Some(parent) = parent else { continue };
+            let (parent_type, grandparent) = usize::try_from(*parent - 1)
+                .ok()
+                .filter(|p| *p < index)
+                .map(|p| sets[p])
+                .ok_or("A sub-set must come after its parent set")?;
+            if grandparent.is_some() {
+                return Err("A sub-set can't have sub-sets of its own");
+            }
+            if !parent_type.has_sub_sets() {
+                return Err("Only drop, rest-pause and cluster sets have sub-sets");
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkoutSet {
@@ -34,6 +81,36 @@ pub struct WorkoutSet {
     pub calories: Option<i32>,
     pub target_distance_meters: Option<i32>,
     pub target_duration_seconds: Option<i32>,
+    /// Seconds since the previous completed set of the exercise, less this
+    /// set's duration and paused time; `None` for the first
+    pub rest_seconds_actual: Option<i32>,
+    pub set_type: SetType,
+    /// The main set this drop or mini-set belongs to
+    pub parent_set_id: Option<Uuid>,
+    /// Prescribed reps in reserve
+    pub target_rir: Option<i16>,
+    /// Reps in reserve, as rated after the set
+    pub rir: Option<i16>,
+    /// Four-phase tempo such as `3-1-1-0`
+    pub tempo: Option<String>,
+    /// Each side is worked separately; `actual_reps` is per side
+    pub is_unilateral: bool,
+    pub actual_reps_left: Option<i32>,
+    pub actual_reps_right: Option<i32>,
+    /// Reps that count towards volume, both sides included (generated by the
+    /// database)
+    pub volume_reps: Option<i32>,
+    pub version: i32,
+}
+
+/// A set from a completed workout, with the workout context records need.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExerciseHistorySet {
+    pub workout_id: Uuid,
+    pub workout_completed_at: DateTime<Utc>,
+    pub exercise_name: String,
+    #[sqlx(flatten)]
+    pub set: WorkoutSet,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -46,6 +123,7 @@ pub struct WorkoutExercise {
     pub notes: Option<String>,
     pub order_index: i32,
     pub superset_id: Option<Uuid>,
+    pub version: i32,
     #[sqlx(skip)]
     pub exercise_category: Option<ExerciseCategory>,
 }
@@ -66,5 +144,6 @@ pub struct Workout {
     pub template_id: Option<Uuid>,
     pub notes: Option<String>,
     pub tags: Vec<String>,
+    pub version: i32,
 }
 
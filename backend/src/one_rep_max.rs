@@ -0,0 +1,243 @@
+//! Estimated one-rep max (e1RM) from a submaximal set. Personal records, progress
+//! charts, overload suggestions and plateau alerts all estimate through here, so a
+//! user's chosen formula gives the same numbers everywhere.
+
+use crate::models::OneRepMaxFormula;
+
+/// Rep-based formulas drift badly past this many reps (Brzycki diverges at 37)
+pub const MAX_REPS: i32 = 20;
+
+/// Lowest RPE that is rated reliably enough to read off the chart
+const MIN_CHART_RPE: i16 = 7;
+
+/// Percentage of 1RM by reps to failure (index 0 = a true single), from the
+/// standard RPE/RIR chart
+const RPE_CHART: [f64; 12] = [
+    1.000, 0.955, 0.922, 0.892, 0.863, 0.837, 0.811, 0.786, 0.762, 0.739, 0.707, 0.680,
+];
+
+/// The formulas `OneRepMaxFormula::Average` takes the mean of
+const AVERAGED: [OneRepMaxFormula; 6] = [
+    OneRepMaxFormula::Brzycki,
+    OneRepMaxFormula::Epley,
+    OneRepMaxFormula::Lombardi,
+    OneRepMaxFormula::OConner,
+    OneRepMaxFormula::Wathan,
+    OneRepMaxFormula::Mayhew,
+];
+
+/// e1RM for `weight` x `reps` by `formula`. A single is its own 1RM; `None`
+/// outside 1..=`MAX_REPS`.
+pub fn estimate(formula: OneRepMaxFormula, weight: f64, reps: i32) -> Option<f64> {
+    if !(1..=MAX_REPS).contains(&reps) {
+        return None;
+    }
+    if reps == 1 {
+        return Some(weight);
+    }
+
+    let r = reps as f64;
+    let e1rm = match formula {
+        OneRepMaxFormula::Brzycki => weight * (36.0 / (37.0 - r)),
+        OneRepMaxFormula::Epley => weight * (1.0 + r / 30.0),
+        OneRepMaxFormula::Lombardi => weight * r.powf(0.10),
+        OneRepMaxFormula::OConner => weight * (1.0 + 0.025 * r),
+        OneRepMaxFormula::Wathan => 100.0 * weight / (48.8 + 53.8 * (-0.075 * r).exp()),
+        OneRepMaxFormula::Mayhew => 100.0 * weight / (52.2 + 41.9 * (-0.055 * r).exp()),
+        OneRepMaxFormula::Average => {
+            let total: f64 = AVERAGED
+                .iter()
+                .filter_map(|f| estimate(*f, weight, reps))
+                .sum();
+            total / AVERAGED.len() as f64
+        }
+    };
+
+    Some(e1rm)
+}
+
+/// e1RM for a logged set. When the set has an RPE of at least 7 the reps left
+/// in reserve (10 - RPE) are added and the RPE chart is used instead, since a
+/// set of 5 at RPE 7 says more about the lifter than one taken to failure.
+pub fn estimate_set(
+    formula: OneRepMaxFormula,
+    weight: f64,
+    reps: i32,
+    rpe: Option<i16>,
+) -> Option<f64> {
+    if let Some(rpe) = rpe.filter(|r| (MIN_CHART_RPE..=10).contains(r)) {
+        let reps_to_failure = reps + i32::from(10 - rpe);
+        if reps >= 1 && reps_to_failure as usize <= RPE_CHART.len() {
+            return Some(weight / RPE_CHART[reps_to_failure as usize - 1]);
+        }
+    }
+
+    estimate(formula, weight, reps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [OneRepMaxFormula; 7] = [
+        OneRepMaxFormula::Brzycki,
+        OneRepMaxFormula::Epley,
+        OneRepMaxFormula::Lombardi,
+        OneRepMaxFormula::OConner,
+        OneRepMaxFormula::Wathan,
+        OneRepMaxFormula::Mayhew,
+        OneRepMaxFormula::Average,
+    ];
+
+    fn brzycki(weight: f64, reps: i32) -> Option<f64> {
+        estimate(OneRepMaxFormula::Brzycki, weight, reps)
+    }
+
+    // ==================== Brzycki ====================
+
+    #[test]
+    fn test_brzycki_single_rep() {
+        // At 1 rep, e1RM should equal actual weight
+        let e1rm = brzycki(100.0, 1).unwrap();
+        assert!((e1rm - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_brzycki_five_reps() {
+        // 100kg x 5 reps = 100 * (36 / 32) = 112.5kg
+        let e1rm = brzycki(100.0, 5).unwrap();
+        assert!((e1rm - 100.0 * (36.0 / 32.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_brzycki_ten_reps() {
+        // 100kg x 10 reps = 100 * (36 / 27) ≈ 133.33kg
+        let e1rm = brzycki(100.0, 10).unwrap();
+        assert!((e1rm - 100.0 * (36.0 / 27.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_brzycki_twelve_reps() {
+        let e1rm = brzycki(100.0, 12).unwrap();
+        assert!((e1rm - 100.0 * (36.0 / 25.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_brzycki_real_world_example() {
+        // Real-world: 140kg x 3 reps ≈ 148.2kg
+        let e1rm = brzycki(140.0, 3).unwrap();
+        assert!((e1rm - 140.0 * (36.0 / 34.0)).abs() < 0.01);
+        assert!(e1rm > 140.0 && e1rm < 160.0);
+    }
+
+    // ==================== Rep range ====================
+
+    #[test]
+    fn test_zero_and_negative_reps_return_none() {
+        for formula in ALL {
+            assert!(estimate(formula, 100.0, 0).is_none());
+            assert!(estimate(formula, 100.0, -5).is_none());
+        }
+    }
+
+    #[test]
+    fn test_reps_past_max_return_none() {
+        for formula in ALL {
+            assert!(estimate(formula, 100.0, MAX_REPS).is_some());
+            assert!(estimate(formula, 100.0, MAX_REPS + 1).is_none());
+        }
+    }
+
+    #[test]
+    fn test_single_is_its_own_max_for_every_formula() {
+        for formula in ALL {
+            assert_eq!(estimate(formula, 142.5, 1), Some(142.5));
+        }
+    }
+
+    #[test]
+    fn test_more_reps_increase_e1rm_for_every_formula() {
+        for formula in ALL {
+            let mut previous = 100.0;
+            for reps in 2..=MAX_REPS {
+                let e1rm = estimate(formula, 100.0, reps).unwrap();
+                assert!(e1rm > previous, "{:?} at {} reps", formula, reps);
+                previous = e1rm;
+            }
+        }
+    }
+
+    // ==================== Other formulas ====================
+
+    #[test]
+    fn test_formulas_at_ten_reps() {
+        let expected = [
+            (OneRepMaxFormula::Epley, 133.33),
+            (OneRepMaxFormula::Lombardi, 125.89),
+            (OneRepMaxFormula::OConner, 125.0),
+            (OneRepMaxFormula::Wathan, 134.75),
+            (OneRepMaxFormula::Mayhew, 130.93),
+        ];
+
+        for (formula, e1rm) in expected {
+            let actual = estimate(formula, 100.0, 10).unwrap();
+            assert!((actual - e1rm).abs() < 0.01, "{:?}: {}", formula, actual);
+        }
+    }
+
+    #[test]
+    fn test_average_is_mean_of_formulas() {
+        let mean = AVERAGED
+            .iter()
+            .map(|f| estimate(*f, 100.0, 8).unwrap())
+            .sum::<f64>()
+            / 6.0;
+
+        let average = estimate(OneRepMaxFormula::Average, 100.0, 8).unwrap();
+        assert!((average - mean).abs() < 1e-9);
+    }
+
+    // ==================== RPE ====================
+
+    #[test]
+    fn test_rpe_ten_single_is_its_own_max() {
+        let e1rm = estimate_set(OneRepMaxFormula::Epley, 100.0, 1, Some(10)).unwrap();
+        assert!((e1rm - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rpe_adds_reps_in_reserve() {
+        // 5 reps at RPE 8 = 7 reps to failure = 81.1%
+        let e1rm = estimate_set(OneRepMaxFormula::Brzycki, 100.0, 5, Some(8)).unwrap();
+        assert!((e1rm - 100.0 / 0.811).abs() < 0.01);
+
+        // Same as 7 reps to failure at RPE 10
+        let at_failure = estimate_set(OneRepMaxFormula::Brzycki, 100.0, 7, Some(10)).unwrap();
+        assert!((e1rm - at_failure).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rpe_chart_ignores_formula() {
+        let brzycki = estimate_set(OneRepMaxFormula::Brzycki, 100.0, 3, Some(9));
+        let oconner = estimate_set(OneRepMaxFormula::OConner, 100.0, 3, Some(9));
+        assert_eq!(brzycki, oconner);
+    }
+
+    #[test]
+    fn test_low_or_missing_rpe_falls_back_to_formula() {
+        let formula = estimate(OneRepMaxFormula::Epley, 100.0, 5);
+
+        assert_eq!(estimate_set(OneRepMaxFormula::Epley, 100.0, 5, None), formula);
+        assert_eq!(estimate_set(OneRepMaxFormula::Epley, 100.0, 5, Some(6)), formula);
+    }
+
+    #[test]
+    fn test_rpe_off_the_chart_falls_back_to_formula() {
+        // 11 reps at RPE 8 = 13 to failure, past the chart
+        assert_eq!(
+            estimate_set(OneRepMaxFormula::Brzycki, 100.0, 11, Some(8)),
+            estimate(OneRepMaxFormula::Brzycki, 100.0, 11)
+        );
+        assert!(estimate_set(OneRepMaxFormula::Brzycki, 100.0, 0, Some(10)).is_none());
+    }
+}
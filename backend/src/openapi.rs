@@ -5,8 +5,8 @@ use crate::dto::*;
 use crate::handlers;
 use crate::models::{
     BarbellType, Equipment, ExerciseCategory, GoalType, MeasurementType, MeasurementUnit,
-    MuscleGroup, PlateCalculatorSettings, PlateConfig, RecordType, Theme, WeightUnit,
-    WorkoutStatus,
+    MuscleGroup, OneRepMaxFormula, Permission, PlateCalculatorSettings, PlateConfig, RecordType,
+    Role, SetType, Theme, TokenScope, WeekStart, WeightUnit, WorkoutStatus,
 };
 
 #[derive(OpenApi)]
@@ -19,20 +19,49 @@ use crate::models::{
     paths(
         // Health
         handlers::health,
+        handlers::jwks,
         // Admin
         handlers::list_admin_users,
         handlers::get_admin_user,
         handlers::delete_admin_user,
         handlers::set_admin_status,
+        handlers::set_user_roles,
+        handlers::list_roles,
+        handlers::unlock_admin_user,
+        handlers::recompute_user_personal_records,
+        handlers::recompute_all_personal_records,
         handlers::get_admin_metrics,
+        handlers::list_admin_exercises,
+        handlers::delete_admin_exercise,
         // Auth
         handlers::register,
         handlers::login,
         handlers::refresh,
+        handlers::forgot_password,
+        handlers::reset_password,
+        handlers::verify_email,
+        handlers::resend_verification_email,
+        handlers::verify_mfa,
+        handlers::get_mfa_status,
+        handlers::setup_totp,
+        handlers::confirm_totp,
+        handlers::disable_totp,
+        handlers::regenerate_recovery_codes,
+        handlers::list_access_tokens,
+        handlers::create_access_token,
+        handlers::revoke_access_token,
+        handlers::export_account,
+        handlers::delete_account,
+        handlers::cancel_account_deletion,
+        handlers::logout,
+        handlers::logout_all,
+        handlers::list_sessions,
+        handlers::revoke_session,
         handlers::me,
         handlers::change_password,
         // Workouts
         handlers::create_workout,
+        handlers::import_workout,
         handlers::list_workouts,
         handlers::get_workout,
         handlers::update_workout,
@@ -40,6 +69,8 @@ use crate::models::{
         handlers::restore_workout,
         handlers::complete_workout,
         handlers::cancel_workout,
+        handlers::pause_workout,
+        handlers::resume_workout,
         // Workout exercises
         handlers::add_exercise,
         handlers::update_exercise,
@@ -92,6 +123,8 @@ use crate::models::{
         handlers::get_weekly_volume,
         handlers::get_muscle_group_distribution,
         handlers::get_exercise_progress,
+        handlers::get_rep_maxes,
+        handlers::get_record_timeline,
         handlers::get_exercises_with_history,
         handlers::get_overload_suggestions,
         handlers::get_plateau_alerts,
@@ -102,6 +135,8 @@ use crate::models::{
         // Settings
         handlers::get_settings,
         handlers::update_settings,
+        handlers::sync,
+        handlers::stream_events,
     ),
     components(
         schemas(
@@ -110,17 +145,29 @@ use crate::models::{
             // Admin
             AdminUserResponse, AdminUserListResponse, AdminUserDetailResponse,
             SetAdminStatusRequest, DailyRegistration, TopUserResponse, AdminMetricsResponse,
+            Role, Permission, SetUserRolesRequest, RoleResponse, RoleListResponse,
+            AdminExerciseResponse, AdminExerciseListResponse, RecomputeQueuedResponse,
             // Error
             ErrorResponse,
             // Auth
             RegisterRequest, LoginRequest, AuthResponse, UserResponse, RefreshRequest, TokenResponse,
-            ChangePasswordRequest,
+            ChangePasswordRequest, ForgotPasswordRequest, ResetPasswordRequest, VerifyEmailRequest,
+            SessionResponse, SessionListResponse, LoginResponse,
+            // MFA
+            MfaStatusResponse, TotpSetupResponse, TotpCodeRequest, RecoveryCodesResponse,
+            DisableTotpRequest, MfaVerifyRequest, MfaChallengeResponse,
+            // Personal access tokens
+            CreateAccessTokenRequest, AccessTokenResponse, CreatedAccessTokenResponse,
+            AccessTokenListResponse, TokenScope,
+            // Account
+            DeleteAccountRequest, AccountDeletionResponse,
             // Workout
             CreateWorkoutRequest, UpdateWorkoutRequest, WorkoutResponse, WorkoutExerciseResponse,
             WorkoutSetResponse, CreateWorkoutExerciseRequest, UpdateWorkoutExerciseRequest,
             CreateSetRequest, UpdateSetRequest, WorkoutListResponse, WorkoutSummaryResponse,
             CreateSupersetRequest, SupersetResponse,
             ReorderExercisesRequest, ExerciseOrderUpdate,
+            ImportWorkoutRequest, ImportWorkoutExercise, ImportWorkoutSet,
             // Exercise
             ExerciseTemplateResponse, CreateExerciseRequest, UpdateExerciseRequest,
             // Template
@@ -139,6 +186,7 @@ use crate::models::{
             DashboardSummary, PersonalRecordResponse, WeeklyVolumeResponse, WeekVolume,
             MuscleGroupDistribution, MuscleGroupData, ExerciseProgressResponse,
             ExerciseHistoryEntry, SetHistoryEntry, PersonalRecordsListResponse,
+            RepMaxTableResponse, RepMaxEntry, RecordTimelineResponse, RecordTimelineSeries,
             ExercisesWithHistoryResponse, ExerciseWithHistorySummary,
             OverloadSuggestionsResponse, ExerciseOverloadSuggestion, SuggestionType, SuggestionConfidence,
             PlateauAlertResponse, ExercisePlateauAlert,
@@ -146,10 +194,13 @@ use crate::models::{
             ConsistencyHeatmapResponse, ConsistencyDay,
             // Settings
             SettingsResponse, UpdateSettingsRequest,
+            // Sync
+            SyncResponse, SyncDeletions,
             // Model enums
-            MuscleGroup, ExerciseCategory, Equipment, WorkoutStatus, GoalType,
+            MuscleGroup, ExerciseCategory, Equipment, WorkoutStatus, SetType, GoalType,
             MeasurementType, RecordType, WeightUnit, MeasurementUnit, Theme,
-            BarbellType, PlateConfig, PlateCalculatorSettings,
+            BarbellType, PlateConfig, PlateCalculatorSettings, OneRepMaxFormula,
+            WeekStart,
         )
     ),
     modifiers(&SecurityAddon),
@@ -170,6 +221,8 @@ use crate::models::{
         (name = "Statistics", description = "Workout statistics and analytics"),
         (name = "Personal Records", description = "Personal records"),
         (name = "Settings", description = "User settings"),
+        (name = "Sync", description = "Offline sync"),
+        (name = "Events", description = "Live workout changes"),
     )
 )]
 pub struct ApiDoc;
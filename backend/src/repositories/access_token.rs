@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::PersonalAccessToken;
+
+pub struct AccessTokenRepository;
+
+impl AccessTokenRepository {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<PersonalAccessToken, AppError> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            INSERT INTO personal_access_tokens (id, user_id, name, token_hash, token_prefix, scopes, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            RETURNING id, user_id, name, token_hash, token_prefix, scopes, expires_at, last_used_at, created_at, revoked_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn list_active(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<PersonalAccessToken>, AppError> {
+        let tokens = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT id, user_id, name, token_hash, token_prefix, scopes, expires_at, last_used_at, created_at, revoked_at
+            FROM personal_access_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Looks up a usable token by hash, bumping `last_used_at` at most once a
+    /// minute like `SessionRepository::touch_active`.
+    pub async fn touch_active(
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, AppError> {
+        let token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            WITH t AS (
+                SELECT id, user_id, name, token_hash, token_prefix, scopes, expires_at, last_used_at, created_at, revoked_at
+                FROM personal_access_tokens
+                WHERE token_hash = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+            ), touched AS (
+                UPDATE personal_access_tokens SET last_used_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM t
+                    WHERE last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute'
+                )
+            )
+            SELECT * FROM t
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Whether the token is still usable, for connections that outlive the
+    /// request that looked it up.
+    pub async fn is_active(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+        let active = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM personal_access_tokens
+                WHERE id = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+            )
+            "#,
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(active)
+    }
+
+    pub async fn revoke(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE personal_access_tokens SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn revoke_all_for_user(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE personal_access_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM personal_access_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
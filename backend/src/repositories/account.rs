@@ -0,0 +1,100 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+pub struct AccountRepository;
+
+impl AccountRepository {
+    /// Everything stored about a user as one JSON document. Rows are exported
+    /// with `to_jsonb`, so keys mirror the table columns and new columns are
+    /// picked up without touching this query. Credentials (password, token and
+    /// recovery code hashes, TOTP secrets) are left out.
+    pub async fn export(pool: &PgPool, user_id: Uuid) -> Result<serde_json::Value, AppError> {
+        let document: Option<serde_json::Value> = sqlx::query_scalar(
+            r#"
+            SELECT jsonb_build_object(
+                'format', 'fitness-tracker-export',
+                'version', 1,
+                'exported_at', NOW(),
+                'user', (
+                    SELECT (to_jsonb(u) - 'password_hash') || jsonb_build_object('roles', ARRAY(
+                        SELECT r.role FROM user_roles r WHERE r.user_id = u.id ORDER BY r.role
+                    ))
+                    FROM users u WHERE u.id = $1
+                ),
+                'settings', (
+                    SELECT to_jsonb(s) FROM user_settings s WHERE s.user_id = $1
+                ),
+                'workouts', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(w) || jsonb_build_object('exercises', (
+                        SELECT COALESCE(jsonb_agg(to_jsonb(we) || jsonb_build_object('sets', (
+                            SELECT COALESCE(jsonb_agg(to_jsonb(ws) ORDER BY ws.set_number), '[]'::jsonb)
+                            FROM workout_sets ws WHERE ws.workout_exercise_id = we.id
+                        )) ORDER BY we.order_index), '[]'::jsonb)
+                        FROM workout_exercises we WHERE we.workout_id = w.id
+                    ), 'pauses', (
+                        SELECT COALESCE(jsonb_agg(to_jsonb(wp) - 'workout_id' ORDER BY wp.started_at), '[]'::jsonb)
+                        FROM workout_pauses wp WHERE wp.workout_id = w.id
+                    )) ORDER BY w.started_at), '[]'::jsonb)
+                    FROM workouts w WHERE w.user_id = $1
+                ),
+                'templates', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(t) || jsonb_build_object('exercises', (
+                        SELECT COALESCE(jsonb_agg(to_jsonb(te) || jsonb_build_object('sets', (
+                            SELECT COALESCE(jsonb_agg(to_jsonb(ts) ORDER BY ts.set_number), '[]'::jsonb)
+                            FROM template_sets ts WHERE ts.template_exercise_id = te.id
+                        )) ORDER BY te.order_index), '[]'::jsonb)
+                        FROM template_exercises te WHERE te.template_id = t.id
+                    )) ORDER BY t.created_at), '[]'::jsonb)
+                    FROM workout_templates t WHERE t.user_id = $1
+                ),
+                'programs', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(p) || jsonb_build_object('workouts', (
+                        SELECT COALESCE(jsonb_agg(to_jsonb(pw) ORDER BY pw.week_number, pw.day_number), '[]'::jsonb)
+                        FROM program_workouts pw WHERE pw.program_id = p.id
+                    )) ORDER BY p.created_at), '[]'::jsonb)
+                    FROM workout_programs p WHERE p.user_id = $1
+                ),
+                'body_measurements', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(m) ORDER BY m.date), '[]'::jsonb)
+                    FROM body_measurements m WHERE m.user_id = $1
+                ),
+                'body_stats_goals', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(g) ORDER BY g.start_date), '[]'::jsonb)
+                    FROM body_stats_goals g WHERE g.user_id = $1
+                ),
+                'personal_records', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(pr) ORDER BY pr.achieved_at), '[]'::jsonb)
+                    FROM personal_records pr WHERE pr.user_id = $1
+                ),
+                'custom_exercises', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(e) || jsonb_build_object(
+                        'muscle_groups', ARRAY(
+                            SELECT mg.muscle_group FROM exercise_muscle_groups mg WHERE mg.exercise_id = e.id
+                        ),
+                        'equipment', ARRAY(
+                            SELECT eq.equipment FROM exercise_equipment eq WHERE eq.exercise_id = e.id
+                        )
+                    ) ORDER BY e.name), '[]'::jsonb)
+                    FROM exercise_templates e WHERE e.user_id = $1
+                ),
+                'sessions', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(us) ORDER BY us.created_at), '[]'::jsonb)
+                    FROM user_sessions us WHERE us.user_id = $1
+                ),
+                'access_tokens', (
+                    SELECT COALESCE(jsonb_agg(to_jsonb(pat) - 'token_hash' ORDER BY pat.created_at), '[]'::jsonb)
+                    FROM personal_access_tokens pat WHERE pat.user_id = $1
+                )
+            )
+            WHERE EXISTS (SELECT 1 FROM users WHERE id = $1)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        document.ok_or(AppError::Unauthorized)
+    }
+}
@@ -3,26 +3,15 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::dto::{
-    AdminMetricsResponse, AdminUserDetailResponse, AdminUserResponse, DailyRegistration,
-    TopUserResponse,
+    AdminExerciseResponse, AdminMetricsResponse, AdminUserDetailResponse, AdminUserResponse,
+    DailyRegistration, TopUserResponse,
 };
 use crate::error::AppError;
+use crate::models::{ExerciseCategory, Role};
 
 pub struct AdminRepository;
 
 impl AdminRepository {
-    pub async fn check_is_admin(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
-        let is_admin: bool = sqlx::query_scalar(
-            "SELECT is_admin FROM users WHERE id = $1"
-        )
-        .bind(user_id)
-        .fetch_optional(pool)
-        .await?
-        .unwrap_or(false);
-
-        Ok(is_admin)
-    }
-
     pub async fn list_users(
         pool: &PgPool,
         page: i64,
@@ -35,13 +24,14 @@ impl AdminRepository {
             SELECT
                 u.id,
                 u.email,
-                u.is_admin,
+                ARRAY(SELECT r.role FROM user_roles r WHERE r.user_id = u.id ORDER BY r.role) AS roles,
+                u.email_verified_at,
                 u.created_at,
                 COUNT(w.id) FILTER (WHERE w.status = 'completed') AS workout_count,
                 MAX(w.completed_at) AS last_active
             FROM users u
             LEFT JOIN workouts w ON w.user_id = u.id
-            GROUP BY u.id, u.email, u.is_admin, u.created_at
+            GROUP BY u.id, u.email, u.email_verified_at, u.created_at
             ORDER BY u.created_at DESC
             LIMIT $1 OFFSET $2
             "#,
@@ -55,17 +45,7 @@ impl AdminRepository {
             .fetch_one(pool)
             .await?;
 
-        let users = rows
-            .into_iter()
-            .map(|r| AdminUserResponse {
-                id: r.id,
-                email: r.email,
-                is_admin: r.is_admin,
-                created_at: r.created_at,
-                workout_count: r.workout_count,
-                last_active: r.last_active,
-            })
-            .collect();
+        let users = rows.into_iter().map(AdminUserRow::into_response).collect();
 
         Ok((users, total))
     }
@@ -79,7 +59,10 @@ impl AdminRepository {
             SELECT
                 u.id,
                 u.email,
-                u.is_admin,
+                ARRAY(SELECT r.role FROM user_roles r WHERE r.user_id = u.id ORDER BY r.role) AS roles,
+                u.email_verified_at,
+                u.failed_login_attempts,
+                u.locked_until,
                 u.created_at,
                 COUNT(DISTINCT w.id) FILTER (WHERE w.status = 'completed') AS workout_count,
                 MAX(w.completed_at) AS last_active,
@@ -89,21 +72,28 @@ impl AdminRepository {
             LEFT JOIN workout_exercises we ON we.workout_id = w.id AND w.status = 'completed'
             LEFT JOIN workout_sets ws ON ws.workout_exercise_id = we.id
             WHERE u.id = $1
-            GROUP BY u.id, u.email, u.is_admin, u.created_at
+            GROUP BY u.id
             "#,
         )
         .bind(user_id)
         .fetch_optional(pool)
         .await?;
 
-        Ok(row.map(|r| AdminUserDetailResponse {
-            id: r.id,
-            email: r.email,
-            is_admin: r.is_admin,
-            created_at: r.created_at,
-            workout_count: r.workout_count,
-            last_active: r.last_active,
-            total_sets: r.total_sets,
+        Ok(row.map(|r| {
+            let roles = Role::parse_all(&r.roles);
+            AdminUserDetailResponse {
+                id: r.id,
+                email: r.email,
+                is_admin: roles.contains(&Role::Admin),
+                roles,
+                email_verified_at: r.email_verified_at,
+                failed_login_attempts: r.failed_login_attempts,
+                locked_until: r.locked_until,
+                created_at: r.created_at,
+                workout_count: r.workout_count,
+                last_active: r.last_active,
+                total_sets: r.total_sets,
+            }
         }))
     }
 
@@ -116,38 +106,86 @@ impl AdminRepository {
         Ok(())
     }
 
-    pub async fn set_admin_status(
+    /// List-view summary of a single user, e.g. after changing their roles.
+    pub async fn find_user(
         pool: &PgPool,
         user_id: Uuid,
-        is_admin: bool,
-    ) -> Result<AdminUserResponse, AppError> {
+    ) -> Result<Option<AdminUserResponse>, AppError> {
         let row = sqlx::query_as::<_, AdminUserRow>(
             r#"
-            UPDATE users SET is_admin = $2, updated_at = NOW()
-            WHERE id = $1
-            RETURNING
-                id,
-                email,
-                is_admin,
-                created_at,
-                0::bigint AS workout_count,
-                NULL::timestamptz AS last_active
+            SELECT
+                u.id,
+                u.email,
+                ARRAY(SELECT r.role FROM user_roles r WHERE r.user_id = u.id ORDER BY r.role) AS roles,
+                u.email_verified_at,
+                u.created_at,
+                COUNT(w.id) FILTER (WHERE w.status = 'completed') AS workout_count,
+                MAX(w.completed_at) AS last_active
+            FROM users u
+            LEFT JOIN workouts w ON w.user_id = u.id
+            WHERE u.id = $1
+            GROUP BY u.id
             "#,
         )
         .bind(user_id)
-        .bind(is_admin)
         .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
-
-        Ok(AdminUserResponse {
-            id: row.id,
-            email: row.email,
-            is_admin: row.is_admin,
-            created_at: row.created_at,
-            workout_count: row.workout_count,
-            last_active: row.last_active,
-        })
+        .await?;
+
+        Ok(row.map(AdminUserRow::into_response))
+    }
+
+    /// Every user's custom exercises, newest owners first, for moderation.
+    pub async fn list_custom_exercises(
+        pool: &PgPool,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<AdminExerciseResponse>, i64), AppError> {
+        let offset = (page - 1) * page_size;
+
+        let rows = sqlx::query_as::<_, AdminExerciseRow>(
+            r#"
+            SELECT e.id, e.name, e.category, e.description, e.user_id, u.email AS owner_email
+            FROM exercise_templates e
+            JOIN users u ON u.id = e.user_id
+            WHERE e.is_custom = true
+            ORDER BY u.created_at DESC, e.name
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM exercise_templates WHERE is_custom = true")
+                .fetch_one(pool)
+                .await?;
+
+        let exercises = rows
+            .into_iter()
+            .map(|r| AdminExerciseResponse {
+                id: r.id,
+                name: r.name,
+                category: r.category,
+                description: r.description,
+                user_id: r.user_id,
+                owner_email: r.owner_email,
+            })
+            .collect();
+
+        Ok((exercises, total))
+    }
+
+    /// Removes any user's custom exercise. Library exercises can't be deleted.
+    pub async fn delete_custom_exercise(pool: &PgPool, id: &str) -> Result<bool, AppError> {
+        let result =
+            sqlx::query("DELETE FROM exercise_templates WHERE id = $1 AND is_custom = true")
+                .bind(id)
+                .execute(pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn get_metrics(pool: &PgPool) -> Result<AdminMetricsResponse, AppError> {
@@ -239,17 +277,37 @@ impl AdminRepository {
 struct AdminUserRow {
     id: Uuid,
     email: String,
-    is_admin: bool,
+    roles: Vec<String>,
+    email_verified_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     workout_count: i64,
     last_active: Option<DateTime<Utc>>,
 }
 
+impl AdminUserRow {
+    fn into_response(self) -> AdminUserResponse {
+        let roles = Role::parse_all(&self.roles);
+        AdminUserResponse {
+            id: self.id,
+            email: self.email,
+            is_admin: roles.contains(&Role::Admin),
+            roles,
+            email_verified_at: self.email_verified_at,
+            created_at: self.created_at,
+            workout_count: self.workout_count,
+            last_active: self.last_active,
+        }
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct AdminUserDetailRow {
     id: Uuid,
     email: String,
-    is_admin: bool,
+    roles: Vec<String>,
+    email_verified_at: Option<DateTime<Utc>>,
+    failed_login_attempts: i32,
+    locked_until: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     workout_count: i64,
     last_active: Option<DateTime<Utc>>,
@@ -269,3 +327,13 @@ struct TopUserRow {
     workout_count: i64,
     total_sets: i64,
 }
+
+#[derive(sqlx::FromRow)]
+struct AdminExerciseRow {
+    id: String,
+    name: String,
+    category: ExerciseCategory,
+    description: Option<String>,
+    user_id: Uuid,
+    owner_email: String,
+}
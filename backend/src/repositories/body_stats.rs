@@ -2,9 +2,11 @@ use chrono::NaiveDate;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::dto::MeasurementQuery;
+use crate::dto::{
+    CreateGoalRequest, CreateMeasurementRequest, MeasurementQuery, UpdateMeasurementRequest,
+};
 use crate::error::AppError;
-use crate::models::{BodyMeasurement, BodyStatsGoal, GoalType, MeasurementType};
+use crate::models::{BodyMeasurement, BodyStatsGoal};
 
 pub struct BodyStatsRepository;
 
@@ -13,23 +15,7 @@ impl BodyStatsRepository {
     pub async fn create_measurement(
         pool: &PgPool,
         user_id: Uuid,
-        date: NaiveDate,
-        weight: Option<f64>,
-        body_fat_percentage: Option<f64>,
-        chest: Option<f64>,
-        waist: Option<f64>,
-        hips: Option<f64>,
-        left_bicep: Option<f64>,
-        right_bicep: Option<f64>,
-        left_thigh: Option<f64>,
-        right_thigh: Option<f64>,
-        neck: Option<f64>,
-        shoulders: Option<f64>,
-        left_calf: Option<f64>,
-        right_calf: Option<f64>,
-        left_forearm: Option<f64>,
-        right_forearm: Option<f64>,
-        notes: Option<&str>,
+        measurement: &CreateMeasurementRequest,
     ) -> Result<BodyMeasurement, AppError> {
         let measurement = sqlx::query_as::<_, BodyMeasurement>(
             r#"
@@ -42,25 +28,25 @@ impl BodyStatsRepository {
             RETURNING *
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(measurement.id.unwrap_or_else(Uuid::new_v4))
         .bind(user_id)
-        .bind(date)
-        .bind(weight)
-        .bind(body_fat_percentage)
-        .bind(chest)
-        .bind(waist)
-        .bind(hips)
-        .bind(left_bicep)
-        .bind(right_bicep)
-        .bind(left_thigh)
-        .bind(right_thigh)
-        .bind(neck)
-        .bind(shoulders)
-        .bind(left_calf)
-        .bind(right_calf)
-        .bind(left_forearm)
-        .bind(right_forearm)
-        .bind(notes)
+        .bind(measurement.date)
+        .bind(measurement.weight)
+        .bind(measurement.body_fat_percentage)
+        .bind(measurement.chest)
+        .bind(measurement.waist)
+        .bind(measurement.hips)
+        .bind(measurement.left_bicep)
+        .bind(measurement.right_bicep)
+        .bind(measurement.left_thigh)
+        .bind(measurement.right_thigh)
+        .bind(measurement.neck)
+        .bind(measurement.shoulders)
+        .bind(measurement.left_calf)
+        .bind(measurement.right_calf)
+        .bind(measurement.left_forearm)
+        .bind(measurement.right_forearm)
+        .bind(measurement.notes.as_deref())
         .fetch_one(pool)
         .await?;
 
@@ -154,23 +140,7 @@ impl BodyStatsRepository {
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
-        date: Option<NaiveDate>,
-        weight: Option<f64>,
-        body_fat_percentage: Option<f64>,
-        chest: Option<f64>,
-        waist: Option<f64>,
-        hips: Option<f64>,
-        left_bicep: Option<f64>,
-        right_bicep: Option<f64>,
-        left_thigh: Option<f64>,
-        right_thigh: Option<f64>,
-        neck: Option<f64>,
-        shoulders: Option<f64>,
-        left_calf: Option<f64>,
-        right_calf: Option<f64>,
-        left_forearm: Option<f64>,
-        right_forearm: Option<f64>,
-        notes: Option<&str>,
+        changes: &UpdateMeasurementRequest,
     ) -> Result<BodyMeasurement, AppError> {
         let measurement = sqlx::query_as::<_, BodyMeasurement>(
             r#"
@@ -198,23 +168,23 @@ impl BodyStatsRepository {
         )
         .bind(id)
         .bind(user_id)
-        .bind(date)
-        .bind(weight)
-        .bind(body_fat_percentage)
-        .bind(chest)
-        .bind(waist)
-        .bind(hips)
-        .bind(left_bicep)
-        .bind(right_bicep)
-        .bind(left_thigh)
-        .bind(right_thigh)
-        .bind(neck)
-        .bind(shoulders)
-        .bind(left_calf)
-        .bind(right_calf)
-        .bind(left_forearm)
-        .bind(right_forearm)
-        .bind(notes)
+        .bind(changes.date)
+        .bind(changes.weight)
+        .bind(changes.body_fat_percentage)
+        .bind(changes.chest)
+        .bind(changes.waist)
+        .bind(changes.hips)
+        .bind(changes.left_bicep)
+        .bind(changes.right_bicep)
+        .bind(changes.left_thigh)
+        .bind(changes.right_thigh)
+        .bind(changes.neck)
+        .bind(changes.shoulders)
+        .bind(changes.left_calf)
+        .bind(changes.right_calf)
+        .bind(changes.left_forearm)
+        .bind(changes.right_forearm)
+        .bind(changes.notes.as_deref())
         .fetch_optional(pool)
         .await?
         .ok_or_else(|| AppError::NotFound("Measurement not found".to_string()))?;
@@ -258,12 +228,7 @@ impl BodyStatsRepository {
     pub async fn create_goal(
         pool: &PgPool,
         user_id: Uuid,
-        goal_type: &GoalType,
-        measurement_type: Option<&MeasurementType>,
-        target_value: f64,
-        start_value: f64,
-        start_date: NaiveDate,
-        target_date: Option<NaiveDate>,
+        goal: &CreateGoalRequest,
     ) -> Result<BodyStatsGoal, AppError> {
         let goal = sqlx::query_as::<_, BodyStatsGoal>(
             r#"
@@ -272,14 +237,14 @@ impl BodyStatsRepository {
             RETURNING *
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(goal.id.unwrap_or_else(Uuid::new_v4))
         .bind(user_id)
-        .bind(goal_type)
-        .bind(measurement_type)
-        .bind(target_value)
-        .bind(start_value)
-        .bind(start_date)
-        .bind(target_date)
+        .bind(&goal.type_)
+        .bind(&goal.measurement_type)
+        .bind(goal.target_value)
+        .bind(goal.start_value)
+        .bind(goal.start_date)
+        .bind(goal.target_date)
         .fetch_one(pool)
         .await?;
 
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::dto::ExerciseQuery;
+use crate::dto::{CreateExerciseRequest, ExerciseQuery, UpdateExerciseRequest};
 use crate::error::AppError;
 use crate::models::{Equipment, ExerciseCategory, ExerciseTemplate, MuscleGroup};
 
@@ -118,16 +120,32 @@ impl ExerciseRepository {
         Ok(row.map(|r| r.into()))
     }
 
+    /// Names of the given exercises the user can see, keyed by ID. IDs that
+    /// don't exist or belong to another user are left out.
+    pub async fn find_names(
+        pool: &PgPool,
+        ids: &[String],
+        user_id: Uuid,
+    ) -> Result<HashMap<String, String>, AppError> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT id, name FROM exercise_templates
+            WHERE id = ANY($1) AND (user_id IS NULL OR user_id = $2)
+            "#,
+        )
+        .bind(ids)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
     pub async fn create(
         pool: &PgPool,
         user_id: Uuid,
         id: &str,
-        name: &str,
-        muscle_groups: &[MuscleGroup],
-        category: &ExerciseCategory,
-        equipment: &[Equipment],
-        description: Option<&str>,
-        instructions: Option<&[String]>,
+        exercise: &CreateExerciseRequest,
     ) -> Result<ExerciseTemplate, AppError> {
         let mut tx = pool.begin().await?;
 
@@ -138,15 +156,15 @@ impl ExerciseRepository {
             "#,
         )
         .bind(id)
-        .bind(name)
-        .bind(category)
-        .bind(description)
-        .bind(instructions)
+        .bind(&exercise.name)
+        .bind(&exercise.category)
+        .bind(&exercise.description)
+        .bind(&exercise.instructions)
         .bind(user_id)
         .execute(&mut *tx)
         .await?;
 
-        for mg in muscle_groups {
+        for mg in &exercise.muscle_groups {
             sqlx::query(
                 "INSERT INTO exercise_muscle_groups (exercise_id, muscle_group) VALUES ($1, $2)",
             )
@@ -156,7 +174,7 @@ impl ExerciseRepository {
             .await?;
         }
 
-        for eq in equipment {
+        for eq in &exercise.equipment {
             sqlx::query("INSERT INTO exercise_equipment (exercise_id, equipment) VALUES ($1, $2)")
                 .bind(id)
                 .bind(eq)
@@ -175,12 +193,7 @@ impl ExerciseRepository {
         pool: &PgPool,
         id: &str,
         user_id: Uuid,
-        name: Option<&str>,
-        muscle_groups: Option<&[MuscleGroup]>,
-        category: Option<&ExerciseCategory>,
-        equipment: Option<&[Equipment]>,
-        description: Option<&str>,
-        instructions: Option<&[String]>,
+        changes: &UpdateExerciseRequest,
     ) -> Result<ExerciseTemplate, AppError> {
         let mut tx = pool.begin().await?;
 
@@ -197,7 +210,7 @@ impl ExerciseRepository {
             return Err(AppError::NotFound("Exercise not found or not editable".to_string()));
         }
 
-        if let Some(name) = name {
+        if let Some(name) = &changes.name {
             sqlx::query("UPDATE exercise_templates SET name = $1 WHERE id = $2")
                 .bind(name)
                 .bind(id)
@@ -205,7 +218,7 @@ impl ExerciseRepository {
                 .await?;
         }
 
-        if let Some(category) = category {
+        if let Some(category) = &changes.category {
             sqlx::query("UPDATE exercise_templates SET category = $1 WHERE id = $2")
                 .bind(category)
                 .bind(id)
@@ -213,7 +226,7 @@ impl ExerciseRepository {
                 .await?;
         }
 
-        if let Some(desc) = description {
+        if let Some(desc) = &changes.description {
             sqlx::query("UPDATE exercise_templates SET description = $1 WHERE id = $2")
                 .bind(desc)
                 .bind(id)
@@ -221,7 +234,7 @@ impl ExerciseRepository {
                 .await?;
         }
 
-        if let Some(inst) = instructions {
+        if let Some(inst) = &changes.instructions {
             sqlx::query("UPDATE exercise_templates SET instructions = $1 WHERE id = $2")
                 .bind(inst)
                 .bind(id)
@@ -229,7 +242,7 @@ impl ExerciseRepository {
                 .await?;
         }
 
-        if let Some(mgs) = muscle_groups {
+        if let Some(mgs) = &changes.muscle_groups {
             sqlx::query("DELETE FROM exercise_muscle_groups WHERE exercise_id = $1")
                 .bind(id)
                 .execute(&mut *tx)
@@ -246,7 +259,7 @@ impl ExerciseRepository {
             }
         }
 
-        if let Some(eqs) = equipment {
+        if let Some(eqs) = &changes.equipment {
             sqlx::query("DELETE FROM exercise_equipment WHERE exercise_id = $1")
                 .bind(id)
                 .execute(&mut *tx)
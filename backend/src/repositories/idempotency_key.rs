@@ -0,0 +1,117 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::IdempotencyKey;
+
+/// How long a stored response is replayed for
+const IDEMPOTENCY_KEY_TTL_HOURS: i32 = 24;
+
+/// A request still marked as running after this long is assumed to have died
+/// (a crash or a dropped connection) and its key may be used again
+const ABANDONED_AFTER_SECS: f64 = 60.0;
+
+pub struct IdempotencyKeyRepository;
+
+impl IdempotencyKeyRepository {
+    /// Claims `key` for a new request. Returns `None` when the caller now owns
+    /// the key, or the live record of an earlier request with the same key.
+    pub async fn reserve(
+        pool: &PgPool,
+        user_id: Uuid,
+        key: &str,
+        method: &str,
+        path: &str,
+        request_hash: &str,
+    ) -> Result<Option<IdempotencyKey>, AppError> {
+        let reserved = sqlx::query_scalar::<_, bool>(
+            r#"
+            INSERT INTO idempotency_keys (user_id, key, method, path, request_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, key) DO UPDATE
+            SET method = EXCLUDED.method, path = EXCLUDED.path,
+                request_hash = EXCLUDED.request_hash, status_code = NULL,
+                content_type = NULL, response_body = NULL, created_at = NOW()
+            WHERE idempotency_keys.created_at < NOW() - make_interval(hours => $6)
+               OR (idempotency_keys.status_code IS NULL
+                   AND idempotency_keys.created_at < NOW() - make_interval(secs => $7))
+            RETURNING TRUE
+            "#,
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(method)
+        .bind(path)
+        .bind(request_hash)
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+        .bind(ABANDONED_AFTER_SECS)
+        .fetch_optional(pool)
+        .await?;
+
+        if reserved.is_some() {
+            return Ok(None);
+        }
+
+        let existing = sqlx::query_as::<_, IdempotencyKey>(
+            r#"
+            SELECT method, path, request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE user_id = $1 AND key = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(existing)
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        user_id: Uuid,
+        key: &str,
+        status_code: i16,
+        content_type: Option<&str>,
+        response_body: &[u8],
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $3, content_type = $4, response_body = $5
+            WHERE user_id = $1 AND key = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(key)
+        .bind(status_code)
+        .bind(content_type)
+        .bind(response_body)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Frees a key whose request failed, so the client can retry with it
+    pub async fn release(pool: &PgPool, user_id: Uuid, key: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2")
+            .bind(user_id)
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "DELETE FROM idempotency_keys WHERE created_at < NOW() - make_interval(hours => $1)",
+        )
+        .bind(IDEMPOTENCY_KEY_TTL_HOURS)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::error::AppError;
+use crate::models::{Job, JobRecord};
+
+pub struct JobRepository;
+
+impl JobRepository {
+    /// Returns false if an identical job is already waiting to run, in which
+    /// case that one covers this request.
+    pub async fn enqueue(pool: &PgPool, job: &Job) -> Result<bool, AppError> {
+        let mut tx = pool.begin().await?;
+        let queued = Self::enqueue_tx(&mut tx, job).await?;
+        tx.commit().await?;
+
+        Ok(queued)
+    }
+
+    /// Enqueues as part of a larger transaction, so the job exists if and only
+    /// if the change that triggered it commits.
+    pub async fn enqueue_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        job: &Job,
+    ) -> Result<bool, AppError> {
+        let (kind, payload) = job
+            .to_parts()
+            .map_err(|e| AppError::Internal(format!("Failed to serialize job: {e}")))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO jobs (kind, payload)
+            VALUES ($1, $2)
+            ON CONFLICT (kind, payload) WHERE status = 'pending' DO NOTHING
+            "#,
+        )
+        .bind(kind)
+        .bind(payload)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claims the oldest due job, or one whose worker hasn't sent a heartbeat
+    /// within `lock_timeout_secs` (it most likely crashed mid-job). A lost job
+    /// that has already used up its attempts is dead-lettered instead.
+    pub async fn claim_next(
+        pool: &PgPool,
+        lock_timeout_secs: i64,
+        max_attempts: i32,
+    ) -> Result<Option<JobRecord>, AppError> {
+        let job = sqlx::query_as::<_, JobRecord>(
+            r#"
+            WITH lost AS (
+                UPDATE jobs
+                SET status = 'dead', locked_at = NULL,
+                    last_error = 'Worker was lost on the final attempt', updated_at = NOW()
+                WHERE status = 'running' AND locked_at < NOW() - make_interval(secs => $1)
+                  AND attempts >= $2
+            )
+            UPDATE jobs
+            SET status = 'running', attempts = attempts + 1, locked_at = NOW(), updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM jobs
+                WHERE (status = 'pending' AND run_at <= NOW())
+                   OR (status = 'running' AND locked_at < NOW() - make_interval(secs => $1)
+                       AND attempts < $2)
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, kind, payload, attempts
+            "#,
+        )
+        .bind(lock_timeout_secs as f64)
+        .bind(max_attempts)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Keeps a running job's lock fresh so it isn't handed to another worker.
+    pub async fn heartbeat(pool: &PgPool, id: i64) -> Result<(), AppError> {
+        sqlx::query("UPDATE jobs SET locked_at = NOW() WHERE id = $1 AND status = 'running'")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete(pool: &PgPool, id: i64) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM jobs WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Puts a failed job back in the queue. If an identical job was enqueued
+    /// while this one ran, that one covers the retry and this one is dropped.
+    pub async fn retry_at(
+        pool: &PgPool,
+        id: i64,
+        run_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'pending', run_at = $2, locked_at = NULL, last_error = $3, updated_at = NOW()
+            WHERE id = $1 AND NOT EXISTS (
+                SELECT 1 FROM jobs pending
+                WHERE pending.kind = jobs.kind AND pending.payload = jobs.payload
+                  AND pending.status = 'pending'
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(run_at)
+        .bind(error)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            sqlx::query("DELETE FROM jobs WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Parks a job that won't be retried. Dead jobs are kept for inspection and
+    /// can be requeued by setting their status back to 'pending'.
+    pub async fn dead_letter(pool: &PgPool, id: i64, error: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'dead', locked_at = NULL, last_error = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
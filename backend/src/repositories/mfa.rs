@@ -0,0 +1,180 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::UserTotp;
+
+pub struct MfaRepository;
+
+impl MfaRepository {
+    pub async fn find_totp(pool: &PgPool, user_id: Uuid) -> Result<Option<UserTotp>, AppError> {
+        let totp = sqlx::query_as::<_, UserTotp>(
+            r#"
+            SELECT user_id, secret, confirmed_at, last_used_step, created_at
+            FROM user_totp
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(totp)
+    }
+
+    pub async fn is_enabled(pool: &PgPool, user_id: Uuid) -> Result<bool, AppError> {
+        let enabled: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL)",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(enabled)
+    }
+
+    /// Starts (or restarts) a pending enrollment. Never overwrites a confirmed secret.
+    pub async fn upsert_pending(
+        pool: &PgPool,
+        user_id: Uuid,
+        secret: &str,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, secret, created_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+            WHERE user_totp.confirmed_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records `step` as used. Returns false if it (or a later step) was already used.
+    pub async fn use_step(pool: &PgPool, user_id: Uuid, step: i64) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Confirms the pending enrollment and replaces any recovery codes.
+    pub async fn confirm(
+        pool: &PgPool,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("UPDATE user_totp SET confirmed_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::replace_recovery_codes_tx(&mut tx, user_id, recovery_code_hashes).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn replace_recovery_codes(
+        pool: &PgPool,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::replace_recovery_codes_tx(&mut tx, user_id, recovery_code_hashes).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn replace_recovery_codes_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::varchar[])
+            "#,
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Burns a recovery code. Returns false if it doesn't exist or was already used.
+    pub async fn use_recovery_code(
+        pool: &PgPool,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_recovery_codes SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn count_unused_recovery_codes(pool: &PgPool, user_id: Uuid) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Turns 2FA off, removing the secret and all recovery codes.
+    pub async fn disable(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
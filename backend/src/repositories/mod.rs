@@ -1,19 +1,41 @@
+mod access_token;
+mod account;
 mod admin;
 mod body_stats;
+mod email_verification_token;
 mod exercise;
+mod idempotency_key;
+mod job;
+mod mfa;
+mod password_reset_token;
 mod personal_record;
 mod program;
+mod refresh_token;
+mod role;
+mod session;
 mod settings;
+mod sync;
 mod template;
 mod user;
 mod workout;
 
+pub use access_token::AccessTokenRepository;
+pub use account::AccountRepository;
 pub use admin::AdminRepository;
 pub use body_stats::BodyStatsRepository;
+pub use email_verification_token::EmailVerificationTokenRepository;
 pub use exercise::ExerciseRepository;
+pub use idempotency_key::IdempotencyKeyRepository;
+pub use job::JobRepository;
+pub use mfa::MfaRepository;
+pub use password_reset_token::PasswordResetTokenRepository;
 pub use personal_record::PersonalRecordRepository;
 pub use program::ProgramRepository;
+pub use refresh_token::RefreshTokenRepository;
+pub use role::RoleRepository;
+pub use session::SessionRepository;
 pub use settings::SettingsRepository;
+pub use sync::{SyncRepository, TOMBSTONE_RETENTION_DAYS};
 pub use template::TemplateRepository;
 pub use user::UserRepository;
-pub use workout::WorkoutRepository;
+pub use workout::{NewSet, WorkoutRepository};
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::PasswordResetToken;
+
+pub struct PasswordResetTokenRepository;
+
+impl PasswordResetTokenRepository {
+    /// Stores a new reset token, invalidating any the user still has outstanding.
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PasswordResetToken, AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let token = sqlx::query_as::<_, PasswordResetToken>(
+            r#"
+            INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            RETURNING id, user_id, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(token)
+    }
+
+    /// Atomically marks an unused, unexpired token as used and returns it.
+    /// Returns None if the token is unknown, expired or was already used.
+    pub async fn consume(
+        pool: &PgPool,
+        token_hash: &str,
+    ) -> Result<Option<PasswordResetToken>, AppError> {
+        let token = sqlx::query_as::<_, PasswordResetToken>(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING id, user_id, token_hash, expires_at, used_at, created_at
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -1,48 +1,113 @@
-use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use tracing::{debug, error, instrument};
 use uuid::Uuid;
 
 use crate::error::AppError;
-use crate::models::{PersonalRecord, RecordType};
+use crate::models::{NewPersonalRecord, PersonalRecord};
 
 pub struct PersonalRecordRepository;
 
 impl PersonalRecordRepository {
-    #[instrument(skip(pool))]
-    pub async fn create(
+    /// Stores `record` if it beats the current record of its type (and rep
+    /// count, for rep maxes), marking that one superseded. Returns the new
+    /// record, or `None` if it wasn't better.
+    #[instrument(skip(pool, record), fields(record_type = ?record.record_type))]
+    pub async fn create_if_better(
         pool: &PgPool,
         user_id: Uuid,
         exercise_template_id: &str,
-        exercise_name: &str,
-        record_type: &RecordType,
-        value: f64,
-        reps: Option<i32>,
-        achieved_at: DateTime<Utc>,
-        workout_id: Uuid,
+        record: &NewPersonalRecord,
+    ) -> Result<Option<PersonalRecord>, AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_exercise_tx(&mut tx, user_id, exercise_template_id).await?;
+
+        let rep_count = record.reps.filter(|_| record.record_type.is_per_rep_count());
+        let current = sqlx::query_as::<_, PersonalRecord>(
+            r#"
+            SELECT * FROM personal_records
+            WHERE user_id = $1 AND exercise_template_id = $2 AND record_type = $3
+              AND ($4::int IS NULL OR reps = $4) AND superseded_at IS NULL
+            ORDER BY achieved_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(exercise_template_id)
+        .bind(record.record_type)
+        .bind(rep_count)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(current) = &current {
+            if !record.record_type.beats(record.value, current.value) {
+                return Ok(None);
+            }
+
+            sqlx::query("UPDATE personal_records SET superseded_at = $2 WHERE id = $1")
+                .bind(current.id)
+                .bind(record.achieved_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let record = NewPersonalRecord {
+            previous_value: current.map(|c| c.value),
+            superseded_at: None,
+            ..record.clone()
+        };
+        let created = Self::insert_tx(&mut tx, user_id, exercise_template_id, &record).await?;
+        tx.commit().await?;
+
+        Ok(Some(created))
+    }
+
+    async fn insert_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        exercise_template_id: &str,
+        record: &NewPersonalRecord,
     ) -> Result<PersonalRecord, AppError> {
         let record = sqlx::query_as::<_, PersonalRecord>(
             r#"
-            INSERT INTO personal_records (id, user_id, exercise_template_id, exercise_name, record_type, value, reps, achieved_at, workout_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO personal_records (id, user_id, exercise_template_id, exercise_name, record_type, value, reps, distance_meters, achieved_at, workout_id, previous_value, superseded_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
         .bind(user_id)
         .bind(exercise_template_id)
-        .bind(exercise_name)
-        .bind(record_type)
-        .bind(value)
-        .bind(reps)
-        .bind(achieved_at)
-        .bind(workout_id)
-        .fetch_one(pool)
+        .bind(&record.exercise_name)
+        .bind(record.record_type)
+        .bind(record.value)
+        .bind(record.reps)
+        .bind(record.distance_meters)
+        .bind(record.achieved_at)
+        .bind(record.workout_id)
+        .bind(record.previous_value)
+        .bind(record.superseded_at)
+        .fetch_one(&mut **tx)
         .await?;
 
         Ok(record)
     }
 
+    /// Serializes detection and recomputation for one user's exercise, so two
+    /// workouts finishing together can't both become the current record.
+    async fn lock_exercise_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        exercise_template_id: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text || '/' || $2, 0))")
+            .bind(user_id)
+            .bind(exercise_template_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
     #[instrument(skip(pool), fields(user_id = %user_id, exercise_id = %exercise_template_id))]
     pub async fn find_by_exercise(
         pool: &PgPool,
@@ -98,20 +163,24 @@ impl PersonalRecordRepository {
         Ok(records)
     }
 
+    /// All of a user's records, newest first. `current` keeps only current
+    /// (`Some(true)`) or only superseded (`Some(false)`) ones.
     #[instrument(skip(pool), fields(user_id = %user_id))]
     pub async fn find_all(
         pool: &PgPool,
         user_id: Uuid,
+        current: Option<bool>,
     ) -> Result<Vec<PersonalRecord>, AppError> {
         debug!("Querying all personal records");
         let records = sqlx::query_as::<_, PersonalRecord>(
             r#"
             SELECT * FROM personal_records
-            WHERE user_id = $1
+            WHERE user_id = $1 AND ($2::bool IS NULL OR (superseded_at IS NULL) = $2)
             ORDER BY achieved_at DESC
             "#,
         )
         .bind(user_id)
+        .bind(current)
         .fetch_all(pool)
         .await
         .map_err(|e| {
@@ -123,26 +192,76 @@ impl PersonalRecordRepository {
         Ok(records)
     }
 
-    pub async fn get_current_record(
+    /// Current rep-max record for each rep count, lowest rep count first.
+    pub async fn find_rep_maxes(
         pool: &PgPool,
         user_id: Uuid,
         exercise_template_id: &str,
-        record_type: &RecordType,
-    ) -> Result<Option<PersonalRecord>, AppError> {
-        let record = sqlx::query_as::<_, PersonalRecord>(
+    ) -> Result<Vec<PersonalRecord>, AppError> {
+        let records = sqlx::query_as::<_, PersonalRecord>(
             r#"
             SELECT * FROM personal_records
-            WHERE user_id = $1 AND exercise_template_id = $2 AND record_type = $3
-            ORDER BY value DESC
-            LIMIT 1
+            WHERE user_id = $1 AND exercise_template_id = $2 AND record_type = 'rep-max'
+              AND superseded_at IS NULL
+            ORDER BY reps
             "#,
         )
         .bind(user_id)
         .bind(exercise_template_id)
-        .bind(record_type)
-        .fetch_optional(pool)
+        .fetch_all(pool)
         .await?;
 
-        Ok(record)
+        Ok(records)
+    }
+
+    /// Swaps the stored records for one exercise with `records` in a single
+    /// transaction, so readers never see a half-rebuilt history.
+    pub async fn replace_for_exercise(
+        pool: &PgPool,
+        user_id: Uuid,
+        exercise_template_id: &str,
+        records: &[NewPersonalRecord],
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_exercise_tx(&mut tx, user_id, exercise_template_id).await?;
+
+        sqlx::query(
+            "DELETE FROM personal_records WHERE user_id = $1 AND exercise_template_id = $2",
+        )
+        .bind(user_id)
+        .bind(exercise_template_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for record in records {
+            Self::insert_tx(&mut tx, user_id, exercise_template_id, record).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Exercises that may hold records for the user: everything in their
+    /// completed workouts plus anything they already have a record for.
+    pub async fn find_recordable_exercises(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<String>, AppError> {
+        let exercise_ids = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT we.exercise_template_id
+            FROM workouts w
+            JOIN workout_exercises we ON we.workout_id = w.id
+            WHERE w.user_id = $1 AND w.status = 'completed'
+            UNION
+            SELECT exercise_template_id FROM personal_records WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(exercise_ids)
     }
 }
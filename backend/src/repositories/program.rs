@@ -1,7 +1,9 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::dto::CreateProgramWorkoutRequest;
 use crate::error::AppError;
+use crate::etag::check_version;
 use crate::models::{ProgramWorkout, WorkoutProgram};
 
 pub struct ProgramRepository;
@@ -10,6 +12,7 @@ impl ProgramRepository {
     pub async fn create(
         pool: &PgPool,
         user_id: Uuid,
+        id: Option<Uuid>,
         name: &str,
         description: Option<&str>,
         duration_weeks: i32,
@@ -21,7 +24,7 @@ impl ProgramRepository {
             RETURNING *
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(id.unwrap_or_else(Uuid::new_v4))
         .bind(user_id)
         .bind(name)
         .bind(description)
@@ -80,10 +83,23 @@ impl ProgramRepository {
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
+        expected_version: Option<i32>,
         name: Option<&str>,
         description: Option<&str>,
         duration_weeks: Option<i32>,
     ) -> Result<WorkoutProgram, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let version = sqlx::query_scalar::<_, i32>(
+            "SELECT version FROM workout_programs WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+        check_version(expected_version, version)?;
+
         let program = sqlx::query_as::<_, WorkoutProgram>(
             r#"
             UPDATE workout_programs SET
@@ -99,9 +115,10 @@ impl ProgramRepository {
         .bind(name)
         .bind(description)
         .bind(duration_weeks)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(program)
     }
@@ -168,12 +185,7 @@ impl ProgramRepository {
     pub async fn add_workout(
         pool: &PgPool,
         program_id: Uuid,
-        week_number: i32,
-        day_number: i32,
-        name: &str,
-        template_id: Option<Uuid>,
-        is_rest_day: bool,
-        notes: Option<&str>,
+        workout_req: &CreateProgramWorkoutRequest,
     ) -> Result<ProgramWorkout, AppError> {
         let workout = sqlx::query_as::<_, ProgramWorkout>(
             r#"
@@ -184,12 +196,12 @@ impl ProgramRepository {
         )
         .bind(Uuid::new_v4())
         .bind(program_id)
-        .bind(week_number)
-        .bind(day_number)
-        .bind(name)
-        .bind(template_id)
-        .bind(is_rest_day)
-        .bind(notes)
+        .bind(workout_req.week_number)
+        .bind(workout_req.day_number)
+        .bind(&workout_req.name)
+        .bind(workout_req.template_id)
+        .bind(workout_req.is_rest_day)
+        .bind(&workout_req.notes)
         .fetch_one(pool)
         .await?;
 
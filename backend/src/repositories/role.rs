@@ -0,0 +1,96 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::Role;
+
+pub struct RoleRepository;
+
+impl RoleRepository {
+    pub async fn find_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Role>, AppError> {
+        let names = sqlx::query_scalar::<_, String>(
+            "SELECT role FROM user_roles WHERE user_id = $1 ORDER BY role",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Role::parse_all(&names))
+    }
+
+    /// Replaces the user's roles with `roles`, keeping the original grant
+    /// details of roles they already held. Returns false if the user doesn't exist.
+    pub async fn set_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        roles: &[Role],
+        granted_by: Uuid,
+    ) -> Result<bool, AppError> {
+        let names: Vec<&str> = roles.iter().map(|r| r.as_str()).collect();
+        let mut tx = pool.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
+        if !exists {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role <> ALL($2)")
+            .bind(user_id)
+            .bind(&names)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role, granted_by, granted_at)
+            SELECT $1, role, $3, NOW() FROM UNNEST($2::text[]) AS role
+            ON CONFLICT (user_id, role) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(&names)
+        .bind(granted_by)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    pub async fn grant(
+        pool: &PgPool,
+        user_id: Uuid,
+        role: Role,
+        granted_by: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role, granted_by, granted_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (user_id, role) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(role.as_str())
+        .bind(granted_by)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn revoke(pool: &PgPool, user_id: Uuid, role: Role) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role = $2")
+            .bind(user_id)
+            .bind(role.as_str())
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::Session;
+
+pub struct SessionRepository;
+
+impl SessionRepository {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        device_label: Option<&str>,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Session, AppError> {
+        let session = sqlx::query_as::<_, Session>(
+            r#"
+            INSERT INTO user_sessions (id, user_id, device_label, user_agent, ip_address, created_at, last_seen_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, NOW(), NOW(), $6)
+            RETURNING id, user_id, device_label, user_agent, ip_address, created_at, last_seen_at, expires_at, revoked_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(device_label)
+        .bind(user_agent)
+        .bind(ip_address)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    pub async fn list_active(pool: &PgPool, user_id: Uuid) -> Result<Vec<Session>, AppError> {
+        let sessions = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT id, user_id, device_label, user_agent, ip_address, created_at, last_seen_at, expires_at, revoked_at
+            FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Called on every refresh: the session lives as long as its newest refresh token.
+    pub async fn extend(
+        pool: &PgPool,
+        id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE user_sessions SET last_seen_at = NOW(), expires_at = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the session is still usable, bumping `last_seen_at` at
+    /// most once a minute so authenticated requests don't each cost a write.
+    pub async fn touch_active(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let active = sqlx::query_scalar::<_, bool>(
+            r#"
+            WITH s AS (
+                SELECT id, last_seen_at
+                FROM user_sessions
+                WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > NOW()
+            ), touched AS (
+                UPDATE user_sessions SET last_seen_at = NOW()
+                WHERE id IN (SELECT id FROM s WHERE last_seen_at < NOW() - INTERVAL '1 minute')
+            )
+            SELECT EXISTS (SELECT 1 FROM s)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(active)
+    }
+
+    /// Revokes a session together with every refresh token issued for it.
+    pub async fn revoke(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Someone else's session, or one already revoked: touch nothing
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Revokes all of a user's sessions, optionally keeping the caller's own.
+    pub async fn revoke_all_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        except: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_sessions SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL AND id IS DISTINCT FROM $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(except)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL AND family_id IS DISTINCT FROM $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(except)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM user_sessions WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
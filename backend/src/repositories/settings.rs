@@ -2,8 +2,10 @@ use sqlx::PgPool;
 use sqlx::types::Json;
 use uuid::Uuid;
 
+use crate::dto::UpdateSettingsRequest;
 use crate::error::AppError;
-use crate::models::{MeasurementUnit, PlateCalculatorSettings, Theme, UserSettings, WeightUnit};
+use crate::etag::check_version;
+use crate::models::UserSettings;
 
 pub struct SettingsRepository;
 
@@ -28,8 +30,8 @@ impl SettingsRepository {
 
         let settings = sqlx::query_as::<_, UserSettings>(
             r#"
-            INSERT INTO user_settings (user_id, weight_unit, measurement_unit, theme, default_rest_timer, auto_start_rest_timer, show_warmup_sets, vibrate_on_timer_end, sound_on_timer_end, plate_calculator, compact_mode)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            INSERT INTO user_settings (user_id, weight_unit, measurement_unit, theme, default_rest_timer, auto_start_rest_timer, show_warmup_sets, vibrate_on_timer_end, sound_on_timer_end, plate_calculator, compact_mode, one_rep_max_formula, timezone, week_start)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#,
         )
@@ -44,6 +46,9 @@ impl SettingsRepository {
         .bind(default.sound_on_timer_end)
         .bind(Json(&default.plate_calculator))
         .bind(default.compact_mode)
+        .bind(default.one_rep_max_formula)
+        .bind(&default.timezone)
+        .bind(default.week_start)
         .fetch_one(pool)
         .await?;
 
@@ -53,20 +58,21 @@ impl SettingsRepository {
     pub async fn update(
         pool: &PgPool,
         user_id: Uuid,
-        weight_unit: Option<&WeightUnit>,
-        measurement_unit: Option<&MeasurementUnit>,
-        theme: Option<&Theme>,
-        default_rest_timer: Option<i32>,
-        auto_start_rest_timer: Option<bool>,
-        show_warmup_sets: Option<bool>,
-        vibrate_on_timer_end: Option<bool>,
-        sound_on_timer_end: Option<bool>,
-        plate_calculator: Option<&PlateCalculatorSettings>,
-        compact_mode: Option<bool>,
+        expected_version: Option<i32>,
+        changes: &UpdateSettingsRequest,
     ) -> Result<UserSettings, AppError> {
         // Ensure settings exist
         Self::get_or_create(pool, user_id).await?;
 
+        let mut tx = pool.begin().await?;
+        let version = sqlx::query_scalar::<_, i32>(
+            "SELECT version FROM user_settings WHERE user_id = $1 FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        check_version(expected_version, version)?;
+
         let settings = sqlx::query_as::<_, UserSettings>(
             r#"
             UPDATE user_settings SET
@@ -79,25 +85,33 @@ impl SettingsRepository {
                 vibrate_on_timer_end = COALESCE($8, vibrate_on_timer_end),
                 sound_on_timer_end = COALESCE($9, sound_on_timer_end),
                 plate_calculator = COALESCE($10, plate_calculator),
-                compact_mode = COALESCE($11, compact_mode)
+                compact_mode = COALESCE($11, compact_mode),
+                one_rep_max_formula = COALESCE($12, one_rep_max_formula),
+                timezone = COALESCE($13, timezone),
+                week_start = COALESCE($14, week_start)
             WHERE user_id = $1
             RETURNING *
             "#,
         )
         .bind(user_id)
-        .bind(weight_unit)
-        .bind(measurement_unit)
-        .bind(theme)
-        .bind(default_rest_timer)
-        .bind(auto_start_rest_timer)
-        .bind(show_warmup_sets)
-        .bind(vibrate_on_timer_end)
-        .bind(sound_on_timer_end)
-        .bind(plate_calculator.map(Json))
-        .bind(compact_mode)
-        .fetch_one(pool)
+        .bind(changes.weight_unit.as_ref())
+        .bind(changes.measurement_unit.as_ref())
+        .bind(changes.theme.as_ref())
+        .bind(changes.default_rest_timer)
+        .bind(changes.auto_start_rest_timer)
+        .bind(changes.show_warmup_sets)
+        .bind(changes.vibrate_on_timer_end)
+        .bind(changes.sound_on_timer_end)
+        .bind(changes.plate_calculator.as_ref().map(Json))
+        .bind(changes.compact_mode)
+        .bind(changes.one_rep_max_formula)
+        .bind(changes.timezone.as_deref())
+        .bind(changes.week_start)
+        .fetch_one(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         Ok(settings)
     }
 }
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::BodyMeasurement;
+
+/// How long measurement tombstones are kept. A client whose last sync is older
+/// than this gets a full snapshot instead of a delta.
+pub const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+pub struct SyncRepository;
+
+impl SyncRepository {
+    /// The database clock, which stamps every `updated_at`
+    pub async fn now(pool: &PgPool) -> Result<DateTime<Utc>, AppError> {
+        let now = sqlx::query_scalar::<_, DateTime<Utc>>("SELECT NOW()")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(now)
+    }
+
+    /// `(id, deleted)` for workouts changed after `since`, or every live
+    /// workout when `since` is `None`.
+    pub async fn workout_changes(
+        pool: &PgPool,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, bool)>, AppError> {
+        let changes = sqlx::query_as::<_, (Uuid, bool)>(
+            r#"
+            SELECT id, deleted_at IS NOT NULL
+            FROM workouts
+            WHERE user_id = $1
+              AND CASE WHEN $2::timestamptz IS NULL THEN deleted_at IS NULL ELSE updated_at > $2 END
+            ORDER BY updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(changes)
+    }
+
+    /// `(id, deleted)` for templates changed after `since`, or every live
+    /// template when `since` is `None`.
+    pub async fn template_changes(
+        pool: &PgPool,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, bool)>, AppError> {
+        let changes = sqlx::query_as::<_, (Uuid, bool)>(
+            r#"
+            SELECT id, deleted_at IS NOT NULL
+            FROM workout_templates
+            WHERE user_id = $1
+              AND CASE WHEN $2::timestamptz IS NULL THEN deleted_at IS NULL ELSE updated_at > $2 END
+            ORDER BY updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(changes)
+    }
+
+    pub async fn changed_measurements(
+        pool: &PgPool,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BodyMeasurement>, AppError> {
+        let measurements = sqlx::query_as::<_, BodyMeasurement>(
+            r#"
+            SELECT * FROM body_measurements
+            WHERE user_id = $1 AND ($2::timestamptz IS NULL OR updated_at > $2)
+            ORDER BY date
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(measurements)
+    }
+
+    pub async fn deleted_measurements(
+        pool: &PgPool,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT entity_id FROM sync_tombstones
+            WHERE user_id = $1 AND entity = 'measurement' AND deleted_at > $2
+            ORDER BY deleted_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// When the user's settings last changed; `None` if they were never saved
+    pub async fn settings_updated_at(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let updated_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            "SELECT updated_at FROM user_settings WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(updated_at)
+    }
+
+    pub async fn delete_expired_tombstones(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "DELETE FROM sync_tombstones WHERE deleted_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(TOMBSTONE_RETENTION_DAYS as i32)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
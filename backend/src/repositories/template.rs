@@ -2,9 +2,10 @@ use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::dto::CreateTemplateExerciseRequest;
+use crate::dto::{CreateTemplateRequest, CreateTemplateSetRequest, UpdateTemplateRequest};
 use crate::error::AppError;
-use crate::models::{TemplateExercise, TemplateSet, WorkoutTemplate};
+use crate::etag::check_version;
+use crate::models::{SetType, TemplateExercise, TemplateSet, WorkoutTemplate};
 
 pub struct TemplateRepository;
 
@@ -12,15 +13,11 @@ impl TemplateRepository {
     pub async fn create(
         pool: &PgPool,
         user_id: Uuid,
-        name: &str,
-        description: Option<&str>,
-        exercises: &[CreateTemplateExerciseRequest],
-        estimated_duration: Option<i32>,
-        tags: Option<&[String]>,
+        template: &CreateTemplateRequest,
     ) -> Result<WorkoutTemplate, AppError> {
         let mut tx = pool.begin().await?;
 
-        let template_id = Uuid::new_v4();
+        let template_id = template.id.unwrap_or_else(Uuid::new_v4);
 
         sqlx::query(
             r#"
@@ -30,14 +27,14 @@ impl TemplateRepository {
         )
         .bind(template_id)
         .bind(user_id)
-        .bind(name)
-        .bind(description)
-        .bind(estimated_duration)
-        .bind(tags)
+        .bind(&template.name)
+        .bind(&template.description)
+        .bind(template.estimated_duration)
+        .bind(&template.tags)
         .execute(&mut *tx)
         .await?;
 
-        for (index, exercise) in exercises.iter().enumerate() {
+        for (index, exercise) in template.exercises.iter().enumerate() {
             let exercise_id = Uuid::new_v4();
 
             sqlx::query(
@@ -57,24 +54,7 @@ impl TemplateRepository {
             .execute(&mut *tx)
             .await?;
 
-            for (set_index, set) in exercise.sets.iter().enumerate() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO template_sets (id, template_exercise_id, set_number, target_reps, target_weight, is_warmup, target_distance_meters, target_duration_seconds)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                    "#,
-                )
-                .bind(Uuid::new_v4())
-                .bind(exercise_id)
-                .bind((set_index + 1) as i32)
-                .bind(set.target_reps)
-                .bind(set.target_weight)
-                .bind(set.is_warmup)
-                .bind(set.target_distance_meters)
-                .bind(set.target_duration_seconds)
-                .execute(&mut *tx)
-                .await?;
-            }
+            Self::insert_sets_tx(&mut tx, exercise_id, &exercise.sets).await?;
         }
 
         tx.commit().await?;
@@ -91,7 +71,7 @@ impl TemplateRepository {
     ) -> Result<Option<WorkoutTemplate>, AppError> {
         let template = sqlx::query_as::<_, WorkoutTemplateRow>(
             r#"
-            SELECT id, user_id, name, description, estimated_duration, created_at, last_used_at, usage_count, tags
+            SELECT id, user_id, name, description, estimated_duration, created_at, last_used_at, usage_count, tags, version
             FROM workout_templates
             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
@@ -110,6 +90,7 @@ impl TemplateRepository {
             created_at: t.created_at,
             last_used_at: t.last_used_at,
             usage_count: t.usage_count,
+            version: t.version,
             tags: t.tags,
         }))
     }
@@ -140,28 +121,23 @@ impl TemplateRepository {
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
-        name: Option<&str>,
-        description: Option<&str>,
-        exercises: Option<&[CreateTemplateExerciseRequest]>,
-        estimated_duration: Option<i32>,
-        tags: Option<&[String]>,
+        expected_version: Option<i32>,
+        changes: &UpdateTemplateRequest,
     ) -> Result<WorkoutTemplate, AppError> {
         let mut tx = pool.begin().await?;
 
-        // Verify ownership
-        let exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM workout_templates WHERE id = $1 AND user_id = $2)",
+        // Verify ownership and lock against concurrent edits
+        let version = sqlx::query_scalar::<_, i32>(
+            "SELECT version FROM workout_templates WHERE id = $1 AND user_id = $2 FOR UPDATE",
         )
         .bind(id)
         .bind(user_id)
-        .fetch_one(&mut *tx)
-        .await?;
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Template not found".to_string()))?;
+        check_version(expected_version, version)?;
 
-        if !exists {
-            return Err(AppError::NotFound("Template not found".to_string()));
-        }
-
-        if let Some(name) = name {
+        if let Some(name) = &changes.name {
             sqlx::query("UPDATE workout_templates SET name = $1 WHERE id = $2")
                 .bind(name)
                 .bind(id)
@@ -169,7 +145,7 @@ impl TemplateRepository {
                 .await?;
         }
 
-        if let Some(desc) = description {
+        if let Some(desc) = &changes.description {
             sqlx::query("UPDATE workout_templates SET description = $1 WHERE id = $2")
                 .bind(desc)
                 .bind(id)
@@ -177,7 +153,7 @@ impl TemplateRepository {
                 .await?;
         }
 
-        if let Some(dur) = estimated_duration {
+        if let Some(dur) = changes.estimated_duration {
             sqlx::query("UPDATE workout_templates SET estimated_duration = $1 WHERE id = $2")
                 .bind(dur)
                 .bind(id)
@@ -185,7 +161,7 @@ impl TemplateRepository {
                 .await?;
         }
 
-        if let Some(tags) = tags {
+        if let Some(tags) = &changes.tags {
             sqlx::query("UPDATE workout_templates SET tags = $1 WHERE id = $2")
                 .bind(tags)
                 .bind(id)
@@ -193,7 +169,7 @@ impl TemplateRepository {
                 .await?;
         }
 
-        if let Some(exercises) = exercises {
+        if let Some(exercises) = &changes.exercises {
             // Delete existing exercises and sets
             sqlx::query(
                 "DELETE FROM template_sets WHERE template_exercise_id IN (SELECT id FROM template_exercises WHERE template_id = $1)",
@@ -228,22 +204,7 @@ impl TemplateRepository {
                 .execute(&mut *tx)
                 .await?;
 
-                for (set_index, set) in exercise.sets.iter().enumerate() {
-                    sqlx::query(
-                        r#"
-                        INSERT INTO template_sets (id, template_exercise_id, set_number, target_reps, target_weight, is_warmup)
-                        VALUES ($1, $2, $3, $4, $5, $6)
-                        "#,
-                    )
-                    .bind(Uuid::new_v4())
-                    .bind(exercise_id)
-                    .bind((set_index + 1) as i32)
-                    .bind(set.target_reps)
-                    .bind(set.target_weight)
-                    .bind(set.is_warmup)
-                    .execute(&mut *tx)
-                    .await?;
-                }
+                Self::insert_sets_tx(&mut tx, exercise_id, &exercise.sets).await?;
             }
         }
 
@@ -254,6 +215,51 @@ impl TemplateRepository {
             .ok_or_else(|| AppError::Internal("Failed to update template".to_string()))
     }
 
+    /// Inserts an exercise's sets in order. A sub-set's parent is given by set
+    /// number, which the request validation has checked points back to a
+    /// main set; the sub-set takes that set's type.
+    async fn insert_sets_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        exercise_id: Uuid,
+        sets: &[CreateTemplateSetRequest],
+    ) -> Result<(), AppError> {
+        let mut inserted: Vec<(Uuid, SetType)> = Vec::with_capacity(sets.len());
+        for (set_index, set) in sets.iter().enumerate() {
+            let parent = set
+                .parent_set_number
+                .and_then(|n| inserted.get(usize::try_from(n - 1).ok()?))
+                .copied();
+            let set_id = Uuid::new_v4();
+            let set_type = parent.map_or(set.set_type, |(_, set_type)| set_type);
+
+            sqlx::query(
+                r#"
+                INSERT INTO template_sets (id, template_exercise_id, set_number, target_reps, target_weight, is_warmup, target_distance_meters, target_duration_seconds, set_type, parent_set_id, target_rir, tempo, is_unilateral)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                "#,
+            )
+            .bind(set_id)
+            .bind(exercise_id)
+            .bind((set_index + 1) as i32)
+            .bind(set.target_reps)
+            .bind(set.target_weight)
+            .bind(set.is_warmup)
+            .bind(set.target_distance_meters)
+            .bind(set.target_duration_seconds)
+            .bind(set_type)
+            .bind(parent.map(|(id, _)| id))
+            .bind(set.target_rir)
+            .bind(set.tempo.as_deref())
+            .bind(set.is_unilateral)
+            .execute(&mut **tx)
+            .await?;
+
+            inserted.push((set_id, set_type));
+        }
+
+        Ok(())
+    }
+
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
         let result = sqlx::query(
             "UPDATE workout_templates SET deleted_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
@@ -299,9 +305,11 @@ impl TemplateRepository {
                 te.id as exercise_id, te.template_id, te.exercise_template_id, te.exercise_name,
                 te.notes, te.rest_seconds, te.order_index, te.superset_id,
                 ts.set_number, ts.target_reps, ts.target_weight, ts.is_warmup,
-                ts.target_distance_meters, ts.target_duration_seconds
+                ts.target_distance_meters, ts.target_duration_seconds, ts.set_type,
+                parent.set_number as parent_set_number, ts.target_rir, ts.tempo, ts.is_unilateral
             FROM template_exercises te
             LEFT JOIN template_sets ts ON ts.template_exercise_id = te.id
+            LEFT JOIN template_sets parent ON parent.id = ts.parent_set_id
             WHERE te.template_id = $1
             ORDER BY te.order_index, ts.set_number
             "#,
@@ -329,17 +337,22 @@ impl TemplateRepository {
             }
 
             // If there's a set (LEFT JOIN may produce NULL for exercises with no sets)
-            if let Some(set_number) = row.set_number {
-                if let Some(exercise) = exercises.last_mut() {
-                    exercise.sets.push(TemplateSet {
-                        set_number,
-                        target_reps: row.target_reps.unwrap_or(0),
-                        target_weight: row.target_weight,
-                        is_warmup: row.is_warmup.unwrap_or(false),
-                        target_distance_meters: row.target_distance_meters,
-                        target_duration_seconds: row.target_duration_seconds,
-                    });
-                }
+            if let Some(set_number) = row.set_number
+                && let Some(exercise) = exercises.last_mut()
+            {
+                exercise.sets.push(TemplateSet {
+                    set_number,
+                    target_reps: row.target_reps.unwrap_or(0),
+                    target_weight: row.target_weight,
+                    is_warmup: row.is_warmup.unwrap_or(false),
+                    target_distance_meters: row.target_distance_meters,
+                    target_duration_seconds: row.target_duration_seconds,
+                    set_type: row.set_type.unwrap_or_default(),
+                    parent_set_number: row.parent_set_number,
+                    target_rir: row.target_rir,
+                    tempo: row.tempo,
+                    is_unilateral: row.is_unilateral.unwrap_or(false),
+                });
             }
         }
 
@@ -369,6 +382,7 @@ struct WorkoutTemplateRow {
     last_used_at: Option<DateTime<Utc>>,
     usage_count: i32,
     tags: Option<Vec<String>>,
+    version: i32,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -403,4 +417,9 @@ struct TemplateExerciseWithSetRow {
     is_warmup: Option<bool>,
     target_distance_meters: Option<i32>,
     target_duration_seconds: Option<i32>,
+    set_type: Option<SetType>,
+    parent_set_number: Option<i32>,
+    target_rir: Option<i16>,
+    tempo: Option<String>,
+    is_unilateral: Option<bool>,
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -16,7 +17,7 @@ impl UserRepository {
             r#"
             INSERT INTO users (id, email, password_hash, created_at, updated_at)
             VALUES ($1, $2, $3, NOW(), NOW())
-            RETURNING id, email, password_hash, is_admin, created_at, updated_at
+            RETURNING id, email, password_hash, FALSE AS is_admin, email_verified_at, failed_login_attempts, locked_until, scheduled_deletion_at, created_at, updated_at
             "#,
         )
         .bind(Uuid::new_v4())
@@ -31,7 +32,8 @@ impl UserRepository {
     pub async fn find_by_email(pool: &PgPool, email: &str) -> Result<Option<User>, AppError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, is_admin, created_at, updated_at
+            SELECT id, email, password_hash, email_verified_at, failed_login_attempts, locked_until, scheduled_deletion_at, created_at, updated_at,
+                EXISTS (SELECT 1 FROM user_roles r WHERE r.user_id = users.id AND r.role = 'admin') AS is_admin
             FROM users
             WHERE email = $1
             "#,
@@ -46,7 +48,8 @@ impl UserRepository {
     pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<User>, AppError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, email, password_hash, is_admin, created_at, updated_at
+            SELECT id, email, password_hash, email_verified_at, failed_login_attempts, locked_until, scheduled_deletion_at, created_at, updated_at,
+                EXISTS (SELECT 1 FROM user_roles r WHERE r.user_id = users.id AND r.role = 'admin') AS is_admin
             FROM users
             WHERE id = $1
             "#,
@@ -58,6 +61,14 @@ impl UserRepository {
         Ok(user)
     }
 
+    pub async fn list_ids(pool: &PgPool) -> Result<Vec<Uuid>, AppError> {
+        let ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users ORDER BY created_at")
+            .fetch_all(pool)
+            .await?;
+
+        Ok(ids)
+    }
+
     pub async fn update_password(
         pool: &PgPool,
         id: Uuid,
@@ -73,4 +84,116 @@ impl UserRepository {
 
         Ok(())
     }
+
+    pub async fn mark_email_verified(pool: &PgPool, id: Uuid) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn is_email_verified(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+        let verified: bool = sqlx::query_scalar(
+            "SELECT email_verified_at IS NOT NULL FROM users WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or(false);
+
+        Ok(verified)
+    }
+
+    /// Counts a failed login and, from the `threshold`th consecutive failure on,
+    /// locks the account for `base_secs` doubling per extra failure up to
+    /// `max_secs`. Returns the lock expiry, if any.
+    pub async fn record_failed_login(
+        pool: &PgPool,
+        id: Uuid,
+        threshold: u32,
+        base_secs: u64,
+        max_secs: u64,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let locked_until: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            UPDATE users SET
+                failed_login_attempts = failed_login_attempts + 1,
+                locked_until = CASE
+                    WHEN failed_login_attempts + 1 >= $2 THEN NOW() + make_interval(secs =>
+                        LEAST($3 * POWER(2, LEAST(failed_login_attempts + 1 - $2, 30)), $4))
+                    ELSE locked_until
+                END
+            WHERE id = $1
+            RETURNING locked_until
+            "#,
+        )
+        .bind(id)
+        .bind(threshold as i32)
+        .bind(base_secs as f64)
+        .bind(max_secs as f64)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(locked_until)
+    }
+
+    /// Clears the failure counter and any lockout, after a successful login,
+    /// a password reset or an admin unlock. Returns false if the user is gone.
+    pub async fn clear_failed_logins(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE users SET failed_login_attempts = 0, locked_until = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn schedule_deletion(
+        pool: &PgPool,
+        id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE users SET scheduled_deletion_at = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(at)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns false if no deletion was scheduled.
+    pub async fn cancel_deletion(pool: &PgPool, id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET scheduled_deletion_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND scheduled_deletion_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hard-deletes accounts whose grace period has run out. Everything they
+    /// own goes with them through `ON DELETE CASCADE`.
+    pub async fn purge_scheduled_deletions(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM users WHERE scheduled_deletion_at <= NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
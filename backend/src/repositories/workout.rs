@@ -3,9 +3,14 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::cursor::decode_cursor;
-use crate::dto::{ExerciseOrderUpdate, WorkoutQuery};
+use crate::dto::{ExerciseOrderUpdate, ImportWorkoutRequest, UpdateSetRequest, WorkoutQuery};
 use crate::error::AppError;
-use crate::models::{ExerciseCategory, Workout, WorkoutExercise, WorkoutSet, WorkoutStatus};
+use crate::etag::check_version;
+use crate::repositories::JobRepository;
+use crate::models::{
+    ExerciseCategory, ExerciseHistorySet, Job, SetType, Workout, WorkoutExercise, WorkoutSet,
+    WorkoutStatus,
+};
 
 pub struct WorkoutRepository;
 
@@ -13,6 +18,7 @@ impl WorkoutRepository {
     pub async fn create(
         pool: &PgPool,
         user_id: Uuid,
+        id: Option<Uuid>,
         name: &str,
         template_id: Option<Uuid>,
         notes: Option<&str>,
@@ -21,10 +27,10 @@ impl WorkoutRepository {
             r#"
             INSERT INTO workouts (id, user_id, name, started_at, total_volume, total_sets, total_reps, status, template_id, notes)
             VALUES ($1, $2, $3, NOW(), 0, 0, 0, 'in-progress', $4, $5)
-            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(id.unwrap_or_else(Uuid::new_v4))
         .bind(user_id)
         .bind(name)
         .bind(template_id)
@@ -42,7 +48,7 @@ impl WorkoutRepository {
     ) -> Result<Option<Workout>, AppError> {
         let workout = sqlx::query_as::<_, Workout>(
             r#"
-            SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
             FROM workouts
             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
@@ -73,7 +79,8 @@ impl WorkoutRepository {
                         w.total_volume, w.total_reps, w.duration,
                         w.status, w.template_id, w.notes, w.tags,
                         (SELECT COUNT(*)::int FROM workout_exercises WHERE workout_id = w.id) as exercise_count,
-                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true) as total_sets
+                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true AND ws2.parent_set_id IS NULL) as total_sets,
+                        (SELECT AVG(ws3.rest_seconds_actual)::int FROM workout_exercises we3 JOIN workout_sets ws3 ON ws3.workout_exercise_id = we3.id WHERE we3.workout_id = w.id AND NOT ws3.is_warmup AND ws3.parent_set_id IS NULL) as avg_rest_seconds
                     FROM workouts w
                     WHERE w.user_id = $1 AND w.deleted_at IS NULL
                     ORDER BY w.started_at DESC, w.id DESC
@@ -94,7 +101,8 @@ impl WorkoutRepository {
                         w.total_volume, w.total_reps, w.duration,
                         w.status, w.template_id, w.notes, w.tags,
                         (SELECT COUNT(*)::int FROM workout_exercises WHERE workout_id = w.id) as exercise_count,
-                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true) as total_sets
+                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true AND ws2.parent_set_id IS NULL) as total_sets,
+                        (SELECT AVG(ws3.rest_seconds_actual)::int FROM workout_exercises we3 JOIN workout_sets ws3 ON ws3.workout_exercise_id = we3.id WHERE we3.workout_id = w.id AND NOT ws3.is_warmup AND ws3.parent_set_id IS NULL) as avg_rest_seconds
                     FROM workouts w
                     WHERE w.user_id = $1 AND w.status = $2 AND w.deleted_at IS NULL
                     ORDER BY w.started_at DESC, w.id DESC
@@ -116,7 +124,8 @@ impl WorkoutRepository {
                         w.total_volume, w.total_reps, w.duration,
                         w.status, w.template_id, w.notes, w.tags,
                         (SELECT COUNT(*)::int FROM workout_exercises WHERE workout_id = w.id) as exercise_count,
-                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true) as total_sets
+                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true AND ws2.parent_set_id IS NULL) as total_sets,
+                        (SELECT AVG(ws3.rest_seconds_actual)::int FROM workout_exercises we3 JOIN workout_sets ws3 ON ws3.workout_exercise_id = we3.id WHERE we3.workout_id = w.id AND NOT ws3.is_warmup AND ws3.parent_set_id IS NULL) as avg_rest_seconds
                     FROM workouts w
                     WHERE w.user_id = $1 AND w.deleted_at IS NULL
                       AND (w.started_at < $2 OR (w.started_at = $2 AND w.id < $3))
@@ -140,7 +149,8 @@ impl WorkoutRepository {
                         w.total_volume, w.total_reps, w.duration,
                         w.status, w.template_id, w.notes, w.tags,
                         (SELECT COUNT(*)::int FROM workout_exercises WHERE workout_id = w.id) as exercise_count,
-                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true) as total_sets
+                        (SELECT COUNT(ws2.id)::int FROM workout_exercises we2 JOIN workout_sets ws2 ON ws2.workout_exercise_id = we2.id WHERE we2.workout_id = w.id AND ws2.is_completed = true AND ws2.parent_set_id IS NULL) as total_sets,
+                        (SELECT AVG(ws3.rest_seconds_actual)::int FROM workout_exercises we3 JOIN workout_sets ws3 ON ws3.workout_exercise_id = we3.id WHERE we3.workout_id = w.id AND NOT ws3.is_warmup AND ws3.parent_set_id IS NULL) as avg_rest_seconds
                     FROM workouts w
                     WHERE w.user_id = $1 AND w.status = $2 AND w.deleted_at IS NULL
                       AND (w.started_at < $3 OR (w.started_at = $3 AND w.id < $4))
@@ -165,16 +175,20 @@ impl WorkoutRepository {
         pool: &PgPool,
         id: Uuid,
         user_id: Uuid,
+        expected_version: Option<i32>,
         name: Option<&str>,
         notes: Option<&str>,
         tags: Option<&[String]>,
     ) -> Result<Workout, AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_version_tx(&mut tx, id, user_id, expected_version).await?;
+
         if let Some(name) = name {
             sqlx::query("UPDATE workouts SET name = $1 WHERE id = $2 AND user_id = $3")
                 .bind(name)
                 .bind(id)
                 .bind(user_id)
-                .execute(pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -183,7 +197,7 @@ impl WorkoutRepository {
                 .bind(notes)
                 .bind(id)
                 .bind(user_id)
-                .execute(pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
@@ -192,44 +206,57 @@ impl WorkoutRepository {
                 .bind(tags)
                 .bind(id)
                 .bind(user_id)
-                .execute(pool)
+                .execute(&mut *tx)
                 .await?;
         }
 
+        tx.commit().await?;
+
         Self::find_by_id(pool, id, user_id)
             .await?
             .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))
     }
 
     pub async fn delete(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
         let result = sqlx::query(
             "UPDATE workouts SET deleted_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
         )
         .bind(id)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Workout not found".to_string()));
         }
 
+        // Records set in this workout no longer count
+        Self::queue_record_recompute_tx(&mut tx, id, None).await?;
+        tx.commit().await?;
+
         Ok(())
     }
 
     pub async fn restore(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Workout, AppError> {
+        let mut tx = pool.begin().await?;
+
         let result = sqlx::query(
             "UPDATE workouts SET deleted_at = NULL WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL",
         )
         .bind(id)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::NotFound("Workout not found or not deleted".to_string()));
         }
 
+        Self::queue_record_recompute_tx(&mut tx, id, None).await?;
+        tx.commit().await?;
+
         Self::find_by_id(pool, id, user_id)
             .await?
             .ok_or_else(|| AppError::Internal("Failed to restore workout".to_string()))
@@ -242,24 +269,16 @@ impl WorkoutRepository {
     ) -> Result<(Workout, bool), AppError> {
         let mut tx = pool.begin().await?;
 
-        // Compute totals inside the transaction so the snapshot and the UPDATE
-        // are atomic. FOR SHARE is not compatible with aggregate functions in
-        // PostgreSQL; the status guard in update_set() already prevents
-        // concurrent mutations once the workout is being completed.
-        let stats = sqlx::query_as::<_, WorkoutStats>(
-            r#"
-            SELECT
-                COALESCE(SUM(CASE WHEN ws.is_completed AND NOT ws.is_warmup THEN ws.actual_weight * ws.actual_reps ELSE 0 END), 0) as total_volume,
-                COALESCE(COUNT(CASE WHEN ws.is_completed AND NOT ws.is_warmup THEN 1 END), 0)::int as total_sets,
-                COALESCE(SUM(CASE WHEN ws.is_completed AND NOT ws.is_warmup THEN ws.actual_reps ELSE 0 END), 0)::int as total_reps
-            FROM workout_exercises we
-            JOIN workout_sets ws ON ws.workout_exercise_id = we.id
-            WHERE we.workout_id = $1
-            "#,
-        )
-        .bind(id)
-        .fetch_one(&mut *tx)
-        .await?;
+        // Lock the workout before computing totals so the snapshot and the
+        // UPDATE are atomic; set edits take the same lock (see update_set()).
+        sqlx::query("SELECT id FROM workouts WHERE id = $1 AND user_id = $2 FOR UPDATE")
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let stats = Self::totals_tx(&mut tx, id).await?;
+        let paused_seconds = Self::end_pause_tx(&mut tx, id).await?;
 
         let updated = sqlx::query_as::<_, Workout>(
             r#"
@@ -269,9 +288,9 @@ impl WorkoutRepository {
                 total_volume = $3,
                 total_sets = $4,
                 total_reps = $5,
-                duration = EXTRACT(EPOCH FROM (NOW() - started_at))::int
+                duration = GREATEST(EXTRACT(EPOCH FROM (NOW() - started_at))::int - $6, 0)
             WHERE id = $1 AND user_id = $2 AND status = 'in-progress'
-            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
             "#,
         )
         .bind(id)
@@ -279,9 +298,23 @@ impl WorkoutRepository {
         .bind(stats.total_volume)
         .bind(stats.total_sets)
         .bind(stats.total_reps)
+        .bind(paused_seconds)
         .fetch_optional(&mut *tx)
         .await?;
 
+        // Queue PR detection in the same transaction so a completion can't
+        // commit without it
+        if updated.is_some() {
+            JobRepository::enqueue_tx(
+                &mut tx,
+                &Job::DetectPersonalRecords {
+                    workout_id: id,
+                    user_id,
+                },
+            )
+            .await?;
+        }
+
         tx.commit().await?;
 
         if let Some(workout) = updated {
@@ -291,7 +324,7 @@ impl WorkoutRepository {
         // No rows updated — workout was not in-progress. Return it as-is if it
         // is already completed (idempotent), otherwise 404.
         let existing = sqlx::query_as::<_, Workout>(
-            r#"SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            r#"SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
                FROM workouts WHERE id = $1 AND user_id = $2"#,
         )
         .bind(id)
@@ -304,20 +337,33 @@ impl WorkoutRepository {
     }
 
     pub async fn cancel(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<Workout, AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("SELECT id FROM workouts WHERE id = $1 AND user_id = $2 FOR UPDATE")
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let paused_seconds = Self::end_pause_tx(&mut tx, id).await?;
+
         let updated = sqlx::query_as::<_, Workout>(
             r#"
             UPDATE workouts
             SET status = 'cancelled',
-                duration = EXTRACT(EPOCH FROM (NOW() - started_at))::int
+                duration = GREATEST(EXTRACT(EPOCH FROM (NOW() - started_at))::int - $3, 0)
             WHERE id = $1 AND user_id = $2 AND status = 'in-progress'
-            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
             "#,
         )
         .bind(id)
         .bind(user_id)
-        .fetch_optional(pool)
+        .bind(paused_seconds)
+        .fetch_optional(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
         if let Some(workout) = updated {
             return Ok(workout);
         }
@@ -325,7 +371,7 @@ impl WorkoutRepository {
         // No rows updated — workout was not in-progress. Return it as-is if it
         // is already cancelled (idempotent), otherwise 404.
         let existing = sqlx::query_as::<_, Workout>(
-            r#"SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags
+            r#"SELECT id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
                FROM workouts WHERE id = $1 AND user_id = $2"#,
         )
         .bind(id)
@@ -337,10 +383,250 @@ impl WorkoutRepository {
         Ok(existing)
     }
 
+    /// Pauses an in-progress workout. Pausing a paused workout changes nothing.
+    pub async fn pause(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_in_progress_tx(&mut tx, id, user_id).await?;
+
+        // The partial unique index allows one open pause per workout
+        let started = sqlx::query(
+            "INSERT INTO workout_pauses (workout_id) VALUES ($1) ON CONFLICT DO NOTHING",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        if started.rows_affected() > 0 {
+            Self::touch_tx(&mut tx, id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Resumes a paused workout. Resuming one that isn't paused changes nothing.
+    pub async fn resume(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_in_progress_tx(&mut tx, id, user_id).await?;
+
+        let ended = sqlx::query(
+            "UPDATE workout_pauses SET ended_at = NOW() WHERE workout_id = $1 AND ended_at IS NULL",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        if ended.rows_affected() > 0 {
+            Self::touch_tx(&mut tx, id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// When the current pause began, if the workout is paused, and the time
+    /// paused so far, including the current pause.
+    pub async fn pause_state(pool: &PgPool, workout_id: Uuid) -> Result<PauseState, AppError> {
+        let state = sqlx::query_as::<_, PauseState>(
+            r#"
+            SELECT
+                MAX(started_at) FILTER (WHERE ended_at IS NULL) AS paused_at,
+                COALESCE(SUM(EXTRACT(EPOCH FROM (COALESCE(ended_at, NOW()) - started_at))), 0)::int
+                    AS paused_seconds
+            FROM workout_pauses
+            WHERE workout_id = $1
+            "#,
+        )
+        .bind(workout_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Writes a whole workout in one transaction. `exercise_names` lines up
+    /// with `workout.exercises`. A completed import gets its totals computed
+    /// as `complete()` does and queues record detection, or a recompute of
+    /// its exercises when it is older than workouts already logged.
+    pub async fn import(
+        pool: &PgPool,
+        user_id: Uuid,
+        workout: &ImportWorkoutRequest,
+        status: &WorkoutStatus,
+        duration: Option<i32>,
+        exercise_names: &[String],
+    ) -> Result<Workout, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO workouts (id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags)
+            VALUES ($1, $2, $3, $4, $5, 0, 0, 0, $6, $7, $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(workout.id.unwrap_or_else(Uuid::new_v4))
+        .bind(user_id)
+        .bind(&workout.name)
+        .bind(workout.started_at)
+        .bind(workout.completed_at)
+        .bind(duration)
+        .bind(status)
+        .bind(workout.template_id)
+        .bind(workout.notes.as_deref())
+        .bind(&workout.tags)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Superset IDs from the document only group exercises; each group gets a new one
+        let mut supersets: std::collections::HashMap<Uuid, Uuid> =
+            std::collections::HashMap::new();
+        for (order_index, (exercise, exercise_name)) in
+            workout.exercises.iter().zip(exercise_names).enumerate()
+        {
+            let superset_id = exercise
+                .superset_id
+                .map(|s| *supersets.entry(s).or_insert_with(Uuid::new_v4));
+
+            let exercise_id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO workout_exercises (id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(id)
+            .bind(&exercise.exercise_template_id)
+            .bind(exercise_name)
+            .bind(exercise.notes.as_deref())
+            .bind(order_index as i32)
+            .bind(superset_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            // (id, type) of the sets so far, for sub-sets to link to
+            let mut inserted: Vec<(Uuid, SetType)> = Vec::with_capacity(exercise.sets.len());
+            for (i, set) in exercise.sets.iter().enumerate() {
+                let completed_at = match set.is_completed {
+                    true => set.completed_at.or(workout.completed_at),
+                    false => None,
+                };
+                let parent = set
+                    .parent_set_number
+                    .and_then(|n| inserted.get(usize::try_from(n - 1).ok()?))
+                    .copied();
+                let set_id = Uuid::new_v4();
+                let set_type = parent.map_or(set.set_type, |(_, set_type)| set_type);
+                // As when updating a set, per-side reps default to the weaker side
+                let weaker_side = match (set.actual_reps_left, set.actual_reps_right) {
+                    (Some(left), Some(right)) => Some(left.min(right)),
+                    (left, right) => left.or(right),
+                };
+                let actual_reps = set.actual_reps.or(weaker_side);
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO workout_sets
+                      (id, workout_exercise_id, set_number, target_reps, actual_reps, target_weight,
+                       actual_weight, is_warmup, is_completed, completed_at, rpe, distance_meters,
+                       duration_seconds, calories, target_distance_meters, target_duration_seconds,
+                       set_type, parent_set_id, target_rir, rir, tempo, is_unilateral,
+                       actual_reps_left, actual_reps_right)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                            $19, $20, $21, $22, $23, $24)
+                    "#,
+                )
+                .bind(set_id)
+                .bind(exercise_id)
+                .bind(i as i32 + 1)
+                .bind(set.target_reps)
+                .bind(actual_reps)
+                .bind(set.target_weight)
+                .bind(set.actual_weight)
+                .bind(set.is_warmup)
+                .bind(set.is_completed)
+                .bind(completed_at)
+                .bind(set.rpe)
+                .bind(set.distance_meters)
+                .bind(set.duration_seconds)
+                .bind(set.calories)
+                .bind(set.target_distance_meters)
+                .bind(set.target_duration_seconds)
+                .bind(set_type)
+                .bind(parent.map(|(id, _)| id))
+                .bind(set.target_rir)
+                .bind(set.rir)
+                .bind(set.tempo.as_deref())
+                .bind(set.is_unilateral)
+                .bind(set.actual_reps_left)
+                .bind(set.actual_reps_right)
+                .execute(&mut *tx)
+                .await?;
+
+                inserted.push((set_id, set_type));
+            }
+            Self::refresh_rest_tx(&mut tx, exercise_id).await?;
+        }
+
+        let stats = Self::totals_tx(&mut tx, id).await?;
+        let imported = sqlx::query_as::<_, Workout>(
+            r#"
+            UPDATE workouts
+            SET total_volume = $2, total_sets = $3, total_reps = $4
+            WHERE id = $1
+            RETURNING id, user_id, name, started_at, completed_at, total_volume, total_sets, total_reps, duration, status, template_id, notes, tags, version
+            "#,
+        )
+        .bind(id)
+        .bind(stats.total_volume)
+        .bind(stats.total_sets)
+        .bind(stats.total_reps)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if *status == WorkoutStatus::Completed {
+            // Detection only compares against current records, so a workout
+            // dated before others needs its exercises' history replayed
+            let backdated = sqlx::query_scalar::<_, bool>(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM workouts
+                    WHERE user_id = $1 AND id <> $2 AND status = 'completed'
+                      AND deleted_at IS NULL AND completed_at > $3
+                )
+                "#,
+            )
+            .bind(user_id)
+            .bind(id)
+            .bind(imported.completed_at)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if backdated {
+                Self::queue_record_recompute_tx(&mut tx, id, None).await?;
+            } else {
+                JobRepository::enqueue_tx(
+                    &mut tx,
+                    &Job::DetectPersonalRecords {
+                        workout_id: id,
+                        user_id,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(imported)
+    }
+
     // Exercise methods
     pub async fn add_exercise(
         pool: &PgPool,
         workout_id: Uuid,
+        id: Option<Uuid>,
         exercise_template_id: &str,
         exercise_name: &str,
         notes: Option<&str>,
@@ -357,10 +643,10 @@ impl WorkoutRepository {
             r#"
             INSERT INTO workout_exercises (id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id)
             VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id
+            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id, version
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(id.unwrap_or_else(Uuid::new_v4))
         .bind(workout_id)
         .bind(exercise_template_id)
         .bind(exercise_name)
@@ -392,7 +678,7 @@ impl WorkoutRepository {
     ) -> Result<Vec<WorkoutExercise>, AppError> {
         let exercises = sqlx::query_as::<_, WorkoutExercise>(
             r#"
-            SELECT id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id
+            SELECT id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id, version
             FROM workout_exercises
             WHERE workout_id = $1
             ORDER BY order_index
@@ -416,12 +702,14 @@ impl WorkoutRepository {
             SELECT
                 we.id as exercise_id, we.workout_id, we.exercise_template_id, we.exercise_name,
                 we.notes as exercise_notes, we.order_index, we.superset_id,
-                et.category as exercise_category,
+                we.version as exercise_version, et.category as exercise_category,
                 ws.id as set_id, ws.workout_exercise_id, ws.set_number, ws.target_reps,
                 ws.actual_reps, ws.target_weight, ws.actual_weight, ws.is_warmup,
                 ws.is_completed, ws.completed_at, ws.rpe,
                 ws.distance_meters, ws.duration_seconds, ws.calories,
-                ws.target_distance_meters, ws.target_duration_seconds
+                ws.target_distance_meters, ws.target_duration_seconds, ws.rest_seconds_actual,
+                ws.set_type, ws.parent_set_id, ws.target_rir, ws.rir, ws.tempo, ws.is_unilateral,
+                ws.actual_reps_left, ws.actual_reps_right, ws.volume_reps, ws.version as set_version
             FROM workout_exercises we
             LEFT JOIN exercise_templates et ON et.id = we.exercise_template_id
             LEFT JOIN workout_sets ws ON ws.workout_exercise_id = we.id
@@ -449,6 +737,7 @@ impl WorkoutRepository {
                         notes: row.exercise_notes.clone(),
                         order_index: row.order_index,
                         superset_id: row.superset_id,
+                        version: row.exercise_version,
                         exercise_category: row.exercise_category.clone(),
                     },
                     Vec::new(),
@@ -456,27 +745,38 @@ impl WorkoutRepository {
             }
 
             // If there's a set (LEFT JOIN may produce NULL set_id for exercises with no sets)
-            if let Some(set_id) = row.set_id {
-                if let Some((_, sets)) = exercises.last_mut() {
-                    sets.push(WorkoutSet {
-                        id: set_id,
-                        workout_exercise_id: row.workout_exercise_id.unwrap_or(row.exercise_id),
-                        set_number: row.set_number.unwrap_or(0),
-                        target_reps: row.target_reps,
-                        actual_reps: row.actual_reps,
-                        target_weight: row.target_weight,
-                        actual_weight: row.actual_weight,
-                        is_warmup: row.is_warmup.unwrap_or(false),
-                        is_completed: row.is_completed.unwrap_or(false),
-                        completed_at: row.completed_at,
-                        rpe: row.rpe,
-                        distance_meters: row.distance_meters,
-                        duration_seconds: row.duration_seconds,
-                        calories: row.calories,
-                        target_distance_meters: row.target_distance_meters,
-                        target_duration_seconds: row.target_duration_seconds,
-                    });
-                }
+            if let Some(set_id) = row.set_id
+                && let Some((_, sets)) = exercises.last_mut()
+            {
+                sets.push(WorkoutSet {
+                    id: set_id,
+                    workout_exercise_id: row.workout_exercise_id.unwrap_or(row.exercise_id),
+                    set_number: row.set_number.unwrap_or(0),
+                    target_reps: row.target_reps,
+                    actual_reps: row.actual_reps,
+                    target_weight: row.target_weight,
+                    actual_weight: row.actual_weight,
+                    is_warmup: row.is_warmup.unwrap_or(false),
+                    is_completed: row.is_completed.unwrap_or(false),
+                    completed_at: row.completed_at,
+                    rpe: row.rpe,
+                    distance_meters: row.distance_meters,
+                    duration_seconds: row.duration_seconds,
+                    calories: row.calories,
+                    target_distance_meters: row.target_distance_meters,
+                    target_duration_seconds: row.target_duration_seconds,
+                    rest_seconds_actual: row.rest_seconds_actual,
+                    set_type: row.set_type.unwrap_or_default(),
+                    parent_set_id: row.parent_set_id,
+                    target_rir: row.target_rir,
+                    rir: row.rir,
+                    tempo: row.tempo,
+                    is_unilateral: row.is_unilateral.unwrap_or(false),
+                    actual_reps_left: row.actual_reps_left,
+                    actual_reps_right: row.actual_reps_right,
+                    volume_reps: row.volume_reps,
+                    version: row.set_version.unwrap_or(1),
+                });
             }
         }
 
@@ -494,7 +794,8 @@ impl WorkoutRepository {
             SELECT id, workout_exercise_id, set_number, target_reps, actual_reps,
                    target_weight, actual_weight, is_warmup, is_completed, completed_at, rpe,
                    distance_meters, duration_seconds, calories,
-                   target_distance_meters, target_duration_seconds
+                   target_distance_meters, target_duration_seconds, rest_seconds_actual, set_type, parent_set_id,
+                   target_rir, rir, tempo, is_unilateral, actual_reps_left, actual_reps_right, volume_reps, version
             FROM workout_sets
             WHERE workout_exercise_id = ANY($1)
             ORDER BY workout_exercise_id, set_number
@@ -515,33 +816,70 @@ impl WorkoutRepository {
 
     pub async fn update_exercise(
         pool: &PgPool,
+        workout_id: Uuid,
         exercise_id: Uuid,
+        user_id: Uuid,
+        expected_version: Option<i32>,
         notes: Option<&str>,
     ) -> Result<WorkoutExercise, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let version = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT we.version FROM workout_exercises we
+            JOIN workouts w ON w.id = we.workout_id
+            WHERE we.id = $1 AND we.workout_id = $2 AND w.user_id = $3
+            FOR UPDATE OF we
+            "#,
+        )
+        .bind(exercise_id)
+        .bind(workout_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+        check_version(expected_version, version)?;
+
         let exercise = sqlx::query_as::<_, WorkoutExercise>(
             r#"
             UPDATE workout_exercises SET notes = $1 WHERE id = $2
-            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id
+            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id, version
             "#,
         )
         .bind(notes)
         .bind(exercise_id)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
 
         Ok(exercise)
     }
 
-    pub async fn delete_exercise(pool: &PgPool, exercise_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM workout_exercises WHERE id = $1")
-            .bind(exercise_id)
-            .execute(pool)
-            .await?;
+    pub async fn delete_exercise(
+        pool: &PgPool,
+        exercise_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("Exercise not found".to_string()));
-        }
+        let deleted = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            DELETE FROM workout_exercises we
+            USING workouts w
+            WHERE we.id = $1 AND w.id = we.workout_id AND w.user_id = $2
+            RETURNING we.workout_id, we.exercise_template_id
+            "#,
+        )
+        .bind(exercise_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+
+        let (workout_id, exercise_template_id) = deleted;
+        Self::after_completed_edit_tx(&mut tx, workout_id, &exercise_template_id).await?;
+        tx.commit().await?;
 
         Ok(())
     }
@@ -550,15 +888,35 @@ impl WorkoutRepository {
     pub async fn add_set(
         pool: &PgPool,
         exercise_id: Uuid,
-        target_reps: Option<i32>,
-        target_weight: Option<f64>,
-        is_warmup: bool,
-        distance_meters: Option<f64>,
-        duration_seconds: Option<i32>,
-        calories: Option<i32>,
-        target_distance_meters: Option<i32>,
-        target_duration_seconds: Option<i32>,
+        new_set: NewSet<'_>,
     ) -> Result<WorkoutSet, AppError> {
+        // A sub-set hangs off a main set of the same exercise and takes its type
+        let set_type = match new_set.parent_set_id {
+            Some(parent_set_id) => {
+                let (parent_type, grandparent) = sqlx::query_as::<_, (SetType, Option<Uuid>)>(
+                    "SELECT set_type, parent_set_id FROM workout_sets WHERE id = $1 AND workout_exercise_id = $2",
+                )
+                .bind(parent_set_id)
+                .bind(exercise_id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Parent set not found".to_string()))?;
+
+                if grandparent.is_some() {
+                    return Err(AppError::Validation(
+                        "A sub-set can't have sub-sets of its own".to_string(),
+                    ));
+                }
+                if !parent_type.has_sub_sets() {
+                    return Err(AppError::Validation(
+                        "Only drop, rest-pause and cluster sets have sub-sets".to_string(),
+                    ));
+                }
+                parent_type
+            }
+            None => new_set.set_type,
+        };
+
         let set_number = sqlx::query_scalar::<_, i32>(
             "SELECT COALESCE(MAX(set_number), 0) + 1 FROM workout_sets WHERE workout_exercise_id = $1",
         )
@@ -571,25 +929,32 @@ impl WorkoutRepository {
             INSERT INTO workout_sets
               (id, workout_exercise_id, set_number, target_reps, target_weight,
                is_warmup, is_completed, distance_meters, duration_seconds, calories,
-               target_distance_meters, target_duration_seconds)
-            VALUES ($1, $2, $3, $4, $5, $6, false, $7, $8, $9, $10, $11)
+               target_distance_meters, target_duration_seconds, set_type, parent_set_id,
+               target_rir, tempo, is_unilateral)
+            VALUES ($1, $2, $3, $4, $5, $6, false, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             RETURNING id, workout_exercise_id, set_number, target_reps, actual_reps,
               target_weight, actual_weight, is_warmup, is_completed, completed_at, rpe,
               distance_meters, duration_seconds, calories,
-              target_distance_meters, target_duration_seconds
+              target_distance_meters, target_duration_seconds, rest_seconds_actual, set_type, parent_set_id,
+              target_rir, rir, tempo, is_unilateral, actual_reps_left, actual_reps_right, volume_reps, version
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(new_set.id.unwrap_or_else(Uuid::new_v4))
         .bind(exercise_id)
         .bind(set_number)
-        .bind(target_reps)
-        .bind(target_weight)
-        .bind(is_warmup)
-        .bind(distance_meters)
-        .bind(duration_seconds)
-        .bind(calories)
-        .bind(target_distance_meters)
-        .bind(target_duration_seconds)
+        .bind(new_set.target_reps)
+        .bind(new_set.target_weight)
+        .bind(new_set.is_warmup)
+        .bind(new_set.distance_meters)
+        .bind(new_set.duration_seconds)
+        .bind(new_set.calories)
+        .bind(new_set.target_distance_meters)
+        .bind(new_set.target_duration_seconds)
+        .bind(set_type)
+        .bind(new_set.parent_set_id)
+        .bind(new_set.target_rir)
+        .bind(new_set.tempo)
+        .bind(new_set.is_unilateral)
         .fetch_one(pool)
         .await?;
 
@@ -601,7 +966,8 @@ impl WorkoutRepository {
             r#"
             SELECT id, workout_exercise_id, set_number, target_reps, actual_reps, target_weight, actual_weight,
                    is_warmup, is_completed, completed_at, rpe, distance_meters, duration_seconds, calories,
-                   target_distance_meters, target_duration_seconds
+                   target_distance_meters, target_duration_seconds, rest_seconds_actual, set_type, parent_set_id,
+                   target_rir, rir, tempo, is_unilateral, actual_reps_left, actual_reps_right, volume_reps, version
             FROM workout_sets
             WHERE workout_exercise_id = $1
             ORDER BY set_number
@@ -617,54 +983,88 @@ impl WorkoutRepository {
     pub async fn update_set(
         pool: &PgPool,
         set_id: Uuid,
-        target_reps: Option<i32>,
-        actual_reps: Option<i32>,
-        target_weight: Option<f64>,
-        actual_weight: Option<f64>,
-        is_warmup: Option<bool>,
-        is_completed: Option<bool>,
-        rpe: Option<i16>,
-        distance_meters: Option<f64>,
-        duration_seconds: Option<i32>,
-        calories: Option<i32>,
+        user_id: Uuid,
+        expected_version: Option<i32>,
+        changes: &UpdateSetRequest,
     ) -> Result<WorkoutSet, AppError> {
-        // Reject mutations on workouts that are no longer in-progress.
-        let workout_status = sqlx::query_scalar::<_, WorkoutStatus>(
-            r#"
-            SELECT w.status FROM workouts w
-            JOIN workout_exercises we ON we.workout_id = w.id
-            JOIN workout_sets ws ON ws.workout_exercise_id = we.id
-            WHERE ws.id = $1
-            "#,
-        )
-        .bind(set_id)
-        .fetch_optional(pool)
-        .await?;
+        let mut tx = pool.begin().await?;
+
+        // Locks the workout so the edit can't interleave with complete()
+        let owner = Self::lock_set_workout_tx(&mut tx, set_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Set not found".to_string()))?;
+
+        // Completed workouts stay editable so mistakes can be corrected
+        // afterwards; their totals and records are recomputed below.
+        if owner.status == WorkoutStatus::Cancelled {
+            return Err(AppError::Conflict(
+                "Cannot modify sets on a cancelled workout".to_string(),
+            ));
+        }
 
-        match workout_status {
-            Some(WorkoutStatus::Completed) | Some(WorkoutStatus::Cancelled) => {
-                return Err(AppError::Conflict(
-                    "Cannot modify sets on a completed workout".to_string(),
+        let (version, current_type, parent_set_id, was_unilateral) =
+            sqlx::query_as::<_, (i32, SetType, Option<Uuid>, bool)>(
+                r#"
+                SELECT version, set_type, parent_set_id, is_unilateral
+                FROM workout_sets WHERE id = $1 FOR UPDATE
+                "#,
+            )
+            .bind(set_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        check_version(expected_version, version)?;
+
+        let has_sides = changes.actual_reps_left.is_some() || changes.actual_reps_right.is_some();
+        if has_sides && !changes.is_unilateral.unwrap_or(was_unilateral) {
+            return Err(AppError::Validation(
+                "Left and right reps are only logged on unilateral sets".to_string(),
+            ));
+        }
+
+        if let Some(set_type) = changes.set_type
+            && set_type != current_type
+        {
+            if parent_set_id.is_some() {
+                return Err(AppError::Validation(
+                    "A sub-set takes its parent's type".to_string(),
                 ));
             }
-            None => {
-                return Err(AppError::NotFound("Set not found".to_string()));
+            if !set_type.has_sub_sets() {
+                let has_sub_sets = sqlx::query_scalar::<_, bool>(
+                    "SELECT EXISTS(SELECT 1 FROM workout_sets WHERE parent_set_id = $1)",
+                )
+                .bind(set_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                if has_sub_sets {
+                    return Err(AppError::Validation(
+                        "Only drop, rest-pause and cluster sets have sub-sets".to_string(),
+                    ));
+                }
             }
-            Some(WorkoutStatus::InProgress) => {}
+
+            sqlx::query("UPDATE workout_sets SET set_type = $2 WHERE parent_set_id = $1")
+                .bind(set_id)
+                .bind(changes.set_type)
+                .execute(&mut *tx)
+                .await?;
         }
 
-        let completed_at: Option<DateTime<Utc>> = if is_completed == Some(true) {
-            Some(Utc::now())
-        } else if is_completed == Some(false) {
-            None
-        } else {
-            // Keep existing
-            sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
-                "SELECT completed_at FROM workout_sets WHERE id = $1",
+        let (was_completed, existing_completed_at) =
+            sqlx::query_as::<_, (bool, Option<DateTime<Utc>>)>(
+                "SELECT is_completed, completed_at FROM workout_sets WHERE id = $1",
             )
             .bind(set_id)
-            .fetch_one(pool)
-            .await?
+            .fetch_one(&mut *tx)
+            .await?;
+
+        // Only completing the set stamps it. Re-sending `isCompleted: true`, or
+        // fixing a set after the workout, keeps the time it was done, which its
+        // rest and the next set's are measured from.
+        let completed_at: Option<DateTime<Utc>> = match changes.is_completed {
+            Some(true) if !was_completed => Some(Utc::now()),
+            Some(false) => None,
+            _ => existing_completed_at,
         };
 
         let set = sqlx::query_as::<_, WorkoutSet>(
@@ -672,7 +1072,14 @@ impl WorkoutRepository {
             UPDATE workout_sets
             SET
                 target_reps = COALESCE($2, target_reps),
-                actual_reps = COALESCE($3, actual_reps),
+                -- On a unilateral set, defaults to the weaker side
+                actual_reps = COALESCE(
+                    $3,
+                    CASE WHEN $18::int IS NOT NULL OR $19::int IS NOT NULL THEN
+                        LEAST(COALESCE($18, actual_reps_left), COALESCE($19, actual_reps_right))
+                    END,
+                    actual_reps
+                ),
                 target_weight = COALESCE($4, target_weight),
                 actual_weight = COALESCE($5, actual_weight),
                 is_warmup = COALESCE($6, is_warmup),
@@ -681,46 +1088,399 @@ impl WorkoutRepository {
                 rpe = COALESCE($9, rpe),
                 distance_meters = COALESCE($10, distance_meters),
                 duration_seconds = COALESCE($11, duration_seconds),
-                calories = COALESCE($12, calories)
+                calories = COALESCE($12, calories),
+                set_type = COALESCE($13, set_type),
+                target_rir = COALESCE($14, target_rir),
+                rir = COALESCE($15, rir),
+                tempo = COALESCE($16, tempo),
+                is_unilateral = COALESCE($17, is_unilateral),
+                -- Sides are dropped when a set stops being unilateral
+                actual_reps_left = CASE WHEN COALESCE($17, is_unilateral)
+                    THEN COALESCE($18, actual_reps_left) END,
+                actual_reps_right = CASE WHEN COALESCE($17, is_unilateral)
+                    THEN COALESCE($19, actual_reps_right) END
             WHERE id = $1
             RETURNING id, workout_exercise_id, set_number, target_reps, actual_reps,
               target_weight, actual_weight, is_warmup, is_completed, completed_at, rpe,
               distance_meters, duration_seconds, calories,
-              target_distance_meters, target_duration_seconds
+              target_distance_meters, target_duration_seconds, rest_seconds_actual, set_type, parent_set_id,
+              target_rir, rir, tempo, is_unilateral, actual_reps_left, actual_reps_right, volume_reps, version
             "#,
         )
         .bind(set_id)
-        .bind(target_reps)
-        .bind(actual_reps)
-        .bind(target_weight)
-        .bind(actual_weight)
-        .bind(is_warmup)
-        .bind(is_completed)
+        .bind(changes.target_reps)
+        .bind(changes.actual_reps)
+        .bind(changes.target_weight)
+        .bind(changes.actual_weight)
+        .bind(changes.is_warmup)
+        .bind(changes.is_completed)
         .bind(completed_at)
-        .bind(rpe)
-        .bind(distance_meters)
-        .bind(duration_seconds)
-        .bind(calories)
-        .fetch_optional(pool)
+        .bind(changes.rpe)
+        .bind(changes.distance_meters)
+        .bind(changes.duration_seconds)
+        .bind(changes.calories)
+        .bind(changes.set_type)
+        .bind(changes.target_rir)
+        .bind(changes.rir)
+        .bind(changes.tempo.as_deref())
+        .bind(changes.is_unilateral)
+        .bind(changes.actual_reps_left)
+        .bind(changes.actual_reps_right)
+        .fetch_optional(&mut *tx)
         .await?
         .ok_or_else(|| AppError::NotFound("Set not found".to_string()))?;
 
+        // Completing or un-completing a set changes its rest and the next one's
+        Self::refresh_rest_tx(&mut tx, set.workout_exercise_id).await?;
+        let set = Self::find_set_tx(&mut tx, set_id).await?;
+
+        Self::after_completed_edit_tx(&mut tx, owner.workout_id, &owner.exercise_template_id)
+            .await?;
+        tx.commit().await?;
+
         Ok(set)
     }
 
-    pub async fn delete_set(pool: &PgPool, set_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query("DELETE FROM workout_sets WHERE id = $1")
-            .bind(set_id)
-            .execute(pool)
+    pub async fn delete_set(pool: &PgPool, set_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let owner = Self::lock_set_workout_tx(&mut tx, set_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Set not found".to_string()))?;
+
+        let exercise_id = sqlx::query_scalar::<_, Uuid>(
+            "DELETE FROM workout_sets WHERE id = $1 RETURNING workout_exercise_id",
+        )
+        .bind(set_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::refresh_rest_tx(&mut tx, exercise_id).await?;
+
+        Self::after_completed_edit_tx(&mut tx, owner.workout_id, &owner.exercise_template_id)
             .await?;
+        tx.commit().await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("Set not found".to_string()));
+        Ok(())
+    }
+
+    /// The workout a set belongs to, provided `user_id` owns it, locked until
+    /// the transaction ends.
+    async fn lock_set_workout_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        set_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<SetOwner>, AppError> {
+        let owner = sqlx::query_as::<_, SetOwner>(
+            r#"
+            SELECT w.id AS workout_id, w.status, we.exercise_template_id
+            FROM workouts w
+            JOIN workout_exercises we ON we.workout_id = w.id
+            JOIN workout_sets ws ON ws.workout_exercise_id = we.id
+            WHERE ws.id = $1 AND w.user_id = $2
+            FOR UPDATE OF w
+            "#,
+        )
+        .bind(set_id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(owner)
+    }
+
+    /// Locks a workout that can still be paused or resumed.
+    async fn lock_in_progress_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let status = sqlx::query_scalar::<_, WorkoutStatus>(
+            r#"
+            SELECT status FROM workouts
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+
+        if status != WorkoutStatus::InProgress {
+            return Err(AppError::Conflict(
+                "Only an in-progress workout can be paused or resumed".to_string(),
+            ));
         }
+        Ok(())
+    }
+
+    /// Ends the workout's open pause, if any, and returns its total paused time.
+    async fn end_pause_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workout_id: Uuid,
+    ) -> Result<i32, AppError> {
+        sqlx::query(
+            "UPDATE workout_pauses SET ended_at = NOW() WHERE workout_id = $1 AND ended_at IS NULL",
+        )
+        .bind(workout_id)
+        .execute(&mut **tx)
+        .await?;
+
+        let paused_seconds = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (ended_at - started_at))), 0)::int
+            FROM workout_pauses
+            WHERE workout_id = $1
+            "#,
+        )
+        .bind(workout_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(paused_seconds)
+    }
+
+    /// Pauses live in their own table, so starting or ending one touches the
+    /// workout to bump its version and put it in the sync feed.
+    async fn touch_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workout_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query("UPDATE workouts SET updated_at = NOW() WHERE id = $1")
+            .bind(workout_id)
+            .execute(&mut **tx)
+            .await?;
 
         Ok(())
     }
 
+    async fn find_set_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        set_id: Uuid,
+    ) -> Result<WorkoutSet, AppError> {
+        let set = sqlx::query_as::<_, WorkoutSet>(
+            r#"
+            SELECT id, workout_exercise_id, set_number, target_reps, actual_reps, target_weight, actual_weight,
+                   is_warmup, is_completed, completed_at, rpe, distance_meters, duration_seconds, calories,
+                   target_distance_meters, target_duration_seconds, rest_seconds_actual, set_type, parent_set_id,
+                   target_rir, rir, tempo, is_unilateral, actual_reps_left, actual_reps_right, volume_reps, version
+            FROM workout_sets
+            WHERE id = $1
+            "#,
+        )
+        .bind(set_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(set)
+    }
+
+    /// Recomputes the rest of every set of an exercise from the completion
+    /// times: the gap since the previous completed set, less the set's own
+    /// duration and any pause in between. Only sets whose rest changed are
+    /// written, so other sets keep their version.
+    async fn refresh_rest_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        exercise_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE workout_sets ws
+            SET rest_seconds_actual = r.rest
+            FROM (
+                SELECT s.id,
+                       CASE WHEN c.previous_at IS NOT NULL THEN GREATEST(
+                           0,
+                           EXTRACT(EPOCH FROM (c.completed_at - c.previous_at))::int
+                               - COALESCE(s.duration_seconds, 0)
+                               - COALESCE((
+                                   SELECT SUM(EXTRACT(EPOCH FROM (
+                                       LEAST(COALESCE(p.ended_at, NOW()), c.completed_at)
+                                       - GREATEST(p.started_at, c.previous_at)
+                                   )))::int
+                                   FROM workout_pauses p
+                                   WHERE p.workout_id = we.workout_id
+                                     AND p.started_at < c.completed_at
+                                     AND COALESCE(p.ended_at, NOW()) > c.previous_at
+                               ), 0)
+                       ) END AS rest
+                FROM workout_sets s
+                JOIN workout_exercises we ON we.id = s.workout_exercise_id
+                LEFT JOIN (
+                    SELECT id, completed_at,
+                           LAG(completed_at) OVER (ORDER BY completed_at, set_number) AS previous_at
+                    FROM workout_sets
+                    WHERE workout_exercise_id = $1 AND is_completed AND completed_at IS NOT NULL
+                ) c ON c.id = s.id
+                WHERE s.workout_exercise_id = $1
+            ) r
+            WHERE ws.id = r.id AND ws.rest_seconds_actual IS DISTINCT FROM r.rest
+            "#,
+        )
+        .bind(exercise_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Locks a live workout, failing with 412 if it has moved past the
+    /// version the client expects.
+    async fn lock_version_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        user_id: Uuid,
+        expected_version: Option<i32>,
+    ) -> Result<(), AppError> {
+        let version = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT version FROM workouts
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+
+        check_version(expected_version, version)
+    }
+
+    /// Totals over completed working sets, as stored on the workout.
+    async fn totals_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workout_id: Uuid,
+    ) -> Result<WorkoutStats, AppError> {
+        let stats = sqlx::query_as::<_, WorkoutStats>(
+            r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN ws.is_completed AND NOT ws.is_warmup THEN ws.actual_weight * ws.volume_reps ELSE 0 END), 0) as total_volume,
+                COALESCE(COUNT(CASE WHEN ws.is_completed AND NOT ws.is_warmup AND ws.parent_set_id IS NULL THEN 1 END), 0)::int as total_sets,
+                COALESCE(SUM(CASE WHEN ws.is_completed AND NOT ws.is_warmup THEN ws.volume_reps ELSE 0 END), 0)::int as total_reps
+            FROM workout_exercises we
+            JOIN workout_sets ws ON ws.workout_exercise_id = we.id
+            WHERE we.workout_id = $1
+            "#,
+        )
+        .bind(workout_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// After an edit to an already-completed workout: refresh its stored totals
+    /// and queue a record recompute for the affected exercise. No-op otherwise.
+    async fn after_completed_edit_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workout_id: Uuid,
+        exercise_template_id: &str,
+    ) -> Result<(), AppError> {
+        let stats = Self::totals_tx(tx, workout_id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE workouts SET total_volume = $2, total_sets = $3, total_reps = $4
+            WHERE id = $1 AND status = 'completed'
+            "#,
+        )
+        .bind(workout_id)
+        .bind(stats.total_volume)
+        .bind(stats.total_sets)
+        .bind(stats.total_reps)
+        .execute(&mut **tx)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            Self::queue_record_recompute_tx(tx, workout_id, Some(exercise_template_id)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Queues a record recompute for each exercise in a completed workout, or
+    /// just `exercise_template_id`, as part of the change that made them stale.
+    async fn queue_record_recompute_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workout_id: Uuid,
+        exercise_template_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let targets = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT DISTINCT w.user_id, we.exercise_template_id
+            FROM workouts w
+            JOIN workout_exercises we ON we.workout_id = w.id
+            WHERE w.id = $1 AND w.status = 'completed'
+              AND ($2::text IS NULL OR we.exercise_template_id = $2)
+            "#,
+        )
+        .bind(workout_id)
+        .bind(exercise_template_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // A deleted exercise no longer joins, but its records still need clearing
+        let targets = match (targets.is_empty(), exercise_template_id) {
+            (true, Some(exercise_template_id)) => sqlx::query_scalar::<_, Uuid>(
+                "SELECT user_id FROM workouts WHERE id = $1 AND status = 'completed'",
+            )
+            .bind(workout_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .map(|user_id| vec![(user_id, exercise_template_id.to_string())])
+            .unwrap_or_default(),
+            _ => targets,
+        };
+
+        for (user_id, exercise_template_id) in targets {
+            JobRepository::enqueue_tx(
+                tx,
+                &Job::RecomputePersonalRecords {
+                    user_id,
+                    exercise_template_id: Some(exercise_template_id),
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Every set the user logged for an exercise in completed,
+    /// non-deleted workouts, oldest workout first.
+    pub async fn find_completed_sets_for_exercise(
+        pool: &PgPool,
+        user_id: Uuid,
+        exercise_template_id: &str,
+    ) -> Result<Vec<ExerciseHistorySet>, AppError> {
+        let sets = sqlx::query_as::<_, ExerciseHistorySet>(
+            r#"
+            SELECT w.id AS workout_id, w.completed_at AS workout_completed_at, we.exercise_name,
+                   ws.id, ws.workout_exercise_id, ws.set_number, ws.target_reps, ws.actual_reps,
+                   ws.target_weight, ws.actual_weight, ws.is_warmup, ws.is_completed, ws.completed_at, ws.rpe,
+                   ws.distance_meters, ws.duration_seconds, ws.calories,
+                   ws.target_distance_meters, ws.target_duration_seconds, ws.rest_seconds_actual, ws.set_type, ws.parent_set_id,
+                   ws.target_rir, ws.rir, ws.tempo, ws.is_unilateral, ws.actual_reps_left, ws.actual_reps_right,
+                   ws.volume_reps, ws.version
+            FROM workouts w
+            JOIN workout_exercises we ON we.workout_id = w.id
+            JOIN workout_sets ws ON ws.workout_exercise_id = we.id
+            WHERE w.user_id = $1 AND we.exercise_template_id = $2
+              AND w.status = 'completed' AND w.deleted_at IS NULL AND w.completed_at IS NOT NULL
+            ORDER BY w.completed_at, w.id, we.order_index, ws.set_number
+            "#,
+        )
+        .bind(user_id)
+        .bind(exercise_template_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sets)
+    }
+
     // Superset methods
     pub async fn create_superset(
         pool: &PgPool,
@@ -776,7 +1536,7 @@ impl WorkoutRepository {
     ) -> Result<Vec<WorkoutExercise>, AppError> {
         let exercises = sqlx::query_as::<_, WorkoutExercise>(
             r#"
-            SELECT id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id
+            SELECT id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id, version
             FROM workout_exercises
             WHERE superset_id = $1
             ORDER BY order_index
@@ -792,8 +1552,13 @@ impl WorkoutRepository {
     pub async fn reorder_exercises(
         pool: &PgPool,
         workout_id: Uuid,
+        user_id: Uuid,
+        expected_version: Option<i32>,
         updates: &[ExerciseOrderUpdate],
     ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        Self::lock_version_tx(&mut tx, workout_id, user_id, expected_version).await?;
+
         let ids: Vec<Uuid> = updates.iter().map(|u| u.id).collect();
         let indices: Vec<i32> = updates.iter().map(|u| u.order_index).collect();
         sqlx::query(
@@ -805,8 +1570,10 @@ impl WorkoutRepository {
         .bind(&ids)
         .bind(&indices)
         .bind(workout_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+
+        tx.commit().await?;
         Ok(())
     }
 
@@ -818,7 +1585,7 @@ impl WorkoutRepository {
         let exercise = sqlx::query_as::<_, WorkoutExercise>(
             r#"
             UPDATE workout_exercises SET superset_id = $1 WHERE id = $2
-            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id
+            RETURNING id, workout_id, exercise_template_id, exercise_name, notes, order_index, superset_id, version
             "#,
         )
         .bind(superset_id)
@@ -847,6 +1614,41 @@ pub struct WorkoutWithCount {
     pub notes: Option<String>,
     pub tags: Vec<String>,
     pub exercise_count: i32,
+    pub avg_rest_seconds: Option<i32>,
+}
+
+/// A set to add to an exercise; anything not given is left blank.
+#[derive(Debug, Default)]
+pub struct NewSet<'a> {
+    /// Client-generated ID, so a retried add can be recognised
+    pub id: Option<Uuid>,
+    pub target_reps: Option<i32>,
+    pub target_weight: Option<f64>,
+    pub is_warmup: bool,
+    pub distance_meters: Option<f64>,
+    pub duration_seconds: Option<i32>,
+    pub calories: Option<i32>,
+    pub target_distance_meters: Option<i32>,
+    pub target_duration_seconds: Option<i32>,
+    /// Ignored for a sub-set, which takes its parent's type
+    pub set_type: SetType,
+    pub parent_set_id: Option<Uuid>,
+    pub target_rir: Option<i16>,
+    pub tempo: Option<&'a str>,
+    pub is_unilateral: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct PauseState {
+    pub paused_at: Option<DateTime<Utc>>,
+    pub paused_seconds: i32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct SetOwner {
+    workout_id: Uuid,
+    status: WorkoutStatus,
+    exercise_template_id: String,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -866,6 +1668,7 @@ struct ExerciseWithSetRow {
     exercise_notes: Option<String>,
     order_index: i32,
     superset_id: Option<Uuid>,
+    exercise_version: i32,
     exercise_category: Option<ExerciseCategory>,
     // Set fields (nullable due to LEFT JOIN)
     set_id: Option<Uuid>,
@@ -884,4 +1687,15 @@ struct ExerciseWithSetRow {
     calories: Option<i32>,
     target_distance_meters: Option<i32>,
     target_duration_seconds: Option<i32>,
+    rest_seconds_actual: Option<i32>,
+    set_type: Option<SetType>,
+    parent_set_id: Option<Uuid>,
+    target_rir: Option<i16>,
+    rir: Option<i16>,
+    tempo: Option<String>,
+    is_unilateral: Option<bool>,
+    actual_reps_left: Option<i32>,
+    actual_reps_right: Option<i32>,
+    volume_reps: Option<i32>,
+    set_version: Option<i32>,
 }
@@ -6,8 +6,8 @@ use axum::{
 };
 use axum_prometheus::PrometheusMetricLayer;
 use sqlx::PgPool;
+use std::sync::Arc;
 use axum::http::{header, Method};
-use tokio::sync::mpsc;
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::{DefaultOnResponse, TraceLayer};
@@ -16,16 +16,25 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::config::Settings;
+use crate::events::EventBus;
 use crate::handlers;
-use crate::middleware::{admin_middleware, auth_middleware, auth_rate_limiter, general_rate_limiter, request_id_middleware};
+use crate::jwt::JwtKeys;
+use crate::mail::{build_mailer, Mailer};
+use crate::middleware::{
+    auth_middleware, auth_rate_limiter, client_ip_middleware, general_rate_limiter,
+    idempotency_middleware, request_id_middleware, require_scope, require_session,
+    require_verified_email, require_verified_email_for_workouts, IDEMPOTENCY_KEY_HEADER,
+};
+use crate::models::ApiResource;
 use crate::openapi::ApiDoc;
-use crate::services::PrJob;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub settings: Settings,
-    pub pr_tx: mpsc::Sender<PrJob>,
+    pub mailer: Arc<dyn Mailer>,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub events: EventBus,
 }
 
 impl FromRef<AppState> for PgPool {
@@ -40,11 +49,31 @@ impl FromRef<AppState> for Settings {
     }
 }
 
-pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob>) -> Router {
+impl FromRef<AppState> for Arc<dyn Mailer> {
+    fn from_ref(state: &AppState) -> Self {
+        state.mailer.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<JwtKeys> {
+    fn from_ref(state: &AppState) -> Self {
+        state.jwt_keys.clone()
+    }
+}
+
+impl FromRef<AppState> for EventBus {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
+}
+
+pub fn create_router(pool: PgPool, settings: Settings) -> Router {
     let state = AppState {
+        events: EventBus::new(pool.clone()),
         pool,
         settings: settings.clone(),
-        pr_tx,
+        mailer: build_mailer(&settings.mail).expect("Failed to build mailer"),
+        jwt_keys: Arc::new(JwtKeys::from_settings(&settings.jwt).expect("Failed to load JWT keys")),
     };
 
     // CORS configuration - use origins from settings
@@ -68,29 +97,74 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             header::CONTENT_TYPE,
             header::AUTHORIZATION,
             header::ACCEPT,
-        ]);
+            header::IF_MATCH,
+            header::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers([header::ETAG]);
 
     // Public routes (no auth required) with strict rate limiting
     let public_routes = Router::new()
         .route("/auth/register", post(handlers::register))
         .route("/auth/login", post(handlers::login))
         .route("/auth/refresh", post(handlers::refresh))
+        .route("/auth/forgot-password", post(handlers::forgot_password))
+        .route("/auth/reset-password", post(handlers::reset_password))
+        .route("/auth/verify-email", post(handlers::verify_email))
+        .route("/auth/mfa/verify", post(handlers::verify_mfa))
         .layer(auth_rate_limiter());
 
-    // Protected routes (auth required)
-    let protected_routes = Router::new()
-        // Auth
-        .route("/auth/me", get(handlers::me))
+    // Account routes (auth required, open to unverified accounts). Managing the
+    // account needs a login session; personal access tokens may only call /auth/me.
+    let account_routes = Router::new()
         .route("/auth/password", patch(handlers::change_password))
+        .route("/auth/logout", post(handlers::logout))
+        .route("/auth/logout-all", post(handlers::logout_all))
+        .route("/auth/sessions", get(handlers::list_sessions))
+        .route("/auth/sessions/{id}", delete(handlers::revoke_session))
+        .route(
+            "/auth/verify-email/resend",
+            post(handlers::resend_verification_email).layer(auth_rate_limiter()),
+        )
+        .route("/auth/mfa", get(handlers::get_mfa_status))
+        .route("/auth/mfa/totp/setup", post(handlers::setup_totp))
+        .route("/auth/mfa/totp/confirm", post(handlers::confirm_totp))
+        .route("/auth/mfa/totp/disable", post(handlers::disable_totp))
+        .route(
+            "/auth/mfa/recovery-codes",
+            post(handlers::regenerate_recovery_codes),
+        )
+        .route("/auth/tokens", get(handlers::list_access_tokens))
+        .route("/auth/tokens", post(handlers::create_access_token))
+        .route("/auth/tokens/{id}", delete(handlers::revoke_access_token))
+        .route("/auth/me", delete(handlers::delete_account))
+        .route(
+            "/auth/me/export",
+            get(handlers::export_account).layer(auth_rate_limiter()),
+        )
+        .route(
+            "/auth/me/cancel-deletion",
+            post(handlers::cancel_account_deletion),
+        )
+        .route_layer(middleware::from_fn(require_session))
+        .route("/auth/me", get(handlers::me));
+
+    // Workout logging routes (auth required, verification per auth.unverified_access).
+    // Each group below is also gated on the matching access token scope.
+    let workout_routes = Router::new()
         // Workouts
         .route("/workouts", get(handlers::list_workouts))
         .route("/workouts", post(handlers::create_workout))
+        .route("/workouts/import", post(handlers::import_workout))
         .route("/workouts/{id}", get(handlers::get_workout))
         .route("/workouts/{id}", patch(handlers::update_workout))
         .route("/workouts/{id}", delete(handlers::delete_workout))
         .route("/workouts/{id}/complete", post(handlers::complete_workout))
         .route("/workouts/{id}/cancel", post(handlers::cancel_workout))
+        .route("/workouts/{id}/pause", post(handlers::pause_workout))
+        .route("/workouts/{id}/resume", post(handlers::resume_workout))
         .route("/workouts/{id}/restore", post(handlers::restore_workout))
+        // Live changes to the user's workouts
+        .route("/events", get(handlers::stream_events))
         // Workout exercises
         .route(
             "/workouts/{workout_id}/exercises",
@@ -131,9 +205,27 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/workouts/{workout_id}/superset/{superset_id}",
             delete(handlers::remove_superset),
         )
-        // Exercises
-        .route("/exercises", get(handlers::list_exercises))
-        .route("/exercises/{id}", get(handlers::get_exercise))
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Workouts,
+            require_scope,
+        ))
+        // Exercise library lookups needed while logging
+        .merge(
+            Router::new()
+                .route("/exercises", get(handlers::list_exercises))
+                .route("/exercises/{id}", get(handlers::get_exercise))
+                .route_layer(middleware::from_fn_with_state(
+                    ApiResource::Exercises,
+                    require_scope,
+                )),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_verified_email_for_workouts,
+        ));
+
+    // Custom exercises
+    let custom_exercise_routes = Router::new()
         .route("/exercises/custom", post(handlers::create_custom_exercise))
         .route(
             "/exercises/custom/{id}",
@@ -143,7 +235,13 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/exercises/custom/{id}",
             delete(handlers::delete_custom_exercise),
         )
-        // Templates
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Exercises,
+            require_scope,
+        ));
+
+    // Templates
+    let template_routes = Router::new()
         .route("/templates", get(handlers::list_templates))
         .route("/templates", post(handlers::create_template))
         .route("/templates/{id}", get(handlers::get_template))
@@ -154,7 +252,13 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/templates/{id}/start",
             post(handlers::start_workout_from_template),
         )
-        // Programs
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Templates,
+            require_scope,
+        ));
+
+    // Programs
+    let program_routes = Router::new()
         .route("/programs", get(handlers::list_programs))
         .route("/programs", post(handlers::create_program))
         .route("/programs/active", get(handlers::get_active_program))
@@ -166,7 +270,13 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/programs/{program_id}/workouts/{workout_id}/start",
             post(handlers::start_program_workout),
         )
-        // Body stats
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Programs,
+            require_scope,
+        ));
+
+    // Body stats
+    let body_stats_routes = Router::new()
         .route(
             "/body-stats/measurements",
             get(handlers::list_measurements),
@@ -196,7 +306,13 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/body-stats/goals/{id}/progress",
             get(handlers::get_goal_progress),
         )
-        // Statistics
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::BodyStats,
+            require_scope,
+        ));
+
+    // Statistics and personal records
+    let stats_routes = Router::new()
         .route("/statistics/summary", get(handlers::get_summary))
         .route("/statistics/volume/weekly", get(handlers::get_weekly_volume))
         .route(
@@ -207,6 +323,14 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/statistics/exercises/{exercise_id}/progress",
             get(handlers::get_exercise_progress),
         )
+        .route(
+            "/statistics/exercises/{exercise_id}/rep-maxes",
+            get(handlers::get_rep_maxes),
+        )
+        .route(
+            "/statistics/exercises/{exercise_id}/record-timeline",
+            get(handlers::get_record_timeline),
+        )
         .route(
             "/statistics/exercises-with-history",
             get(handlers::get_exercises_with_history),
@@ -227,26 +351,73 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
             "/statistics/consistency-heatmap",
             get(handlers::get_consistency_heatmap),
         )
-        // Personal Records
         .route("/personal-records", get(handlers::get_personal_records))
-        // Settings
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Stats,
+            require_scope,
+        ));
+
+    // Settings
+    let settings_routes = Router::new()
         .route("/settings", get(handlers::get_settings))
         .route("/settings", put(handlers::update_settings))
+        .route_layer(middleware::from_fn_with_state(
+            ApiResource::Settings,
+            require_scope,
+        ));
+
+    // Offline sync (filters each section by the access token's scopes itself)
+    let sync_routes = Router::new().route("/sync", get(handlers::sync));
+
+    // Remaining feature routes (auth and, if configured, a verified email required)
+    let feature_routes = custom_exercise_routes
+        .merge(template_routes)
+        .merge(program_routes)
+        .merge(body_stats_routes)
+        .merge(stats_routes)
+        .merge(settings_routes)
+        .merge(sync_routes)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_verified_email,
+        ));
+
+    // Protected routes (auth required)
+    let protected_routes = account_routes
+        .merge(workout_routes)
+        .merge(feature_routes)
         .layer(middleware::from_fn_with_state(
-            settings.clone(),
+            state.clone(),
+            idempotency_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
             auth_middleware,
         ));
 
-    // Admin routes (admin JWT check in middleware)
+    // Staff routes (each handler checks its own permission via `Authorized<P>`)
     let admin_routes = Router::new()
         .route("/admin/users", get(handlers::list_admin_users))
         .route("/admin/users/{id}", get(handlers::get_admin_user))
         .route("/admin/users/{id}", delete(handlers::delete_admin_user))
         .route("/admin/users/{id}", patch(handlers::set_admin_status))
+        .route("/admin/users/{id}/unlock", post(handlers::unlock_admin_user))
+        .route("/admin/users/{id}/roles", put(handlers::set_user_roles))
+        .route(
+            "/admin/users/{id}/personal-records/recompute",
+            post(handlers::recompute_user_personal_records),
+        )
+        .route(
+            "/admin/personal-records/recompute",
+            post(handlers::recompute_all_personal_records),
+        )
+        .route("/admin/roles", get(handlers::list_roles))
         .route("/admin/metrics", get(handlers::get_admin_metrics))
+        .route("/admin/exercises", get(handlers::list_admin_exercises))
+        .route("/admin/exercises/{id}", delete(handlers::delete_admin_exercise))
         .layer(middleware::from_fn_with_state(
             state.clone(),
-            admin_middleware,
+            auth_middleware,
         ));
 
     let (prometheus_layer, metrics_handle) = PrometheusMetricLayer::pair();
@@ -260,6 +431,7 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
     // Top-level router: /health and /metrics are outside the rate limiter
     Router::new()
         .route("/health", get(handlers::health))
+        .route("/.well-known/jwks.json", get(handlers::jwks))
         .route("/metrics", get(move || async move { metrics_handle.render() }))
         .merge(api_routes)
         .layer(
@@ -280,6 +452,10 @@ pub fn create_router(pool: PgPool, settings: Settings, pr_tx: mpsc::Sender<PrJob
                 .on_response(DefaultOnResponse::new().level(Level::INFO)),
         )
         .layer(middleware::from_fn(request_id_middleware))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            client_ip_middleware,
+        ))
         .layer(cors)
         .layer(CompressionLayer::new())
         .layer(DefaultBodyLimit::max(1024 * 1024)) // 1 MB
@@ -0,0 +1,86 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::dto::{
+    AccessTokenListResponse, AccessTokenResponse, CreateAccessTokenRequest,
+    CreatedAccessTokenResponse,
+};
+use crate::error::AppError;
+use crate::models::PersonalAccessToken;
+use crate::repositories::AccessTokenRepository;
+use crate::services::AuthService;
+
+/// Marks a bearer token as a personal access token rather than a JWT.
+pub const ACCESS_TOKEN_PREFIX: &str = "ftp_";
+/// Characters kept for display: the marker plus 8 of the secret
+const DISPLAY_PREFIX_LEN: usize = ACCESS_TOKEN_PREFIX.len() + 8;
+
+pub struct AccessTokenService;
+
+impl AccessTokenService {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        req: &CreateAccessTokenRequest,
+    ) -> Result<CreatedAccessTokenResponse, AppError> {
+        let mut scopes: Vec<String> = req.scopes.iter().map(|s| s.as_str().to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+
+        let token = format!("{}{}", ACCESS_TOKEN_PREFIX, AuthService::generate_opaque_token());
+        let stored = AccessTokenRepository::create(
+            pool,
+            user_id,
+            req.name.trim(),
+            &AuthService::hash_token(&token),
+            &token[..DISPLAY_PREFIX_LEN],
+            &scopes,
+            req.expires_in_days.map(|days| Utc::now() + Duration::days(days)),
+        )
+        .await?;
+
+        Ok(CreatedAccessTokenResponse {
+            token,
+            details: Self::to_response(stored),
+        })
+    }
+
+    pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<AccessTokenListResponse, AppError> {
+        let tokens = AccessTokenRepository::list_active(pool, user_id).await?;
+
+        Ok(AccessTokenListResponse {
+            tokens: tokens.into_iter().map(Self::to_response).collect(),
+        })
+    }
+
+    pub async fn revoke(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<(), AppError> {
+        if !AccessTokenRepository::revoke(pool, id, user_id).await? {
+            return Err(AppError::NotFound("Access token not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a raw bearer token to its stored record, if it is still usable.
+    pub async fn authenticate(
+        pool: &PgPool,
+        token: &str,
+    ) -> Result<PersonalAccessToken, AppError> {
+        AccessTokenRepository::touch_active(pool, &AuthService::hash_token(token))
+            .await?
+            .ok_or(AppError::Unauthorized)
+    }
+
+    fn to_response(token: PersonalAccessToken) -> AccessTokenResponse {
+        AccessTokenResponse {
+            scopes: token.parsed_scopes(),
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Settings;
+use crate::dto::AccountDeletionResponse;
+use crate::error::AppError;
+use crate::mail::{send_in_background, EmailMessage, Mailer};
+use crate::repositories::{
+    AccessTokenRepository, AccountRepository, MfaRepository, SessionRepository, UserRepository,
+};
+use crate::services::{AuthService, MfaService};
+
+pub struct AccountService;
+
+impl AccountService {
+    pub async fn export(pool: &PgPool, user_id: Uuid) -> Result<serde_json::Value, AppError> {
+        AccountRepository::export(pool, user_id).await
+    }
+
+    /// Schedules the account for purging after the grace period and signs it
+    /// out everywhere. Logging back in and cancelling keeps the account.
+    pub async fn request_deletion(
+        pool: &PgPool,
+        settings: &Settings,
+        mailer: Arc<dyn Mailer>,
+        user_id: Uuid,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<AccountDeletionResponse, AppError> {
+        let user = UserRepository::find_by_id(pool, user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if user.scheduled_deletion_at.is_some() {
+            return Err(AppError::Conflict(
+                "Account deletion is already scheduled".to_string(),
+            ));
+        }
+
+        if !AuthService::verify_password(password, &user.password_hash)? {
+            return Err(AppError::BadRequest("Password is incorrect".to_string()));
+        }
+
+        if MfaRepository::is_enabled(pool, user_id).await? {
+            let valid = match code {
+                Some(code) => MfaService::verify_code(pool, user_id, code).await?,
+                None => false,
+            };
+            if !valid {
+                return Err(AppError::BadRequest("Invalid code".to_string()));
+            }
+        }
+
+        let scheduled_deletion_at = Utc::now() + Duration::days(settings.auth.deletion_grace_days);
+        UserRepository::schedule_deletion(pool, user_id, scheduled_deletion_at).await?;
+        SessionRepository::revoke_all_for_user(pool, user_id, None).await?;
+        AccessTokenRepository::revoke_all_for_user(pool, user_id).await?;
+
+        let message = EmailMessage {
+            to: user.email,
+            subject: "Your account is scheduled for deletion".to_string(),
+            body: format!(
+                "Your Fitness Tracker account and all of its data will be permanently \
+                 deleted on {}.\n\n\
+                 Changed your mind? Sign in at {} before then and cancel the deletion.",
+                scheduled_deletion_at.format("%Y-%m-%d %H:%M UTC"),
+                settings.mail.app_url.trim_end_matches('/'),
+            ),
+        };
+        send_in_background(mailer, message);
+
+        Ok(AccountDeletionResponse {
+            scheduled_deletion_at,
+        })
+    }
+
+    pub async fn cancel_deletion(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        if !UserRepository::cancel_deletion(pool, user_id).await? {
+            return Err(AppError::Conflict(
+                "No account deletion is scheduled".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,39 @@
 use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+    },
     Argon2,
 };
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::config::Settings;
-use crate::dto::{AuthResponse, TokenResponse, UserResponse};
+use crate::dto::{
+    AuthResponse, LoginResponse, MfaChallengeResponse, RegisterRequest, SessionListResponse,
+    SessionResponse, TokenResponse, UserResponse,
+};
 use crate::error::AppError;
+use crate::jwt::JwtKeys;
+use crate::mail::{send_in_background, EmailMessage, Mailer};
+use crate::middleware::ClientInfo;
 use crate::models::User;
-use crate::repositories::UserRepository;
+use crate::repositories::{
+    EmailVerificationTokenRepository, MfaRepository, PasswordResetTokenRepository,
+    RefreshTokenRepository, SessionRepository, UserRepository,
+};
+use crate::services::MfaService;
+
+/// How long a password reset link stays valid
+const PASSWORD_RESET_EXPIRY_MINUTES: i64 = 30;
+/// How long an email verification link stays valid
+const EMAIL_VERIFICATION_EXPIRY_HOURS: i64 = 24;
+/// How long a user has to enter their 2FA code after the password step
+const MFA_CHALLENGE_EXPIRY_MINUTES: i64 = 5;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
@@ -20,6 +41,9 @@ pub struct Claims {
     pub email: String,
     pub exp: i64,
     pub iat: i64,
+    pub jti: Uuid,
+    /// Session the token belongs to; revoking it invalidates the token
+    pub sid: Uuid,
     pub token_type: TokenType,
 }
 
@@ -28,6 +52,8 @@ pub struct Claims {
 pub enum TokenType {
     Access,
     Refresh,
+    /// Proof that the password step of a 2FA login succeeded
+    MfaChallenge,
 }
 
 pub struct AuthService;
@@ -50,21 +76,78 @@ impl AuthService {
             .is_ok())
     }
 
-    pub fn generate_tokens(user: &User, settings: &Settings) -> Result<TokenResponse, AppError> {
+    /// SHA-256 hex digest used to store bearer secrets (e.g. refresh tokens) at rest.
+    pub fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Random 256-bit secret, hex encoded, for links sent by email.
+    pub fn generate_opaque_token() -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        hex::encode(bytes)
+    }
+
+    /// Opens a new session for `user` and issues its first token pair.
+    async fn start_session(
+        pool: &PgPool,
+        user: &User,
+        settings: &Settings,
+        keys: &JwtKeys,
+        client: &ClientInfo,
+        device_label: Option<&str>,
+    ) -> Result<TokenResponse, AppError> {
+        let expires_at = Utc::now() + Duration::days(settings.jwt.refresh_token_expiry_days);
+        let session = SessionRepository::create(
+            pool,
+            user.id,
+            device_label,
+            client.user_agent.as_deref(),
+            Some(&client.ip_address),
+            expires_at,
+        )
+        .await?;
+
+        Self::generate_tokens(pool, user, settings, keys, session.id).await
+    }
+
+    /// Issues an access/refresh pair bound to `session_id`. The session id doubles
+    /// as the refresh token family, so revoking the session kills every rotation.
+    pub async fn generate_tokens(
+        pool: &PgPool,
+        user: &User,
+        settings: &Settings,
+        keys: &JwtKeys,
+        session_id: Uuid,
+    ) -> Result<TokenResponse, AppError> {
         let access_token = Self::generate_token(
             user,
+            session_id,
             TokenType::Access,
             Duration::hours(settings.jwt.access_token_expiry_hours),
-            &settings.jwt.secret,
+            keys,
         )?;
 
+        let refresh_expiry = Duration::days(settings.jwt.refresh_token_expiry_days);
         let refresh_token = Self::generate_token(
             user,
+            session_id,
             TokenType::Refresh,
-            Duration::days(settings.jwt.refresh_token_expiry_days),
-            &settings.jwt.secret,
+            refresh_expiry,
+            keys,
         )?;
 
+        let expires_at = Utc::now() + refresh_expiry;
+        RefreshTokenRepository::create(
+            pool,
+            user.id,
+            session_id,
+            &Self::hash_token(&refresh_token),
+            expires_at,
+        )
+        .await?;
+        SessionRepository::extend(pool, session_id, expires_at).await?;
+
         Ok(TokenResponse {
             access_token,
             refresh_token,
@@ -73,9 +156,10 @@ impl AuthService {
 
     fn generate_token(
         user: &User,
+        session_id: Uuid,
         token_type: TokenType,
         expiry: Duration,
-        secret: &str,
+        keys: &JwtKeys,
     ) -> Result<String, AppError> {
         let now = Utc::now();
         let claims = Claims {
@@ -83,102 +167,238 @@ impl AuthService {
             email: user.email.clone(),
             exp: (now + expiry).timestamp(),
             iat: now.timestamp(),
+            jti: Uuid::new_v4(),
+            sid: session_id,
             token_type,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(secret.as_bytes()),
-        )?;
-
-        Ok(token)
+        keys.encode(&claims)
     }
 
-    pub fn verify_token(token: &str, secret: &str) -> Result<Claims, AppError> {
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )?;
-
-        Ok(token_data.claims)
+    pub fn verify_token(token: &str, keys: &JwtKeys) -> Result<Claims, AppError> {
+        keys.decode(token)
     }
 
     pub async fn register(
         pool: &PgPool,
         settings: &Settings,
-        email: &str,
-        password: &str,
+        keys: &JwtKeys,
+        mailer: Arc<dyn Mailer>,
+        client: &ClientInfo,
+        req: &RegisterRequest,
     ) -> Result<AuthResponse, AppError> {
         // Check if user exists
-        if UserRepository::find_by_email(pool, email).await?.is_some() {
+        if UserRepository::find_by_email(pool, &req.email).await?.is_some() {
             return Err(AppError::Conflict("Email already registered".to_string()));
         }
 
-        let password_hash = Self::hash_password(password)?;
-        let user = UserRepository::create(pool, email, &password_hash).await?;
-        let tokens = Self::generate_tokens(&user, settings)?;
+        let password_hash = Self::hash_password(&req.password)?;
+        let user = UserRepository::create(pool, &req.email, &password_hash).await?;
+        Self::send_verification_email(pool, settings, mailer, &user).await?;
+        let tokens = Self::start_session(pool, &user, settings, keys, client, req.device_label.as_deref()).await?;
 
-        Ok(AuthResponse {
-            access_token: tokens.access_token,
-            refresh_token: tokens.refresh_token,
-            user: UserResponse {
-                id: user.id,
-                email: user.email,
-                is_admin: user.is_admin,
-            },
-        })
+        Ok(Self::auth_response(user, tokens))
     }
 
     pub async fn login(
         pool: &PgPool,
         settings: &Settings,
+        keys: &JwtKeys,
+        client: &ClientInfo,
         email: &str,
         password: &str,
-    ) -> Result<AuthResponse, AppError> {
+        device_label: Option<&str>,
+    ) -> Result<LoginResponse, AppError> {
         let user = UserRepository::find_by_email(pool, email)
             .await?
             .ok_or(AppError::InvalidCredentials)?;
 
+        // Checked before the password so a locked account gives nothing away
+        Self::ensure_not_locked(&user)?;
+
         if !Self::verify_password(password, &user.password_hash)? {
-            return Err(AppError::InvalidCredentials);
+            return Err(Self::record_login_failure(pool, settings, user.id).await);
+        }
+
+        if MfaRepository::is_enabled(pool, user.id).await? {
+            // No session, and no reset of the failure count, until the second
+            // factor checks out
+            let mfa_token = Self::generate_token(
+                &user,
+                Uuid::nil(),
+                TokenType::MfaChallenge,
+                Duration::minutes(MFA_CHALLENGE_EXPIRY_MINUTES),
+                keys,
+            )?;
+
+            return Ok(LoginResponse::MfaRequired(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_token,
+            }));
         }
 
-        let tokens = Self::generate_tokens(&user, settings)?;
+        Self::clear_login_failures(pool, &user).await?;
+        let tokens = Self::start_session(pool, &user, settings, keys, client, device_label).await?;
+
+        Ok(LoginResponse::Authenticated(Self::auth_response(user, tokens)))
+    }
 
-        Ok(AuthResponse {
+    /// Second step of a 2FA login: exchanges the challenge token and a TOTP or
+    /// recovery code for a normal session.
+    pub async fn verify_mfa_login(
+        pool: &PgPool,
+        settings: &Settings,
+        keys: &JwtKeys,
+        client: &ClientInfo,
+        mfa_token: &str,
+        code: &str,
+        device_label: Option<&str>,
+    ) -> Result<AuthResponse, AppError> {
+        let claims = Self::verify_token(mfa_token, keys)?;
+
+        if claims.token_type != TokenType::MfaChallenge {
+            return Err(AppError::Unauthorized);
+        }
+
+        let user = UserRepository::find_by_id(pool, claims.sub)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        Self::ensure_not_locked(&user)?;
+
+        if !MfaService::verify_code(pool, user.id, code).await? {
+            return Err(Self::record_login_failure(pool, settings, user.id).await);
+        }
+
+        Self::clear_login_failures(pool, &user).await?;
+        let tokens = Self::start_session(pool, &user, settings, keys, client, device_label).await?;
+
+        Ok(Self::auth_response(user, tokens))
+    }
+
+    fn ensure_not_locked(user: &User) -> Result<(), AppError> {
+        match user.locked_until {
+            Some(until) if until > Utc::now() => Err(AppError::AccountLocked(until)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a failed password or second-factor check against the account and
+    /// returns the error to report, which is the lockout once it kicks in.
+    async fn record_login_failure(pool: &PgPool, settings: &Settings, user_id: Uuid) -> AppError {
+        let locked_until = UserRepository::record_failed_login(
+            pool,
+            user_id,
+            settings.auth.max_failed_logins,
+            settings.auth.lockout_base_secs,
+            settings.auth.lockout_max_secs,
+        )
+        .await;
+
+        match locked_until {
+            Ok(Some(until)) if until > Utc::now() => {
+                tracing::warn!(user_id = %user_id, locked_until = %until, "Account locked after failed logins");
+                AppError::AccountLocked(until)
+            }
+            Ok(_) => AppError::InvalidCredentials,
+            Err(e) => e,
+        }
+    }
+
+    async fn clear_login_failures(pool: &PgPool, user: &User) -> Result<(), AppError> {
+        if user.failed_login_attempts > 0 {
+            UserRepository::clear_failed_logins(pool, user.id).await?;
+        }
+
+        Ok(())
+    }
+
+    fn auth_response(user: User, tokens: TokenResponse) -> AuthResponse {
+        AuthResponse {
             access_token: tokens.access_token,
             refresh_token: tokens.refresh_token,
             user: UserResponse {
                 id: user.id,
+                email_verified: user.email_verified_at.is_some(),
                 email: user.email,
                 is_admin: user.is_admin,
+                scheduled_deletion_at: user.scheduled_deletion_at,
             },
-        })
+        }
     }
 
     pub async fn refresh(
         pool: &PgPool,
         settings: &Settings,
+        keys: &JwtKeys,
         refresh_token: &str,
     ) -> Result<TokenResponse, AppError> {
-        let claims = Self::verify_token(refresh_token, &settings.jwt.secret)?;
+        let claims = Self::verify_token(refresh_token, keys)?;
 
         if claims.token_type != TokenType::Refresh {
             return Err(AppError::Unauthorized);
         }
 
+        let stored = RefreshTokenRepository::find_by_hash(pool, &Self::hash_token(refresh_token))
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if stored.user_id != claims.sub
+            || stored.family_id != claims.sid
+            || stored.expires_at <= Utc::now()
+        {
+            return Err(AppError::Unauthorized);
+        }
+
+        // Signed-out sessions have all their tokens revoked; that's not reuse
+        if !SessionRepository::touch_active(pool, stored.family_id, stored.user_id).await? {
+            return Err(AppError::Unauthorized);
+        }
+
+        // A token that was already rotated is being replayed: either the client
+        // or an attacker holds a stale copy, so kill the whole session.
+        if stored.revoked_at.is_some() || !RefreshTokenRepository::revoke(pool, stored.id).await? {
+            tracing::warn!(
+                user_id = %stored.user_id,
+                session_id = %stored.family_id,
+                "Refresh token reuse detected, revoking session"
+            );
+            SessionRepository::revoke(pool, stored.family_id, stored.user_id).await?;
+            return Err(AppError::Unauthorized);
+        }
+
         let user = UserRepository::find_by_id(pool, claims.sub)
             .await?
             .ok_or(AppError::Unauthorized)?;
 
-        Self::generate_tokens(&user, settings)
+        Self::generate_tokens(pool, &user, settings, keys, stored.family_id).await
+    }
+
+    pub async fn logout(
+        pool: &PgPool,
+        keys: &JwtKeys,
+        user_id: Uuid,
+        refresh_token: &str,
+    ) -> Result<(), AppError> {
+        let claims = Self::verify_token(refresh_token, keys)?;
+
+        if claims.token_type != TokenType::Refresh || claims.sub != user_id {
+            return Err(AppError::Unauthorized);
+        }
+
+        SessionRepository::revoke(pool, claims.sid, user_id).await?;
+
+        Ok(())
+    }
+
+    pub async fn logout_all(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+        SessionRepository::revoke_all_for_user(pool, user_id, None).await
     }
 
     pub async fn change_password(
         pool: &PgPool,
         user_id: Uuid,
+        session_id: Uuid,
         current_password: &str,
         new_password: &str,
     ) -> Result<(), AppError> {
@@ -193,6 +413,165 @@ impl AuthService {
         let new_hash = Self::hash_password(new_password)?;
         UserRepository::update_password(pool, user_id, &new_hash).await?;
 
+        // Sign out every other device that authenticated with the old password
+        SessionRepository::revoke_all_for_user(pool, user_id, Some(session_id)).await?;
+
+        Ok(())
+    }
+
+    /// Emails a reset link if the address belongs to an account. Always succeeds
+    /// so the endpoint can't be used to discover registered emails.
+    pub async fn forgot_password(
+        pool: &PgPool,
+        settings: &Settings,
+        mailer: Arc<dyn Mailer>,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let Some(user) = UserRepository::find_by_email(pool, email).await? else {
+            return Ok(());
+        };
+
+        let token = Self::generate_opaque_token();
+        PasswordResetTokenRepository::create(
+            pool,
+            user.id,
+            &Self::hash_token(&token),
+            Utc::now() + Duration::minutes(PASSWORD_RESET_EXPIRY_MINUTES),
+        )
+        .await?;
+
+        let message = EmailMessage {
+            to: user.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Someone requested a password reset for your Fitness Tracker account.\n\n\
+                 Use this link within {} minutes to choose a new password:\n\n\
+                 {}/reset-password?token={}\n\n\
+                 If this wasn't you, you can ignore this email.",
+                PASSWORD_RESET_EXPIRY_MINUTES,
+                settings.mail.app_url.trim_end_matches('/'),
+                token
+            ),
+        };
+
+        // Off the request path so response timing doesn't reveal whether the account exists
+        send_in_background(mailer, message);
+
+        Ok(())
+    }
+
+    pub async fn reset_password(
+        pool: &PgPool,
+        token: &str,
+        new_password: &str,
+    ) -> Result<(), AppError> {
+        let reset = PasswordResetTokenRepository::consume(pool, &Self::hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invalid or expired reset token".to_string()))?;
+
+        let new_hash = Self::hash_password(new_password)?;
+        UserRepository::update_password(pool, reset.user_id, &new_hash).await?;
+
+        // Whoever had the old password may still be signed in
+        SessionRepository::revoke_all_for_user(pool, reset.user_id, None).await?;
+        // Proving access to the mailbox lifts any lockout
+        UserRepository::clear_failed_logins(pool, reset.user_id).await?;
+
+        Ok(())
+    }
+
+    async fn send_verification_email(
+        pool: &PgPool,
+        settings: &Settings,
+        mailer: Arc<dyn Mailer>,
+        user: &User,
+    ) -> Result<(), AppError> {
+        let token = Self::generate_opaque_token();
+        EmailVerificationTokenRepository::create(
+            pool,
+            user.id,
+            &Self::hash_token(&token),
+            Utc::now() + Duration::hours(EMAIL_VERIFICATION_EXPIRY_HOURS),
+        )
+        .await?;
+
+        let message = EmailMessage {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Welcome to Fitness Tracker!\n\n\
+                 Confirm your email address within {} hours using this link:\n\n\
+                 {}/verify-email?token={}\n\n\
+                 If you didn't create an account, you can ignore this email.",
+                EMAIL_VERIFICATION_EXPIRY_HOURS,
+                settings.mail.app_url.trim_end_matches('/'),
+                token
+            ),
+        };
+
+        send_in_background(mailer, message);
+
+        Ok(())
+    }
+
+    pub async fn verify_email(pool: &PgPool, token: &str) -> Result<(), AppError> {
+        let verification = EmailVerificationTokenRepository::consume(pool, &Self::hash_token(token))
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest("Invalid or expired verification token".to_string())
+            })?;
+
+        UserRepository::mark_email_verified(pool, verification.user_id).await
+    }
+
+    pub async fn resend_verification_email(
+        pool: &PgPool,
+        settings: &Settings,
+        mailer: Arc<dyn Mailer>,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let user = UserRepository::find_by_id(pool, user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if user.email_verified_at.is_some() {
+            return Err(AppError::Conflict("Email already verified".to_string()));
+        }
+
+        Self::send_verification_email(pool, settings, mailer, &user).await
+    }
+
+    pub async fn list_sessions(
+        pool: &PgPool,
+        user_id: Uuid,
+        current_session_id: Uuid,
+    ) -> Result<SessionListResponse, AppError> {
+        let sessions = SessionRepository::list_active(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|s| SessionResponse {
+                current: s.id == current_session_id,
+                id: s.id,
+                device_label: s.device_label,
+                user_agent: s.user_agent,
+                ip_address: s.ip_address,
+                created_at: s.created_at,
+                last_seen_at: s.last_seen_at,
+            })
+            .collect();
+
+        Ok(SessionListResponse { sessions })
+    }
+
+    pub async fn revoke_session(
+        pool: &PgPool,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<(), AppError> {
+        if !SessionRepository::revoke(pool, session_id, user_id).await? {
+            return Err(AppError::NotFound("Session not found".to_string()));
+        }
+
         Ok(())
     }
 
@@ -205,6 +584,8 @@ impl AuthService {
             id: user.id,
             email: user.email,
             is_admin: user.is_admin,
+            email_verified: user.email_verified_at.is_some(),
+            scheduled_deletion_at: user.scheduled_deletion_at,
         })
     }
 }
@@ -289,20 +670,25 @@ mod tests {
             email: "test@example.com".to_string(),
             password_hash: "hash".to_string(),
             is_admin: false,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            scheduled_deletion_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
-        let secret = "test_jwt_secret_key_that_is_long_enough";
+        let keys = JwtKeys::from_pems(&[], "test_jwt_secret_key_that_is_long_enough").unwrap();
 
         let token = AuthService::generate_token(
             &user,
+            Uuid::new_v4(),
             TokenType::Access,
             Duration::hours(1),
-            secret,
+            &keys,
         )
         .unwrap();
 
-        let claims = AuthService::verify_token(&token, secret).unwrap();
+        let claims = AuthService::verify_token(&token, &keys).unwrap();
 
         assert_eq!(claims.sub, user.id);
         assert_eq!(claims.email, user.email);
@@ -316,21 +702,26 @@ mod tests {
             email: "test@example.com".to_string(),
             password_hash: "hash".to_string(),
             is_admin: false,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            scheduled_deletion_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
-        let secret = "correct_secret";
-        let wrong_secret = "wrong_secret";
+        let keys = JwtKeys::from_pems(&[], "correct_secret").unwrap();
+        let wrong_keys = JwtKeys::from_pems(&[], "wrong_secret").unwrap();
 
         let token = AuthService::generate_token(
             &user,
+            Uuid::new_v4(),
             TokenType::Access,
             Duration::hours(1),
-            secret,
+            &keys,
         )
         .unwrap();
 
-        let result = AuthService::verify_token(&token, wrong_secret);
+        let result = AuthService::verify_token(&token, &wrong_keys);
         assert!(result.is_err());
     }
 
@@ -341,29 +732,35 @@ mod tests {
             email: "test@example.com".to_string(),
             password_hash: "hash".to_string(),
             is_admin: false,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            scheduled_deletion_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
-        let secret = "test_secret";
+        let keys = JwtKeys::from_pems(&[], "test_secret").unwrap();
 
         let access_token = AuthService::generate_token(
             &user,
+            Uuid::new_v4(),
             TokenType::Access,
             Duration::hours(1),
-            secret,
+            &keys,
         )
         .unwrap();
 
         let refresh_token = AuthService::generate_token(
             &user,
+            Uuid::new_v4(),
             TokenType::Refresh,
             Duration::days(7),
-            secret,
+            &keys,
         )
         .unwrap();
 
-        let access_claims = AuthService::verify_token(&access_token, secret).unwrap();
-        let refresh_claims = AuthService::verify_token(&refresh_token, secret).unwrap();
+        let access_claims = AuthService::verify_token(&access_token, &keys).unwrap();
+        let refresh_claims = AuthService::verify_token(&refresh_token, &keys).unwrap();
 
         assert_eq!(access_claims.token_type, TokenType::Access);
         assert_eq!(refresh_claims.token_type, TokenType::Refresh);
@@ -376,26 +773,81 @@ mod tests {
             email: "test@example.com".to_string(),
             password_hash: "hash".to_string(),
             is_admin: false,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            scheduled_deletion_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
-        let secret = "test_secret";
+        let keys = JwtKeys::from_pems(&[], "test_secret").unwrap();
         let expiry_hours = 2;
 
         let token = AuthService::generate_token(
             &user,
+            Uuid::new_v4(),
             TokenType::Access,
             Duration::hours(expiry_hours),
-            secret,
+            &keys,
         )
         .unwrap();
 
-        let claims = AuthService::verify_token(&token, secret).unwrap();
+        let claims = AuthService::verify_token(&token, &keys).unwrap();
         let expected_exp = claims.iat + (expiry_hours * 3600);
 
         assert_eq!(claims.exp, expected_exp);
     }
 
+    #[test]
+    fn test_tokens_issued_together_are_unique() {
+        let user = User {
+            id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            password_hash: "hash".to_string(),
+            is_admin: false,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            scheduled_deletion_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let keys = JwtKeys::from_pems(&[], "test_secret").unwrap();
+        let sid = Uuid::new_v4();
+
+        let first =
+            AuthService::generate_token(&user, sid, TokenType::Refresh, Duration::days(7), &keys)
+                .unwrap();
+        let second =
+            AuthService::generate_token(&user, sid, TokenType::Refresh, Duration::days(7), &keys)
+                .unwrap();
+
+        // Same user, same second: the jti must still make the stored hashes distinct
+        assert_ne!(first, second);
+        assert_ne!(AuthService::hash_token(&first), AuthService::hash_token(&second));
+    }
+
+    #[test]
+    fn test_hash_token_is_deterministic_hex() {
+        let hash1 = AuthService::hash_token("some.refresh.token");
+        let hash2 = AuthService::hash_token("some.refresh.token");
+
+        assert_eq!(hash1, hash2);
+        assert_eq!(hash1.len(), 64);
+        assert!(hash1.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(hash1, AuthService::hash_token("other.refresh.token"));
+    }
+
+    #[test]
+    fn test_generate_opaque_token_is_random_hex() {
+        let first = AuthService::generate_opaque_token();
+        let second = AuthService::generate_opaque_token();
+
+        assert_eq!(first.len(), 64);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+    }
+
     #[test]
     fn test_token_type_serialization() {
         // Test that TokenType serializes correctly for JSON
@@ -407,6 +859,10 @@ mod tests {
 
         assert_eq!(access_json, "\"access\"");
         assert_eq!(refresh_json, "\"refresh\"");
+        assert_eq!(
+            serde_json::to_string(&TokenType::MfaChallenge).unwrap(),
+            "\"mfa_challenge\""
+        );
     }
 
     #[test]
@@ -416,6 +872,8 @@ mod tests {
             email: "test@example.com".to_string(),
             exp: 1234567890,
             iat: 1234567800,
+            jti: Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap(),
+            sid: Uuid::parse_str("6ba7b811-9dad-11d1-80b4-00c04fd430c8").unwrap(),
             token_type: TokenType::Access,
         };
 
@@ -426,6 +884,8 @@ mod tests {
         assert_eq!(deserialized.email, claims.email);
         assert_eq!(deserialized.exp, claims.exp);
         assert_eq!(deserialized.iat, claims.iat);
+        assert_eq!(deserialized.jti, claims.jti);
+        assert_eq!(deserialized.sid, claims.sid);
         assert_eq!(deserialized.token_type, claims.token_type);
     }
 }
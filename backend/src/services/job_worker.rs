@@ -0,0 +1,229 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::config::JobSettings;
+use crate::error::AppError;
+use crate::models::{Job, JobRecord};
+use crate::repositories::JobRepository;
+use crate::services::{PersonalRecordService, WorkoutService};
+
+const BACKOFF_BASE_SECS: u64 = 10;
+const BACKOFF_MAX_SECS: u64 = 3600;
+
+/// Handle to the background worker that drains the `jobs` table.
+pub struct JobWorker {
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl JobWorker {
+    pub fn spawn(pool: PgPool, settings: JobSettings) -> Self {
+        let (shutdown, rx) = watch::channel(false);
+        let task = tokio::spawn(Self::run(pool, settings, rx));
+        Self { shutdown, task }
+    }
+
+    /// Stops claiming new jobs and waits for the one in flight, if any, to finish.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        if let Err(e) = self.task.await {
+            tracing::error!("Job worker panicked: {e}");
+        }
+    }
+
+    /// Processes every job that is due right now and returns how many ran.
+    pub async fn run_pending(pool: &PgPool, settings: &JobSettings) -> usize {
+        let mut processed = 0;
+        while let Ok(Some(record)) =
+            JobRepository::claim_next(pool, settings.lock_timeout_secs, settings.max_attempts).await
+        {
+            Self::process(pool, settings, record).await;
+            processed += 1;
+        }
+        processed
+    }
+
+    async fn run(pool: PgPool, settings: JobSettings, mut shutdown: watch::Receiver<bool>) {
+        let poll_interval = Duration::from_millis(settings.poll_interval_ms);
+
+        while !*shutdown.borrow() {
+            match JobRepository::claim_next(
+                &pool,
+                settings.lock_timeout_secs,
+                settings.max_attempts,
+            )
+            .await
+            {
+                Ok(Some(record)) => {
+                    Self::process(&pool, &settings, record).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Failed to poll job queue: {e}"),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {}
+                changed = shutdown.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        tracing::info!("Job worker shut down.");
+    }
+
+    async fn process(pool: &PgPool, settings: &JobSettings, record: JobRecord) {
+        let job = match Job::from_parts(&record.kind, record.payload) {
+            Ok(job) => job,
+            Err(e) => {
+                // Retrying can't help a job this build doesn't understand
+                let error = format!("Undecodable job: {e}");
+                tracing::error!(job_id = record.id, kind = %record.kind, "{error}");
+                metrics::counter!("jobs_dead_lettered_total", "kind" => record.kind.clone())
+                    .increment(1);
+                if let Err(e) = JobRepository::dead_letter(pool, record.id, &error).await {
+                    tracing::error!(job_id = record.id, "Failed to dead-letter job: {e}");
+                }
+                return;
+            }
+        };
+
+        let kind = job.kind();
+        let execution = {
+            let pool = pool.clone();
+            async move { Self::execute(&pool, &job).await }
+        };
+        let result = tokio::select! {
+            result = isolated(execution) => result,
+            () = Self::keep_locked(pool, record.id, settings.lock_timeout_secs) => {
+                unreachable!("the heartbeat runs until the job finishes")
+            }
+        };
+        let outcome = match result {
+            Ok(()) => JobRepository::complete(pool, record.id).await.map(|_| {
+                metrics::counter!("jobs_completed_total", "kind" => kind).increment(1);
+            }),
+            Err(e) if record.attempts >= settings.max_attempts => {
+                tracing::error!(
+                    job_id = record.id,
+                    kind,
+                    attempts = record.attempts,
+                    "Job failed permanently: {e}"
+                );
+                metrics::counter!("jobs_dead_lettered_total", "kind" => kind).increment(1);
+                JobRepository::dead_letter(pool, record.id, &e.to_string()).await
+            }
+            Err(e) => {
+                let delay = backoff(record.attempts);
+                tracing::warn!(
+                    job_id = record.id,
+                    kind,
+                    attempts = record.attempts,
+                    "Job failed, retrying in {}s: {e}",
+                    delay.as_secs()
+                );
+                metrics::counter!("jobs_retried_total", "kind" => kind).increment(1);
+                let run_at = Utc::now()
+                    + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+                JobRepository::retry_at(pool, record.id, run_at, &e.to_string()).await
+            }
+        };
+
+        // The job stays locked and is picked up again after the lock timeout
+        if let Err(e) = outcome {
+            tracing::error!(job_id = record.id, "Failed to record job outcome: {e}");
+        }
+    }
+
+    /// Refreshes the job's lock a few times per lock timeout, so a job that
+    /// takes longer than the timeout isn't reclaimed and run a second time.
+    async fn keep_locked(pool: &PgPool, id: i64, lock_timeout_secs: i64) {
+        let period = Duration::from_secs((lock_timeout_secs / 3).max(1) as u64);
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = JobRepository::heartbeat(pool, id).await {
+                tracing::warn!(job_id = id, "Failed to refresh job lock: {e}");
+            }
+        }
+    }
+
+    async fn execute(pool: &PgPool, job: &Job) -> Result<(), AppError> {
+        match job {
+            Job::DetectPersonalRecords {
+                workout_id,
+                user_id,
+            } => WorkoutService::detect_personal_records(pool, *workout_id, *user_id).await,
+            Job::RecomputePersonalRecords {
+                user_id,
+                exercise_template_id,
+            } => {
+                match exercise_template_id {
+                    Some(exercise_template_id) => {
+                        PersonalRecordService::recompute_for_exercise(
+                            pool,
+                            *user_id,
+                            exercise_template_id,
+                        )
+                        .await?
+                    }
+                    None => PersonalRecordService::recompute_for_user(pool, *user_id).await?,
+                };
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Runs a job on a task of its own, so a panic only fails that attempt instead
+/// of taking the worker down with it.
+async fn isolated(
+    execution: impl Future<Output = Result<(), AppError>> + Send + 'static,
+) -> Result<(), AppError> {
+    tokio::spawn(execution)
+        .await
+        .unwrap_or_else(|e| Err(AppError::Internal(format!("Job panicked: {e}"))))
+}
+
+/// Delay before retrying after the given (1-based) failed attempt: exponential
+/// from `BACKOFF_BASE_SECS`, capped at `BACKOFF_MAX_SECS`.
+fn backoff(attempt: i32) -> Duration {
+    let exponent = attempt.saturating_sub(1).clamp(0, 16) as u32;
+    Duration::from_secs((BACKOFF_BASE_SECS << exponent).min(BACKOFF_MAX_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_until_capped() {
+        assert_eq!(backoff(1), Duration::from_secs(10));
+        assert_eq!(backoff(2), Duration::from_secs(20));
+        assert_eq!(backoff(3), Duration::from_secs(40));
+        assert_eq!(backoff(9), Duration::from_secs(2560));
+        assert_eq!(backoff(10), Duration::from_secs(BACKOFF_MAX_SECS));
+        assert_eq!(backoff(i32::MAX), Duration::from_secs(BACKOFF_MAX_SECS));
+    }
+
+    #[test]
+    fn test_backoff_treats_unclaimed_attempts_as_first() {
+        assert_eq!(backoff(0), backoff(1));
+    }
+
+    #[tokio::test]
+    async fn test_panicking_job_fails_its_attempt() {
+        let result = isolated(async { panic!("bad payload") }).await;
+        assert!(
+            matches!(result, Err(AppError::Internal(message)) if message.contains("bad payload"))
+        );
+
+        assert!(isolated(async { Ok(()) }).await.is_ok());
+    }
+}
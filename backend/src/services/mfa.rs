@@ -0,0 +1,224 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Settings;
+use crate::dto::{MfaStatusResponse, RecoveryCodesResponse, TotpSetupResponse};
+use crate::error::AppError;
+use crate::models::Role;
+use crate::repositories::{MfaRepository, RoleRepository, UserRepository};
+use crate::services::AuthService;
+use crate::totp;
+
+const TOTP_ISSUER: &str = "Fitness Tracker";
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Unambiguous lowercase alphabet (no 0/o, 1/l/i) for codes users may type by hand
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+pub struct MfaService;
+
+impl MfaService {
+    pub async fn status(pool: &PgPool, user_id: Uuid) -> Result<MfaStatusResponse, AppError> {
+        Ok(MfaStatusResponse {
+            enabled: MfaRepository::is_enabled(pool, user_id).await?,
+            recovery_codes_remaining: MfaRepository::count_unused_recovery_codes(pool, user_id)
+                .await?,
+        })
+    }
+
+    pub async fn setup_totp(pool: &PgPool, user_id: Uuid) -> Result<TotpSetupResponse, AppError> {
+        let user = UserRepository::find_by_id(pool, user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        let secret = totp::generate_secret();
+        if !MfaRepository::upsert_pending(pool, user_id, &secret).await? {
+            return Err(AppError::Conflict(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        Ok(TotpSetupResponse {
+            otpauth_uri: totp::otpauth_uri(TOTP_ISSUER, &user.email, &secret),
+            secret,
+        })
+    }
+
+    pub async fn confirm_totp(
+        pool: &PgPool,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, AppError> {
+        let pending = MfaRepository::find_totp(pool, user_id)
+            .await?
+            .filter(|t| t.confirmed_at.is_none())
+            .ok_or_else(|| AppError::BadRequest("No pending two-factor enrollment".to_string()))?;
+
+        if !Self::verify_totp(pool, user_id, &pending.secret, code).await? {
+            return Err(AppError::BadRequest("Invalid code".to_string()));
+        }
+
+        let codes = Self::generate_recovery_codes();
+        MfaRepository::confirm(pool, user_id, &Self::hash_recovery_codes(&codes)).await?;
+
+        Ok(RecoveryCodesResponse {
+            recovery_codes: codes,
+        })
+    }
+
+    pub async fn regenerate_recovery_codes(
+        pool: &PgPool,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, AppError> {
+        let enrolled = MfaRepository::find_totp(pool, user_id)
+            .await?
+            .filter(|t| t.confirmed_at.is_some())
+            .ok_or_else(|| {
+                AppError::BadRequest("Two-factor authentication is not enabled".to_string())
+            })?;
+
+        if !Self::verify_totp(pool, user_id, &enrolled.secret, code).await? {
+            return Err(AppError::BadRequest("Invalid code".to_string()));
+        }
+
+        let codes = Self::generate_recovery_codes();
+        MfaRepository::replace_recovery_codes(pool, user_id, &Self::hash_recovery_codes(&codes))
+            .await?;
+
+        Ok(RecoveryCodesResponse {
+            recovery_codes: codes,
+        })
+    }
+
+    pub async fn disable_totp(
+        pool: &PgPool,
+        settings: &Settings,
+        user_id: Uuid,
+        password: &str,
+        code: &str,
+    ) -> Result<(), AppError> {
+        let user = UserRepository::find_by_id(pool, user_id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        if settings.auth.require_admin_mfa
+            && RoleRepository::find_for_user(pool, user_id)
+                .await?
+                .iter()
+                .any(Role::is_staff)
+        {
+            return Err(AppError::BadRequest(
+                "Two-factor authentication is mandatory for staff accounts".to_string(),
+            ));
+        }
+
+        if !AuthService::verify_password(password, &user.password_hash)? {
+            return Err(AppError::BadRequest("Password is incorrect".to_string()));
+        }
+
+        if !Self::verify_code(pool, user_id, code).await? {
+            return Err(AppError::BadRequest("Invalid code".to_string()));
+        }
+
+        MfaRepository::disable(pool, user_id).await
+    }
+
+    /// Accepts either a current TOTP code or an unused recovery code for an
+    /// enrolled user. Both are single-use.
+    pub async fn verify_code(pool: &PgPool, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+        let Some(enrolled) = MfaRepository::find_totp(pool, user_id)
+            .await?
+            .filter(|t| t.confirmed_at.is_some())
+        else {
+            return Ok(false);
+        };
+
+        let code = code.trim();
+        if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            return Self::verify_totp(pool, user_id, &enrolled.secret, code).await;
+        }
+
+        let hash = AuthService::hash_token(&Self::normalize_recovery_code(code));
+        MfaRepository::use_recovery_code(pool, user_id, &hash).await
+    }
+
+    async fn verify_totp(
+        pool: &PgPool,
+        user_id: Uuid,
+        secret: &str,
+        code: &str,
+    ) -> Result<bool, AppError> {
+        match totp::verify(secret, code, Utc::now().timestamp()) {
+            Some(step) => MfaRepository::use_step(pool, user_id, step).await,
+            None => Ok(false),
+        }
+    }
+
+    fn generate_recovery_codes() -> Vec<String> {
+        (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let mut bytes = [0u8; 10];
+                OsRng.fill_bytes(&mut bytes);
+                let chars: String = bytes
+                    .iter()
+                    .map(|b| {
+                        RECOVERY_CODE_ALPHABET[*b as usize % RECOVERY_CODE_ALPHABET.len()] as char
+                    })
+                    .collect();
+                format!("{}-{}", &chars[..5], &chars[5..])
+            })
+            .collect()
+    }
+
+    fn hash_recovery_codes(codes: &[String]) -> Vec<String> {
+        codes
+            .iter()
+            .map(|c| AuthService::hash_token(&Self::normalize_recovery_code(c)))
+            .collect()
+    }
+
+    /// Case, spaces and dashes don't matter when typing a recovery code back in.
+    fn normalize_recovery_code(code: &str) -> String {
+        code.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_codes_are_unique_and_formatted() {
+        let codes = MfaService::generate_recovery_codes();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        for code in &codes {
+            assert_eq!(code.len(), 11);
+            assert_eq!(&code[5..6], "-");
+            assert!(code
+                .bytes()
+                .filter(|b| *b != b'-')
+                .all(|b| RECOVERY_CODE_ALPHABET.contains(&b)));
+        }
+
+        let mut deduped = codes.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), codes.len());
+    }
+
+    #[test]
+    fn test_recovery_code_normalization_ignores_case_and_separators() {
+        let hashes = MfaService::hash_recovery_codes(&["abcde-fghjk".to_string()]);
+
+        assert_eq!(
+            hashes[0],
+            AuthService::hash_token(&MfaService::normalize_recovery_code(" ABCDE FGHJK "))
+        );
+    }
+}
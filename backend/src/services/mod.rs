@@ -1,9 +1,17 @@
+mod access_token;
+mod account;
 mod auth;
-pub mod pr_worker;
+mod job_worker;
+mod mfa;
+mod personal_record;
 mod statistics;
 mod workout;
 
+pub use access_token::{AccessTokenService, ACCESS_TOKEN_PREFIX};
+pub use account::AccountService;
 pub use auth::{AuthService, TokenType};
-pub use pr_worker::{pr_worker, PrJob};
+pub use job_worker::JobWorker;
+pub use mfa::MfaService;
+pub use personal_record::PersonalRecordService;
 pub use statistics::StatisticsService;
 pub use workout::WorkoutService;
@@ -0,0 +1,725 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    ExerciseCategory, ExerciseHistorySet, NewPersonalRecord, OneRepMaxFormula, RecordType,
+    WorkoutSet,
+};
+use crate::one_rep_max;
+use crate::repositories::{PersonalRecordRepository, SettingsRepository, WorkoutRepository};
+
+/// Rep counts covered by the rep-max table.
+pub const REP_MAX_RANGE: std::ops::RangeInclusive<i32> = 1..=20;
+
+/// Standard distances for fastest-time records, in meters.
+const FASTEST_TIME_DISTANCES: [(RecordType, f64); 3] = [
+    (RecordType::Fastest1k, 1000.0),
+    (RecordType::Fastest5k, 5000.0),
+    (RecordType::Fastest10k, 10000.0),
+];
+/// Shorter efforts don't count for best pace, so a stride or a typo can't set it.
+const MIN_PACE_DISTANCE_METERS: f64 = 400.0;
+
+/// A record-worthy value from one workout, before it is compared with history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordCandidate {
+    pub record_type: RecordType,
+    pub value: f64,
+    pub reps: Option<i32>,
+    /// Distance of the set it came from, for cardio and carries
+    pub distance_meters: Option<f64>,
+}
+
+impl RecordCandidate {
+    fn new(record_type: RecordType, value: f64, reps: Option<i32>) -> Self {
+        Self {
+            record_type,
+            value,
+            reps,
+            distance_meters: None,
+        }
+    }
+
+    fn over(self, distance_meters: f64) -> Self {
+        Self {
+            distance_meters: Some(distance_meters),
+            ..self
+        }
+    }
+}
+
+pub struct PersonalRecordService;
+
+impl PersonalRecordService {
+    /// Best value of each record type among one exercise's sets in one workout.
+    /// Which types apply depends on the exercise's category. Warm-ups and
+    /// unfinished sets don't count. Sub-sets (drops and rest-pause or cluster
+    /// mini-sets) only add to session volume: a drop's lighter weight or a
+    /// mini-set's few reps aren't a set of their own. Estimated 1RMs use the
+    /// user's `formula`.
+    ///
+    /// Both live detection and recomputation go through here, so records can
+    /// always be rebuilt from history exactly as they were first detected.
+    pub fn candidates(
+        category: &ExerciseCategory,
+        sets: &[WorkoutSet],
+        formula: OneRepMaxFormula,
+    ) -> Vec<RecordCandidate> {
+        let working_sets: Vec<&WorkoutSet> = sets
+            .iter()
+            .filter(|s| s.is_completed && !s.is_warmup)
+            .collect();
+        let main_sets: Vec<&WorkoutSet> = working_sets
+            .iter()
+            .copied()
+            .filter(|s| s.parent_set_id.is_none())
+            .collect();
+
+        match category {
+            ExerciseCategory::Strength | ExerciseCategory::Bodyweight => {
+                Self::strength_candidates(&main_sets, &working_sets, formula)
+            }
+            ExerciseCategory::Cardio => Self::cardio_candidates(&main_sets),
+            ExerciseCategory::Timed => Self::timed_candidates(&main_sets),
+            ExerciseCategory::Carry => Self::carry_candidates(&main_sets),
+        }
+    }
+
+    fn strength_candidates(
+        main_sets: &[&WorkoutSet],
+        working_sets: &[&WorkoutSet],
+        formula: OneRepMaxFormula,
+    ) -> Vec<RecordCandidate> {
+        let max_weight = main_sets
+            .iter()
+            .filter_map(|s| s.actual_weight.map(|w| (w, s.actual_reps)))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+
+        let max_reps = main_sets.iter().filter_map(|s| s.actual_reps).max();
+
+        // Estimated 1RM, RPE-adjusted where the set was rated
+        let e1rm = main_sets
+            .iter()
+            .filter_map(|s| match (s.actual_weight, s.actual_reps) {
+                (Some(weight), Some(reps)) => {
+                    one_rep_max::estimate_set(formula, weight, reps, s.rpe).map(|e1rm| (e1rm, reps))
+                }
+                _ => None,
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+
+        // (volume, reps) of a set that moved weight, both sides of a
+        // unilateral set included
+        let volume_of = |s: &&WorkoutSet| match (s.actual_weight, s.volume_reps) {
+            (Some(weight), Some(reps)) if weight > 0.0 && reps > 0 => {
+                Some((weight * reps as f64, reps))
+            }
+            _ => None,
+        };
+
+        // Best single set by weight x reps
+        let set_volume = main_sets
+            .iter()
+            .filter_map(volume_of)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Best total across the session, sub-sets included; reps is the
+        // session's total
+        let set_volumes: Vec<(f64, i32)> = working_sets.iter().filter_map(volume_of).collect();
+        let session_volume = (!set_volumes.is_empty()).then(|| {
+            set_volumes
+                .iter()
+                .fold((0.0, 0), |(volume, reps), (v, r)| (volume + v, reps + r))
+        });
+
+        // Heaviest weight for each rep count in the table
+        let mut rep_maxes: Vec<(f64, i32)> = Vec::new();
+        for (weight, reps) in main_sets
+            .iter()
+            .filter_map(|s| s.actual_weight.zip(s.actual_reps))
+            .filter(|(weight, reps)| *weight > 0.0 && REP_MAX_RANGE.contains(reps))
+        {
+            match rep_maxes.iter_mut().find(|(_, r)| *r == reps) {
+                Some(best) if weight > best.0 => best.0 = weight,
+                Some(_) => {}
+                None => rep_maxes.push((weight, reps)),
+            }
+        }
+        rep_maxes.sort_by_key(|(_, reps)| *reps);
+
+        [
+            max_weight
+                .map(|(weight, reps)| RecordCandidate::new(RecordType::MaxWeight, weight, reps)),
+            max_reps.map(|reps| RecordCandidate::new(RecordType::MaxReps, reps as f64, Some(reps))),
+            e1rm.map(|(e1rm, reps)| {
+                RecordCandidate::new(RecordType::Estimated1rm, e1rm, Some(reps))
+            }),
+            set_volume.map(|(volume, reps)| {
+                RecordCandidate::new(RecordType::MaxVolume, volume, Some(reps))
+            }),
+            session_volume.map(|(volume, reps)| {
+                RecordCandidate::new(RecordType::MaxSessionVolume, volume, Some(reps))
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(
+            rep_maxes
+                .into_iter()
+                .map(|(weight, reps)| RecordCandidate::new(RecordType::RepMax, weight, Some(reps))),
+        )
+        .collect()
+    }
+
+    fn cardio_candidates(working_sets: &[&WorkoutSet]) -> Vec<RecordCandidate> {
+        let distances: Vec<(f64, Option<i32>)> = working_sets
+            .iter()
+            .filter_map(|s| s.distance_meters.map(|d| (d, s.duration_seconds)))
+            .filter(|(distance, _)| *distance > 0.0)
+            .collect();
+        // (distance, seconds) of every set with both
+        let timed: Vec<(f64, f64)> = distances
+            .iter()
+            .filter_map(|(distance, seconds)| match seconds {
+                Some(seconds) if *seconds > 0 => Some((*distance, *seconds as f64)),
+                _ => None,
+            })
+            .collect();
+
+        let mut candidates = Vec::new();
+
+        if let Some((distance, _)) = distances.iter().max_by(|a, b| a.0.total_cmp(&b.0)) {
+            candidates.push(
+                RecordCandidate::new(RecordType::LongestDistance, *distance, None).over(*distance),
+            );
+        }
+
+        // A longer effort counts for shorter distances at its average pace
+        for (record_type, target) in FASTEST_TIME_DISTANCES {
+            let fastest = timed
+                .iter()
+                .filter(|(distance, _)| *distance >= target)
+                .map(|(distance, seconds)| (seconds * target / distance, *distance))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((seconds, distance)) = fastest {
+                candidates.push(RecordCandidate::new(record_type, seconds, None).over(distance));
+            }
+        }
+
+        let best_pace = timed
+            .iter()
+            .filter(|(distance, _)| *distance >= MIN_PACE_DISTANCE_METERS)
+            .map(|(distance, seconds)| (seconds * 1000.0 / distance, *distance))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((pace, distance)) = best_pace {
+            candidates.push(RecordCandidate::new(RecordType::BestPace, pace, None).over(distance));
+        }
+
+        candidates
+    }
+
+    fn timed_candidates(working_sets: &[&WorkoutSet]) -> Vec<RecordCandidate> {
+        working_sets
+            .iter()
+            .filter_map(|s| s.duration_seconds)
+            .filter(|seconds| *seconds > 0)
+            .max()
+            .map(|seconds| RecordCandidate::new(RecordType::LongestHold, seconds as f64, None))
+            .into_iter()
+            .collect()
+    }
+
+    fn carry_candidates(working_sets: &[&WorkoutSet]) -> Vec<RecordCandidate> {
+        // Heaviest first, then furthest
+        working_sets
+            .iter()
+            .filter_map(|s| s.actual_weight.zip(s.distance_meters))
+            .filter(|(weight, distance)| *weight > 0.0 && *distance > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            .map(|(weight, distance)| {
+                RecordCandidate::new(RecordType::HeaviestCarry, weight, None).over(distance)
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// What a candidate competes against: its type, plus the rep count for
+    /// types tracked per rep count.
+    pub fn record_key(record_type: RecordType, reps: Option<i32>) -> (RecordType, Option<i32>) {
+        (record_type, reps.filter(|_| record_type.is_per_rep_count()))
+    }
+
+    /// Rebuilds a user's records for one exercise from their completed,
+    /// non-deleted workouts, replacing whatever was stored. Returns how many
+    /// records were written.
+    pub async fn recompute_for_exercise(
+        pool: &PgPool,
+        user_id: Uuid,
+        exercise_template_id: &str,
+    ) -> Result<usize, AppError> {
+        let category = WorkoutRepository::get_exercise_category(pool, exercise_template_id)
+            .await?
+            .unwrap_or(ExerciseCategory::Strength);
+        let formula = SettingsRepository::get_or_create(pool, user_id)
+            .await?
+            .one_rep_max_formula;
+        let history = WorkoutRepository::find_completed_sets_for_exercise(
+            pool,
+            user_id,
+            exercise_template_id,
+        )
+        .await?;
+
+        let records = Self::records_from_history(&category, formula, history);
+        PersonalRecordRepository::replace_for_exercise(
+            pool,
+            user_id,
+            exercise_template_id,
+            &records,
+        )
+        .await?;
+
+        Ok(records.len())
+    }
+
+    /// Replays one exercise's history, oldest workout first, keeping every
+    /// candidate that beat the best before it and linking each to the record
+    /// it superseded.
+    fn records_from_history(
+        category: &ExerciseCategory,
+        formula: OneRepMaxFormula,
+        history: Vec<ExerciseHistorySet>,
+    ) -> Vec<NewPersonalRecord> {
+        // History is ordered by completion, so each workout's sets are contiguous
+        let mut sessions: Vec<Vec<ExerciseHistorySet>> = Vec::new();
+        for entry in history {
+            match sessions.last_mut() {
+                Some(session) if session[0].workout_id == entry.workout_id => session.push(entry),
+                _ => sessions.push(vec![entry]),
+            }
+        }
+
+        // Current best of each record line, as an index into `records`
+        let mut best: HashMap<(RecordType, Option<i32>), usize> = HashMap::new();
+        let mut records: Vec<NewPersonalRecord> = Vec::new();
+        for session in sessions {
+            let first = &session[0];
+            let (workout_id, achieved_at) = (first.workout_id, first.workout_completed_at);
+            let exercise_name = first.exercise_name.clone();
+            let sets: Vec<WorkoutSet> = session.into_iter().map(|entry| entry.set).collect();
+
+            for candidate in Self::candidates(category, &sets, formula) {
+                let key = Self::record_key(candidate.record_type, candidate.reps);
+                let previous = best.get(&key).map(|i| &mut records[*i]);
+                if previous
+                    .as_ref()
+                    .is_some_and(|p| !candidate.record_type.beats(candidate.value, p.value))
+                {
+                    continue;
+                }
+                let previous_value = previous.map(|p| {
+                    p.superseded_at = Some(achieved_at);
+                    p.value
+                });
+                best.insert(key, records.len());
+                records.push(NewPersonalRecord {
+                    exercise_name: exercise_name.clone(),
+                    record_type: candidate.record_type,
+                    value: candidate.value,
+                    reps: candidate.reps,
+                    distance_meters: candidate.distance_meters,
+                    achieved_at,
+                    workout_id,
+                    previous_value,
+                    superseded_at: None,
+                });
+            }
+        }
+
+        records
+    }
+
+    /// Recomputes every exercise the user has trained or holds a record for.
+    pub async fn recompute_for_user(pool: &PgPool, user_id: Uuid) -> Result<usize, AppError> {
+        let exercise_ids =
+            PersonalRecordRepository::find_recordable_exercises(pool, user_id).await?;
+
+        let mut written = 0;
+        for exercise_id in exercise_ids {
+            written += Self::recompute_for_exercise(pool, user_id, &exercise_id).await?;
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SetType;
+    use chrono::TimeZone;
+
+    fn set(
+        weight: Option<f64>,
+        reps: Option<i32>,
+        is_warmup: bool,
+        is_completed: bool,
+    ) -> WorkoutSet {
+        WorkoutSet {
+            id: Uuid::new_v4(),
+            workout_exercise_id: Uuid::nil(),
+            set_number: 1,
+            target_reps: None,
+            actual_reps: reps,
+            target_weight: None,
+            actual_weight: weight,
+            is_warmup,
+            is_completed,
+            completed_at: None,
+            rpe: None,
+            distance_meters: None,
+            duration_seconds: None,
+            calories: None,
+            target_distance_meters: None,
+            target_duration_seconds: None,
+            rest_seconds_actual: None,
+            set_type: SetType::Normal,
+            parent_set_id: None,
+            target_rir: None,
+            rir: None,
+            tempo: None,
+            is_unilateral: false,
+            actual_reps_left: None,
+            actual_reps_right: None,
+            volume_reps: reps,
+            version: 1,
+        }
+    }
+
+    fn cardio_set(distance: Option<f64>, seconds: Option<i32>) -> WorkoutSet {
+        WorkoutSet {
+            distance_meters: distance,
+            duration_seconds: seconds,
+            ..set(None, None, false, true)
+        }
+    }
+
+    fn value_of(
+        candidates: &[RecordCandidate],
+        record_type: RecordType,
+    ) -> Option<(f64, Option<i32>)> {
+        candidates
+            .iter()
+            .find(|c| c.record_type == record_type)
+            .map(|c| (c.value, c.reps))
+    }
+
+    fn strength(sets: &[WorkoutSet]) -> Vec<RecordCandidate> {
+        PersonalRecordService::candidates(
+            &ExerciseCategory::Strength,
+            sets,
+            OneRepMaxFormula::Brzycki,
+        )
+    }
+
+    #[test]
+    fn test_candidates_cover_weight_reps_and_volume() {
+        let sets = [
+            set(Some(100.0), Some(5), false, true),
+            set(Some(80.0), Some(10), false, true),
+        ];
+        let candidates = strength(&sets);
+
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxWeight),
+            Some((100.0, Some(5)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxReps),
+            Some((10.0, Some(10)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxVolume),
+            Some((800.0, Some(10)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxSessionVolume),
+            Some((1300.0, Some(15)))
+        );
+        assert!(value_of(&candidates, RecordType::Estimated1rm).is_some());
+    }
+
+    #[test]
+    fn test_estimated_1rm_uses_formula_and_rpe() {
+        let e1rm = |formula, rpe| {
+            let sets = [WorkoutSet {
+                rpe,
+                ..set(Some(100.0), Some(5), false, true)
+            }];
+            let candidates =
+                PersonalRecordService::candidates(&ExerciseCategory::Strength, &sets, formula);
+            value_of(&candidates, RecordType::Estimated1rm).map(|(value, _)| value)
+        };
+
+        assert_eq!(
+            e1rm(OneRepMaxFormula::Epley, None),
+            one_rep_max::estimate(OneRepMaxFormula::Epley, 100.0, 5)
+        );
+        assert_eq!(
+            e1rm(OneRepMaxFormula::Epley, Some(8)),
+            one_rep_max::estimate_set(OneRepMaxFormula::Brzycki, 100.0, 5, Some(8))
+        );
+    }
+
+    #[test]
+    fn test_candidates_ignore_warmups_and_unfinished_sets() {
+        let sets = [
+            set(Some(140.0), Some(1), true, true),
+            set(Some(150.0), Some(1), false, false),
+            set(Some(100.0), Some(3), false, true),
+        ];
+        let candidates = strength(&sets);
+
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxWeight),
+            Some((100.0, Some(3)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxSessionVolume),
+            Some((300.0, Some(3)))
+        );
+    }
+
+    #[test]
+    fn test_candidates_include_rep_max_per_rep_count() {
+        let sets = [
+            set(Some(100.0), Some(5), false, true),
+            set(Some(105.0), Some(5), false, true),
+            set(Some(120.0), Some(1), false, true),
+            set(Some(40.0), Some(25), false, true),
+        ];
+        let rep_maxes: Vec<(f64, Option<i32>)> = strength(&sets)
+            .into_iter()
+            .filter(|c| c.record_type == RecordType::RepMax)
+            .map(|c| (c.value, c.reps))
+            .collect();
+
+        // Sorted by rep count; 25 reps is outside the table
+        assert_eq!(rep_maxes, vec![(120.0, Some(1)), (105.0, Some(5))]);
+    }
+
+    #[test]
+    fn test_record_key_separates_rep_counts_for_rep_max_only() {
+        assert_eq!(
+            PersonalRecordService::record_key(RecordType::RepMax, Some(3)),
+            (RecordType::RepMax, Some(3))
+        );
+        assert_eq!(
+            PersonalRecordService::record_key(RecordType::MaxWeight, Some(3)),
+            (RecordType::MaxWeight, None)
+        );
+    }
+
+    #[test]
+    fn test_sub_sets_only_add_to_session_volume() {
+        let top = WorkoutSet {
+            set_type: SetType::Drop,
+            ..set(Some(100.0), Some(5), false, true)
+        };
+        let drop = |weight, reps| WorkoutSet {
+            set_type: SetType::Drop,
+            parent_set_id: Some(top.id),
+            ..set(Some(weight), Some(reps), false, true)
+        };
+        let sets = [top.clone(), drop(80.0, 8), drop(60.0, 12)];
+        let candidates = strength(&sets);
+
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxReps),
+            Some((5.0, Some(5)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxVolume),
+            Some((500.0, Some(5)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxSessionVolume),
+            Some((1860.0, Some(25)))
+        );
+        assert!(
+            strength(&sets)
+                .iter()
+                .all(|c| c.record_type != RecordType::RepMax || c.reps == Some(5))
+        );
+    }
+
+    #[test]
+    fn test_unilateral_volume_counts_both_sides() {
+        // 10 reps a side with 20 kg dumbbells
+        let sets = [WorkoutSet {
+            is_unilateral: true,
+            actual_reps_left: Some(10),
+            actual_reps_right: Some(10),
+            volume_reps: Some(20),
+            ..set(Some(20.0), Some(10), false, true)
+        }];
+        let candidates = strength(&sets);
+
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxReps),
+            Some((10.0, Some(10)))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxVolume),
+            Some((400.0, Some(20)))
+        );
+    }
+
+    #[test]
+    fn test_amrap_sets_count_like_any_other() {
+        let sets = [WorkoutSet {
+            set_type: SetType::Amrap,
+            ..set(Some(60.0), Some(15), false, true)
+        }];
+
+        assert_eq!(
+            value_of(&strength(&sets), RecordType::MaxReps),
+            Some((15.0, Some(15)))
+        );
+    }
+
+    #[test]
+    fn test_bodyweight_sets_have_no_volume() {
+        let sets = [set(None, Some(12), false, true)];
+        let candidates = strength(&sets);
+
+        assert_eq!(
+            value_of(&candidates, RecordType::MaxReps),
+            Some((12.0, Some(12)))
+        );
+        assert_eq!(value_of(&candidates, RecordType::MaxVolume), None);
+        assert_eq!(value_of(&candidates, RecordType::MaxSessionVolume), None);
+    }
+
+    #[test]
+    fn test_cardio_records_pro_rate_longer_efforts() {
+        let sets = [
+            // 5 km in 25:00 (5:00/km)
+            cardio_set(Some(5000.0), Some(1500)),
+            // 1.2 km in 5:24 (4:30/km)
+            cardio_set(Some(1200.0), Some(324)),
+            cardio_set(Some(200.0), Some(30)),
+        ];
+        let candidates = PersonalRecordService::candidates(
+            &ExerciseCategory::Cardio,
+            &sets,
+            OneRepMaxFormula::Brzycki,
+        );
+
+        assert_eq!(
+            value_of(&candidates, RecordType::LongestDistance),
+            Some((5000.0, None))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::Fastest1k),
+            Some((270.0, None))
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::Fastest5k),
+            Some((1500.0, None))
+        );
+        assert_eq!(value_of(&candidates, RecordType::Fastest10k), None);
+        // The 200 m stride is too short to count for pace
+        assert_eq!(
+            value_of(&candidates, RecordType::BestPace),
+            Some((270.0, None))
+        );
+        assert_eq!(value_of(&candidates, RecordType::MaxWeight), None);
+
+        let fastest_1k = candidates
+            .iter()
+            .find(|c| c.record_type == RecordType::Fastest1k)
+            .unwrap();
+        assert_eq!(fastest_1k.distance_meters, Some(1200.0));
+    }
+
+    #[test]
+    fn test_timed_and_carry_records() {
+        let holds = [cardio_set(None, Some(90)), cardio_set(None, Some(120))];
+        let candidates = PersonalRecordService::candidates(
+            &ExerciseCategory::Timed,
+            &holds,
+            OneRepMaxFormula::Brzycki,
+        );
+        assert_eq!(
+            value_of(&candidates, RecordType::LongestHold),
+            Some((120.0, None))
+        );
+
+        let carries = [
+            WorkoutSet {
+                distance_meters: Some(40.0),
+                ..set(Some(60.0), None, false, true)
+            },
+            WorkoutSet {
+                distance_meters: Some(20.0),
+                ..set(Some(80.0), None, false, true)
+            },
+            // Weight without a distance isn't a carry
+            set(Some(100.0), None, false, true),
+        ];
+        let candidates = PersonalRecordService::candidates(
+            &ExerciseCategory::Carry,
+            &carries,
+            OneRepMaxFormula::Brzycki,
+        );
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].record_type, RecordType::HeaviestCarry);
+        assert_eq!(candidates[0].value, 80.0);
+        assert_eq!(candidates[0].distance_meters, Some(20.0));
+    }
+
+    fn completed_on(day: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc
+            .with_ymd_and_hms(2026, 1, day, 12, 0, 0)
+            .unwrap()
+    }
+
+    fn history_entry(workout: u128, day: u32, weight: f64, reps: i32) -> ExerciseHistorySet {
+        ExerciseHistorySet {
+            workout_id: Uuid::from_u128(workout),
+            workout_completed_at: completed_on(day),
+            exercise_name: "Bench Press".to_string(),
+            set: set(Some(weight), Some(reps), false, true),
+        }
+    }
+
+    #[test]
+    fn test_history_links_superseded_records() {
+        let history = vec![
+            history_entry(1, 1, 100.0, 5),
+            history_entry(2, 8, 95.0, 5),
+            history_entry(3, 15, 105.0, 5),
+        ];
+        let records = PersonalRecordService::records_from_history(
+            &ExerciseCategory::Strength,
+            OneRepMaxFormula::Brzycki,
+            history,
+        );
+        let max_weight: Vec<&NewPersonalRecord> = records
+            .iter()
+            .filter(|r| r.record_type == RecordType::MaxWeight)
+            .collect();
+
+        assert_eq!(max_weight.len(), 2);
+        assert_eq!(max_weight[0].value, 100.0);
+        assert_eq!(max_weight[0].previous_value, None);
+        assert_eq!(max_weight[0].superseded_at, Some(completed_on(15)));
+        assert_eq!(max_weight[1].value, 105.0);
+        assert_eq!(max_weight[1].previous_value, Some(100.0));
+        assert_eq!(max_weight[1].superseded_at, None);
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+use chrono::{Duration, NaiveDate, Utc};
 use sqlx::PgPool;
 use tracing::{debug, error, info, instrument};
 use uuid::Uuid;
@@ -69,25 +69,17 @@ pub fn calculate_percentage(part: i64, total: i64) -> f64 {
     }
 }
 
-#[cfg(test)]
-/// Calculate estimated 1RM using Brzycki formula
-/// Used in statistics for exercise progress charts
-pub fn estimate_1rm(weight: f64, reps: i32) -> Option<f64> {
-    if reps > 0 && reps <= 12 {
-        Some(weight * (36.0 / (37.0 - reps as f64)))
-    } else {
-        None
-    }
-}
-
 use crate::dto::{
     DashboardSummary, ExerciseHistoryEntry, ExerciseOverloadSuggestion, ExercisePlateauAlert,
     ExerciseProgressResponse, MuscleGroupData, MuscleGroupDistribution,
-    OverloadSuggestionsResponse, PersonalRecordResponse, PlateauAlertResponse, SetHistoryEntry,
+    OverloadSuggestionsResponse, PersonalRecordResponse, PlateauAlertResponse,
+    RecordTimelineResponse, RecordTimelineSeries, RepMaxEntry, RepMaxTableResponse,
+    SetHistoryEntry,
     StatisticsQuery, SuggestionConfidence, SuggestionType, WeekVolume, WeeklyVolumeResponse,
 };
 use crate::error::AppError;
-use crate::models::{MuscleGroup, WeightUnit};
+use crate::models::{MuscleGroup, OneRepMaxFormula, SetType, UserSettings, WeightUnit};
+use crate::one_rep_max;
 use crate::repositories::{PersonalRecordRepository, SettingsRepository};
 
 fn is_large_muscle_group(mg: &MuscleGroup) -> bool {
@@ -112,8 +104,9 @@ impl StatisticsService {
     ) -> Result<DashboardSummary, AppError> {
         info!("Starting dashboard summary calculation");
 
-        let now = Utc::now();
-        let week_start = now - Duration::days(now.weekday().num_days_from_monday() as i64);
+        let settings = Self::settings(pool, user_id).await?;
+        let tz = settings.tz();
+        let week_start = settings.week_start.week_of(settings.today());
 
         // Run all 6 independent queries in parallel
         let (
@@ -130,22 +123,24 @@ impl StatisticsService {
             .bind(user_id)
             .fetch_one(pool),
             sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM workouts WHERE user_id = $1 AND status = 'completed' AND started_at >= $2",
+                "SELECT COUNT(*) FROM workouts WHERE user_id = $1 AND status = 'completed' AND (started_at AT TIME ZONE $3)::date >= $2",
             )
             .bind(user_id)
             .bind(week_start)
+            .bind(tz.name())
             .fetch_one(pool),
             sqlx::query_as::<_, (f64, f64)>(
                 r#"
                 SELECT
                     COALESCE(SUM(total_volume), 0),
-                    COALESCE(SUM(CASE WHEN started_at >= $2 THEN total_volume ELSE 0 END), 0)
+                    COALESCE(SUM(CASE WHEN (started_at AT TIME ZONE $3)::date >= $2 THEN total_volume ELSE 0 END), 0)
                 FROM workouts
                 WHERE user_id = $1 AND status = 'completed'
                 "#,
             )
             .bind(user_id)
             .bind(week_start)
+            .bind(tz.name())
             .fetch_one(pool),
             sqlx::query_as::<_, (i64, i64)>(
                 r#"
@@ -158,7 +153,7 @@ impl StatisticsService {
             )
             .bind(user_id)
             .fetch_one(pool),
-            Self::calculate_streaks(pool, user_id),
+            Self::calculate_streaks(pool, user_id, &settings),
             PersonalRecordRepository::find_recent(pool, user_id, 5),
         );
 
@@ -169,16 +164,7 @@ impl StatisticsService {
         let (current_streak, longest_streak) = streaks_r?;
         let recent_prs = recent_prs_r?
             .into_iter()
-            .map(|pr| PersonalRecordResponse {
-                id: pr.id,
-                exercise_template_id: pr.exercise_template_id,
-                exercise_name: pr.exercise_name,
-                type_: pr.type_,
-                value: pr.value,
-                reps: pr.reps,
-                achieved_at: pr.achieved_at,
-                workout_id: pr.workout_id,
-            })
+            .map(PersonalRecordResponse::from)
             .collect();
 
         info!("Dashboard summary calculation completed successfully");
@@ -195,18 +181,24 @@ impl StatisticsService {
         })
     }
 
-    #[instrument(skip(pool), fields(user_id = %user_id))]
-    async fn calculate_streaks(pool: &PgPool, user_id: Uuid) -> Result<(i32, i32), AppError> {
+    /// Streaks count the user's local calendar days.
+    #[instrument(skip(pool, settings), fields(user_id = %user_id))]
+    async fn calculate_streaks(
+        pool: &PgPool,
+        user_id: Uuid,
+        settings: &UserSettings,
+    ) -> Result<(i32, i32), AppError> {
         debug!("Fetching workout dates for streak calculation");
         let workout_dates = sqlx::query_scalar::<_, NaiveDate>(
             r#"
-            SELECT DISTINCT DATE(started_at) as workout_date
+            SELECT DISTINCT (started_at AT TIME ZONE $2)::date as workout_date
             FROM workouts
             WHERE user_id = $1 AND status = 'completed'
             ORDER BY workout_date DESC
             "#,
         )
         .bind(user_id)
+        .bind(settings.tz().name())
         .fetch_all(pool)
         .await
         .map_err(|e| {
@@ -221,7 +213,7 @@ impl StatisticsService {
             return Ok((0, 0));
         }
 
-        let today = Utc::now().date_naive();
+        let today = settings.today();
         let mut current_streak = 0;
         let mut longest_streak = 0;
         let mut streak = 0;
@@ -275,8 +267,9 @@ impl StatisticsService {
         user_id: Uuid,
         query: &StatisticsQuery,
     ) -> Result<WeeklyVolumeResponse, AppError> {
+        let settings = Self::settings(pool, user_id).await?;
         let weeks = query.weeks.unwrap_or(12);
-        let end_date = query.end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let end_date = query.end_date.unwrap_or_else(|| settings.today());
         let start_date = query
             .start_date
             .unwrap_or_else(|| end_date - Duration::weeks(weeks as i64));
@@ -284,21 +277,27 @@ impl StatisticsService {
         let rows = sqlx::query_as::<_, WeekVolumeRow>(
             r#"
             SELECT
-                DATE_TRUNC('week', started_at)::date as week_start,
+                week_start,
                 COALESCE(SUM(total_volume), 0) as total_volume,
                 COUNT(*)::int as workout_count
-            FROM workouts
-            WHERE user_id = $1
-                AND status = 'completed'
-                AND DATE(started_at) >= $2
-                AND DATE(started_at) <= $3
-            GROUP BY DATE_TRUNC('week', started_at)
+            FROM (
+                SELECT
+                    DATE_TRUNC('week', (started_at AT TIME ZONE $4) + make_interval(days => $5))::date - $5 as week_start,
+                    (started_at AT TIME ZONE $4)::date as local_date,
+                    total_volume
+                FROM workouts
+                WHERE user_id = $1 AND status = 'completed'
+            ) w
+            WHERE local_date >= $2 AND local_date <= $3
+            GROUP BY week_start
             ORDER BY week_start
             "#,
         )
         .bind(user_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(settings.tz().name())
+        .bind(settings.week_start.sql_offset_days())
         .fetch_all(pool)
         .await?;
 
@@ -319,7 +318,8 @@ impl StatisticsService {
         user_id: Uuid,
         query: &StatisticsQuery,
     ) -> Result<MuscleGroupDistribution, AppError> {
-        let end_date = query.end_date.unwrap_or_else(|| Utc::now().date_naive());
+        let settings = Self::settings(pool, user_id).await?;
+        let end_date = query.end_date.unwrap_or_else(|| settings.today());
         let start_date = query
             .start_date
             .unwrap_or_else(|| end_date - Duration::days(30));
@@ -328,8 +328,8 @@ impl StatisticsService {
             r#"
             SELECT
                 emg.muscle_group,
-                COUNT(DISTINCT ws.id) as set_count,
-                COALESCE(SUM(ws.actual_weight * ws.actual_reps), 0) as volume
+                COUNT(DISTINCT ws.id) FILTER (WHERE ws.parent_set_id IS NULL) as set_count,
+                COALESCE(SUM(ws.actual_weight * ws.volume_reps), 0) as volume
             FROM workouts w
             JOIN workout_exercises we ON we.workout_id = w.id
             JOIN workout_sets ws ON ws.workout_exercise_id = we.id
@@ -338,8 +338,8 @@ impl StatisticsService {
                 AND w.status = 'completed'
                 AND ws.is_completed = true
                 AND ws.is_warmup = false
-                AND DATE(w.started_at) >= $2
-                AND DATE(w.started_at) <= $3
+                AND (w.started_at AT TIME ZONE $4)::date >= $2
+                AND (w.started_at AT TIME ZONE $4)::date <= $3
             GROUP BY emg.muscle_group
             ORDER BY set_count DESC
             "#,
@@ -347,6 +347,7 @@ impl StatisticsService {
         .bind(user_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(settings.tz().name())
         .fetch_all(pool)
         .await?;
 
@@ -374,20 +375,8 @@ impl StatisticsService {
         user_id: Uuid,
         exercise_id: &str,
     ) -> Result<ExerciseProgressResponse, AppError> {
-        // Get exercise name (cached — exercise templates are static)
-        let exercise_name = if let Some(cached) = crate::cache::get_exercise_name(exercise_id) {
-            cached
-        } else {
-            let name = sqlx::query_scalar::<_, String>(
-                "SELECT name FROM exercise_templates WHERE id = $1",
-            )
-            .bind(exercise_id)
-            .fetch_optional(pool)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
-            crate::cache::set_exercise_name(exercise_id.to_string(), name.clone());
-            name
-        };
+        let exercise_name = Self::exercise_name(pool, exercise_id).await?;
+        let formula = Self::settings(pool, user_id).await?.one_rep_max_formula;
 
         // Get workout history for this exercise (ordered oldest to newest for chart display)
         let history_rows = sqlx::query_as::<_, ExerciseHistoryRow>(
@@ -422,30 +411,32 @@ impl StatisticsService {
                 .unwrap_or_default();
 
             let working_sets: Vec<_> = sets.iter().filter(|s| !s.is_warmup).collect();
+            // Drops and mini-sets add volume but aren't sets of their own
+            let main_sets: Vec<_> = working_sets
+                .iter()
+                .copied()
+                .filter(|s| !s.is_sub_set)
+                .collect();
 
-            let max_weight = working_sets
+            let max_weight = main_sets
                 .iter()
                 .filter_map(|s| s.actual_weight)
                 .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
             let total_volume: f64 = working_sets
                 .iter()
-                .filter_map(|s| match (s.actual_weight, s.actual_reps) {
+                .filter_map(|s| match (s.actual_weight, s.volume_reps) {
                     (Some(w), Some(r)) => Some(w * r as f64),
                     _ => None,
                 })
                 .sum();
 
             // Estimated 1RM from best set
-            let estimated_1rm = working_sets
-                .iter()
-                .filter_map(|s| match (s.actual_weight, s.actual_reps) {
-                    (Some(w), Some(r)) if r > 0 && r <= 12 => {
-                        Some(w * (36.0 / (37.0 - r as f64)))
-                    }
-                    _ => None,
-                })
-                .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            let estimated_1rm = best_estimated_1rm(main_sets.iter().copied(), formula);
+
+            let rests: Vec<i32> = main_sets.iter().filter_map(|s| s.rest_seconds).collect();
+            let avg_rest_seconds = (!rests.is_empty())
+                .then(|| rests.iter().sum::<i32>() / rests.len() as i32);
 
             history.push(ExerciseHistoryEntry {
                 date: row.date,
@@ -457,11 +448,15 @@ impl StatisticsService {
                         reps: s.actual_reps,
                         weight: s.actual_weight,
                         is_warmup: s.is_warmup,
+                        rest_seconds: s.rest_seconds,
+                        set_type: s.set_type,
+                        is_sub_set: s.is_sub_set,
                     })
                     .collect(),
                 max_weight,
                 total_volume,
                 estimated_1rm,
+                avg_rest_seconds,
             });
         }
 
@@ -469,16 +464,7 @@ impl StatisticsService {
         let prs = PersonalRecordRepository::find_by_exercise(pool, user_id, exercise_id).await?;
         let personal_records = prs
             .into_iter()
-            .map(|pr| PersonalRecordResponse {
-                id: pr.id,
-                exercise_template_id: pr.exercise_template_id,
-                exercise_name: pr.exercise_name,
-                type_: pr.type_,
-                value: pr.value,
-                reps: pr.reps,
-                achieved_at: pr.achieved_at,
-                workout_id: pr.workout_id,
-            })
+            .map(PersonalRecordResponse::from)
             .collect();
 
         Ok(ExerciseProgressResponse {
@@ -489,6 +475,98 @@ impl StatisticsService {
         })
     }
 
+    pub async fn get_rep_maxes(
+        pool: &PgPool,
+        user_id: Uuid,
+        exercise_id: &str,
+    ) -> Result<RepMaxTableResponse, AppError> {
+        let exercise_name = Self::exercise_name(pool, exercise_id).await?;
+
+        let rep_maxes = PersonalRecordRepository::find_rep_maxes(pool, user_id, exercise_id)
+            .await?
+            .into_iter()
+            .filter_map(|pr| {
+                Some(RepMaxEntry {
+                    reps: pr.reps?,
+                    weight: pr.value,
+                    achieved_at: pr.achieved_at,
+                    workout_id: pr.workout_id,
+                })
+            })
+            .collect();
+
+        Ok(RepMaxTableResponse {
+            exercise_template_id: exercise_id.to_string(),
+            exercise_name,
+            rep_maxes,
+        })
+    }
+
+    pub async fn get_record_timeline(
+        pool: &PgPool,
+        user_id: Uuid,
+        exercise_id: &str,
+    ) -> Result<RecordTimelineResponse, AppError> {
+        let exercise_name = Self::exercise_name(pool, exercise_id).await?;
+
+        let mut records =
+            PersonalRecordRepository::find_by_exercise(pool, user_id, exercise_id).await?;
+        records.reverse();
+
+        // One series per record line, in order of each line's first record
+        let mut series: Vec<RecordTimelineSeries> = Vec::new();
+        for pr in records {
+            let reps = pr.reps.filter(|_| pr.type_.is_per_rep_count());
+            match series
+                .iter_mut()
+                .find(|s| s.type_ == pr.type_ && s.reps == reps)
+            {
+                Some(line) => line.records.push(pr.into()),
+                None => series.push(RecordTimelineSeries {
+                    type_: pr.type_,
+                    reps,
+                    records: vec![pr.into()],
+                }),
+            }
+        }
+
+        Ok(RecordTimelineResponse {
+            exercise_template_id: exercise_id.to_string(),
+            exercise_name,
+            series,
+        })
+    }
+
+    /// Exercise name (cached — exercise templates are static)
+    async fn exercise_name(pool: &PgPool, exercise_id: &str) -> Result<String, AppError> {
+        if let Some(cached) = crate::cache::get_exercise_name(exercise_id) {
+            return Ok(cached);
+        }
+
+        let name = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM exercise_templates WHERE id = $1",
+        )
+        .bind(exercise_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+        crate::cache::set_exercise_name(exercise_id.to_string(), name.clone());
+
+        Ok(name)
+    }
+
+    /// User settings (cached with 60s TTL)
+    pub async fn settings(pool: &PgPool, user_id: Uuid) -> Result<UserSettings, AppError> {
+        if let Some(cached) = crate::cache::get_settings(user_id) {
+            return Ok(cached);
+        }
+
+        let settings = SettingsRepository::get_or_create(pool, user_id).await?;
+        crate::cache::set_settings(user_id, settings.clone());
+
+        Ok(settings)
+    }
+
     #[instrument(skip(pool), fields(user_id = %user_id))]
     pub async fn get_progressive_overload_suggestions(
         pool: &PgPool,
@@ -496,14 +574,7 @@ impl StatisticsService {
     ) -> Result<OverloadSuggestionsResponse, AppError> {
         info!("Calculating progressive overload suggestions");
 
-        // Get user's weight unit (cached with 60s TTL)
-        let settings = if let Some(cached) = crate::cache::get_settings(user_id) {
-            cached
-        } else {
-            let s = SettingsRepository::get_or_create(pool, user_id).await?;
-            crate::cache::set_settings(user_id, s.clone());
-            s
-        };
+        let settings = Self::settings(pool, user_id).await?;
         let large_increment = match settings.weight_unit {
             WeightUnit::Kg => 2.5,
             WeightUnit::Lbs => 5.0,
@@ -652,6 +723,7 @@ impl StatisticsService {
                     max_weight,
                     avg_reps: avg_reps_at_max,
                     all_targets_met,
+                    estimated_1rm: best_estimated_1rm(&sets, settings.one_rep_max_formula),
                 });
             }
 
@@ -662,6 +734,7 @@ impl StatisticsService {
             let last = &session_data[0];
             let current_weight = last.max_weight;
             let current_reps = last.avg_reps;
+            let estimated_1rm = last.estimated_1rm;
 
             let confidence = match session_count {
                 1 => SuggestionConfidence::Low,
@@ -688,6 +761,7 @@ impl StatisticsService {
                             suggested_reps: None,
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Completed all sets at {}{} in last 2 sessions. Try {}{}!",
                                 current_weight, unit_label, suggested, unit_label
@@ -704,6 +778,7 @@ impl StatisticsService {
                             suggested_reps: Some(suggested_reps),
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Good session! Try {} reps at {}{} next time.",
                                 suggested_reps, current_weight, unit_label
@@ -719,6 +794,7 @@ impl StatisticsService {
                             suggested_reps: None,
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Keep working at {}{} x {} reps.",
                                 current_weight, unit_label, current_reps
@@ -738,6 +814,7 @@ impl StatisticsService {
                             suggested_reps: Some(suggested_reps),
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Isolation exercise — build reps before adding weight. Try {} reps at {}{}.",
                                 suggested_reps, current_weight, unit_label
@@ -754,6 +831,7 @@ impl StatisticsService {
                             suggested_reps: None,
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Hit {} reps consistently — time for a small weight jump. Try {}{}!",
                                 current_reps, suggested, unit_label
@@ -769,6 +847,7 @@ impl StatisticsService {
                             suggested_reps: None,
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Good session at {}{} x {} reps. Build more consistency before progressing.",
                                 current_weight, unit_label, current_reps
@@ -784,6 +863,7 @@ impl StatisticsService {
                             suggested_reps: None,
                             current_weight,
                             current_reps,
+                            estimated_1rm,
                             reason: format!(
                                 "Keep working at {}{} x {} reps.",
                                 current_weight, unit_label, current_reps
@@ -802,6 +882,7 @@ impl StatisticsService {
                     suggested_reps: None,
                     current_weight,
                     current_reps,
+                    estimated_1rm,
                     reason: "Need more workout data for a suggestion.".to_string(),
                     confidence,
                 }
@@ -821,6 +902,9 @@ impl StatisticsService {
     ) -> Result<PlateauAlertResponse, AppError> {
         info!("Calculating plateau alerts");
 
+        let settings = Self::settings(pool, user_id).await?;
+        let (formula, tz) = (settings.one_rep_max_formula, settings.tz());
+
         let three_weeks_ago = Utc::now() - Duration::weeks(3);
 
         // Get all exercises the user has completed
@@ -906,61 +990,66 @@ impl StatisticsService {
                 continue;
             }
 
-            // Get max weight from recent sessions (using pre-fetched sets)
-            let mut recent_max: f64 = 0.0;
-            for session in &recent_sessions {
+            // Best weight and e1RM of each period (using pre-fetched sets)
+            let session_bests = |session: &ExerciseHistoryRow| {
                 let sets = all_sets_map
                     .get(&session.workout_exercise_id)
-                    .cloned()
+                    .map(Vec::as_slice)
                     .unwrap_or_default();
-
-                let session_max = sets
+                let max_weight = sets
                     .iter()
                     .filter_map(|s| s.actual_weight)
                     .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
                     .unwrap_or(0.0);
-                if session_max > recent_max {
-                    recent_max = session_max;
+                (max_weight, best_estimated_1rm(sets, formula))
+            };
+
+            let mut recent_max: f64 = 0.0;
+            let mut recent_e1rm: Option<f64> = None;
+            for session in &recent_sessions {
+                let (session_max, session_e1rm) = session_bests(session);
+                recent_max = recent_max.max(session_max);
+                if session_e1rm > recent_e1rm {
+                    recent_e1rm = session_e1rm;
                 }
             }
 
-            // Get max weight and date from older sessions (using pre-fetched sets)
+            // Older bests, and when each was set
             let mut older_max: f64 = 0.0;
             let mut older_max_date: Option<NaiveDate> = None;
+            let mut older_e1rm: Option<f64> = None;
+            let mut older_e1rm_date: Option<NaiveDate> = None;
             for session in &older_sessions {
-                let sets = all_sets_map
-                    .get(&session.workout_exercise_id)
-                    .cloned()
-                    .unwrap_or_default();
-
-                let session_max = sets
-                    .iter()
-                    .filter_map(|s| s.actual_weight)
-                    .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-                    .unwrap_or(0.0);
+                let (session_max, session_e1rm) = session_bests(session);
                 if session_max > older_max {
                     older_max = session_max;
-                    older_max_date = Some(session.date.date_naive());
+                    older_max_date = Some(session.date.with_timezone(&tz).date_naive());
+                }
+                if session_e1rm > older_e1rm {
+                    older_e1rm = session_e1rm;
+                    older_e1rm_date = Some(session.date.with_timezone(&tz).date_naive());
                 }
             }
 
-            // Plateau: recent max hasn't exceeded older max
-            if recent_max <= older_max && older_max > 0.0 {
-                let weeks_since = older_max_date
+            // Judge on e1RM when both periods have one, else on weight
+            let (plateaued, last_progress_date) = match (recent_e1rm, older_e1rm) {
+                (Some(recent), Some(older)) => (recent <= older, older_e1rm_date),
+                _ => (recent_max <= older_max && older_max > 0.0, older_max_date),
+            };
+
+            // Plateau: recent best hasn't exceeded older best
+            if plateaued {
+                let weeks_since = last_progress_date
                     .map(|d| {
-                        let days = (Utc::now().date_naive() - d).num_days();
+                        let days = (settings.today() - d).num_days();
                         (days / 7).max(3) as i32
                     })
                     .unwrap_or(3);
 
                 let suggestion = if weeks_since >= 6 {
-                    format!(
-                        "Consider a deload week or try a variation of this exercise."
-                    )
+                    "Consider a deload week or try a variation of this exercise.".to_string()
                 } else {
-                    format!(
-                        "Try adjusting rep ranges, adding pause reps, or changing tempo."
-                    )
+                    "Try adjusting rep ranges, adding pause reps, or changing tempo.".to_string()
                 };
 
                 alerts.push(ExercisePlateauAlert {
@@ -969,7 +1058,9 @@ impl StatisticsService {
                     weeks_since_progress: weeks_since,
                     last_max_weight: older_max,
                     current_max_weight: recent_max,
-                    last_progress_date: older_max_date,
+                    last_estimated_1rm: older_e1rm,
+                    current_estimated_1rm: recent_e1rm,
+                    last_progress_date,
                     suggestion,
                 });
             }
@@ -991,7 +1082,8 @@ impl StatisticsService {
 
         let rows = sqlx::query_as::<_, SetRowWithExerciseId>(
             r#"
-            SELECT workout_exercise_id, set_number, actual_reps, actual_weight, is_warmup
+            SELECT workout_exercise_id, set_number, actual_reps, actual_weight, is_warmup, rpe,
+                   rest_seconds_actual, set_type, parent_set_id IS NOT NULL AS is_sub_set, volume_reps
             FROM workout_sets
             WHERE workout_exercise_id = ANY($1) AND is_completed = true
             ORDER BY workout_exercise_id, set_number
@@ -1009,13 +1101,19 @@ impl StatisticsService {
                 actual_reps: row.actual_reps,
                 actual_weight: row.actual_weight,
                 is_warmup: row.is_warmup,
+                rpe: row.rpe,
+                rest_seconds: row.rest_seconds_actual,
+                set_type: row.set_type,
+                is_sub_set: row.is_sub_set,
+                volume_reps: row.volume_reps,
             });
         }
 
         Ok(map)
     }
 
-    /// Batch-fetch completed working (non-warmup) sets for multiple workout_exercise_ids.
+    /// Batch-fetch completed working (non-warmup) main sets for multiple
+    /// workout_exercise_ids; drops and mini-sets are left out.
     /// Used by overload suggestions and plateau alerts.
     async fn batch_fetch_working_sets(
         pool: &PgPool,
@@ -1027,11 +1125,13 @@ impl StatisticsService {
 
         let rows = sqlx::query_as::<_, SetRowWithExerciseId>(
             r#"
-            SELECT workout_exercise_id, set_number, actual_reps, actual_weight, is_warmup
+            SELECT workout_exercise_id, set_number, actual_reps, actual_weight, is_warmup, rpe,
+                   rest_seconds_actual, set_type, parent_set_id IS NOT NULL AS is_sub_set, volume_reps
             FROM workout_sets
             WHERE workout_exercise_id = ANY($1)
                 AND is_completed = true
                 AND is_warmup = false
+                AND parent_set_id IS NULL
             ORDER BY workout_exercise_id, set_number
             "#,
         )
@@ -1047,6 +1147,11 @@ impl StatisticsService {
                 actual_reps: row.actual_reps,
                 actual_weight: row.actual_weight,
                 is_warmup: row.is_warmup,
+                rpe: row.rpe,
+                rest_seconds: row.rest_seconds_actual,
+                set_type: row.set_type,
+                is_sub_set: row.is_sub_set,
+                volume_reps: row.volume_reps,
             });
         }
 
@@ -1081,12 +1186,32 @@ struct SetRow {
     actual_reps: Option<i32>,
     actual_weight: Option<f64>,
     is_warmup: bool,
+    rpe: Option<i16>,
+    rest_seconds: Option<i32>,
+    set_type: SetType,
+    is_sub_set: bool,
+    /// Both sides of a unilateral set
+    volume_reps: Option<i32>,
 }
 
 struct SessionAnalysis {
     max_weight: f64,
     avg_reps: i32,
     all_targets_met: bool,
+    estimated_1rm: Option<f64>,
+}
+
+/// Best estimated 1RM among `sets`, by the user's formula and each set's RPE
+fn best_estimated_1rm<'a>(
+    sets: impl IntoIterator<Item = &'a SetRow>,
+    formula: OneRepMaxFormula,
+) -> Option<f64> {
+    sets.into_iter()
+        .filter_map(|s| match (s.actual_weight, s.actual_reps) {
+            (Some(w), Some(r)) => one_rep_max::estimate_set(formula, w, r, s.rpe),
+            _ => None,
+        })
+        .max_by(|a, b| a.total_cmp(b))
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1096,6 +1221,12 @@ struct SetRowWithExerciseId {
     actual_reps: Option<i32>,
     actual_weight: Option<f64>,
     is_warmup: bool,
+    rpe: Option<i16>,
+    rest_seconds_actual: Option<i32>,
+    set_type: SetType,
+    is_sub_set: bool,
+    /// Both sides of a unilateral set
+    volume_reps: Option<i32>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -1109,6 +1240,8 @@ struct ExerciseHistoryRowWithTemplate {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Datelike;
+    use crate::models::WeekStart;
 
     // ==================== Streak Calculation Tests ====================
 
@@ -1283,46 +1416,6 @@ mod tests {
         assert!((percentage - 50.0).abs() < 0.01);
     }
 
-    // ==================== Estimated 1RM Tests ====================
-
-    #[test]
-    fn test_estimate_1rm_single_rep() {
-        let e1rm = estimate_1rm(100.0, 1).unwrap();
-        assert!((e1rm - 100.0).abs() < 0.01);
-    }
-
-    #[test]
-    fn test_estimate_1rm_five_reps() {
-        let e1rm = estimate_1rm(100.0, 5).unwrap();
-        let expected = 100.0 * (36.0 / 32.0);
-        assert!((e1rm - expected).abs() < 0.01);
-    }
-
-    #[test]
-    fn test_estimate_1rm_ten_reps() {
-        let e1rm = estimate_1rm(100.0, 10).unwrap();
-        let expected = 100.0 * (36.0 / 27.0);
-        assert!((e1rm - expected).abs() < 0.01);
-    }
-
-    #[test]
-    fn test_estimate_1rm_zero_reps() {
-        let result = estimate_1rm(100.0, 0);
-        assert!(result.is_none());
-    }
-
-    #[test]
-    fn test_estimate_1rm_over_twelve() {
-        let result = estimate_1rm(100.0, 15);
-        assert!(result.is_none());
-    }
-
-    #[test]
-    fn test_estimate_1rm_negative_reps() {
-        let result = estimate_1rm(100.0, -1);
-        assert!(result.is_none());
-    }
-
     // ==================== Model Serialization Tests ====================
 
     #[test]
@@ -1349,6 +1442,42 @@ mod tests {
 
     // ==================== Week Calculation Tests ====================
 
+    #[test]
+    fn test_week_of_respects_week_start() {
+        let wednesday = NaiveDate::from_ymd_opt(2024, 1, 17).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 21).unwrap();
+
+        assert_eq!(
+            WeekStart::Monday.week_of(wednesday),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        assert_eq!(
+            WeekStart::Sunday.week_of(wednesday),
+            NaiveDate::from_ymd_opt(2024, 1, 14).unwrap()
+        );
+        // A Sunday starts its own week, or ends the Monday-based one
+        assert_eq!(WeekStart::Sunday.week_of(sunday), sunday);
+        assert_eq!(
+            WeekStart::Monday.week_of(sunday),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_timezone_falls_back_to_utc() {
+        let settings = UserSettings {
+            timezone: "Mars/Olympus_Mons".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(settings.tz(), chrono_tz::Tz::UTC);
+
+        let settings = UserSettings {
+            timezone: "America/Los_Angeles".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(settings.tz(), chrono_tz::America::Los_Angeles);
+    }
+
     #[test]
     fn test_week_start_calculation_monday() {
         let monday = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(); // This is a Monday
@@ -0,0 +1,156 @@
+//! RFC 6238 time-based one-time passwords (HMAC-SHA1, 6 digits, 30 second steps),
+//! the parameters every mainstream authenticator app expects.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Accept codes from one step either side to tolerate clock drift
+const SKEW_STEPS: i64 = 1;
+const ALPHABET: Alphabet = Alphabet::Rfc4648 { padding: false };
+
+/// New 160-bit shared secret, base32 encoded as authenticator apps expect.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    OsRng.fill_bytes(&mut bytes);
+    base32::encode(ALPHABET, &bytes)
+}
+
+/// `otpauth://` URI for QR codes, per the Google Authenticator key URI format.
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        issuer,
+        percent_encode(account),
+        secret,
+        issuer,
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+pub fn time_step(unix_seconds: i64) -> i64 {
+    unix_seconds.div_euclid(STEP_SECONDS)
+}
+
+/// HOTP value (RFC 4226) for a raw key and counter, truncated to `digits`.
+fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    binary % 10u32.pow(digits)
+}
+
+/// Checks `code` against the steps around `unix_seconds` and returns the matching
+/// step, so callers can refuse to accept the same step twice.
+pub fn verify(secret: &str, code: &str, unix_seconds: i64) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let key = base32::decode(ALPHABET, secret)?;
+    let current = time_step(unix_seconds);
+
+    (current - SKEW_STEPS..=current + SKEW_STEPS)
+        .filter(|step| *step >= 0)
+        .find(|step| hotp(&key, *step as u64, DIGITS) == code)
+}
+
+/// Current code for `secret`, as an authenticator app would display it.
+pub fn code_at(secret: &str, unix_seconds: i64) -> Option<String> {
+    let key = base32::decode(ALPHABET, secret)?;
+    let code = hotp(&key, time_step(unix_seconds) as u64, DIGITS);
+    Some(format!("{:0width$}", code, width = DIGITS as usize))
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 Appendix B reference key for SHA1
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_hotp_matches_rfc6238_vectors() {
+        let vectors = [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1111111111, 14050471),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+            (20000000000, 65353130),
+        ];
+
+        for (time, expected) in vectors {
+            assert_eq!(hotp(RFC_KEY, time_step(time) as u64, 8), expected, "T={}", time);
+        }
+    }
+
+    #[test]
+    fn test_verify_accepts_adjacent_steps_only() {
+        let secret = base32::encode(ALPHABET, RFC_KEY);
+        let now = 1111111111;
+        let code = |t: i64| code_at(&secret, t).unwrap();
+
+        assert_eq!(verify(&secret, &code(now), now), Some(time_step(now)));
+        assert_eq!(verify(&secret, &code(now - 30), now), Some(time_step(now) - 1));
+        assert_eq!(verify(&secret, &code(now + 30), now), Some(time_step(now) + 1));
+        assert_eq!(verify(&secret, &code(now - 90), now), None);
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_codes() {
+        let secret = generate_secret();
+
+        assert_eq!(verify(&secret, "", 0), None);
+        assert_eq!(verify(&secret, "12345", 0), None);
+        assert_eq!(verify(&secret, "12a456", 0), None);
+        assert_eq!(verify("not base32!", "123456", 0), None);
+    }
+
+    #[test]
+    fn test_generate_secret_is_base32() {
+        let secret = generate_secret();
+
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32::decode(ALPHABET, &secret).unwrap().len(), 20);
+        assert_ne!(secret, generate_secret());
+    }
+
+    #[test]
+    fn test_otpauth_uri_encodes_labels() {
+        let uri = otpauth_uri("Fitness Tracker", "a+b@example.com", "JBSWY3DPEHPK3PXP");
+
+        assert_eq!(
+            uri,
+            "otpauth://totp/Fitness%20Tracker:a%2Bb%40example.com?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Fitness%20Tracker&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -1,6 +1,12 @@
 mod common;
 
-use common::{body_json, TestApp};
+use common::{body_json, register_and_login, TestApp};
+use fitness_tracker_api::config::UnverifiedAccess;
+use fitness_tracker_api::repositories::{
+    EmailVerificationTokenRepository, PasswordResetTokenRepository, UserRepository,
+};
+use fitness_tracker_api::services::AuthService;
+use fitness_tracker_api::totp;
 
 #[tokio::test]
 async fn test_register_success() {
@@ -90,3 +96,810 @@ async fn test_me_unauthenticated() {
     let resp = app.get("/api/v1/auth/me").await;
     assert_eq!(resp.status(), 401);
 }
+
+#[tokio::test]
+async fn test_refresh_rotates_token() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "frank@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let refresh_token = body_json(resp).await["refreshToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": refresh_token }),
+        )
+        .await;
+
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert!(body["accessToken"].is_string());
+    assert_ne!(body["refreshToken"].as_str().unwrap(), refresh_token);
+}
+
+#[tokio::test]
+async fn test_refresh_token_reuse_revokes_family() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "gina@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let original = body_json(resp).await["refreshToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": original }),
+        )
+        .await;
+    let rotated = body_json(resp).await["refreshToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Replaying the original token is rejected...
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": original }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    // ...and takes the legitimately rotated token down with it
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": rotated }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_logout_revokes_refresh_token() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "hank@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let access_token = body["accessToken"].as_str().unwrap().to_string();
+    let refresh_token = body["refreshToken"].as_str().unwrap().to_string();
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/logout",
+            &access_token,
+            serde_json::json!({ "refreshToken": refresh_token }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": refresh_token }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_change_password_revokes_other_sessions() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "iris@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let access_token = body["accessToken"].as_str().unwrap().to_string();
+    let own_refresh = body["refreshToken"].as_str().unwrap().to_string();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "iris@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let other_refresh = body["refreshToken"].as_str().unwrap().to_string();
+
+    let resp = app
+        .patch_auth(
+            "/api/v1/auth/password",
+            &access_token,
+            serde_json::json!({ "currentPassword": "secret1234", "newPassword": "newsecret5678" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": other_refresh }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    // The session that changed the password stays signed in
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": own_refresh }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_list_sessions_marks_current() {
+    let app = TestApp::new().await;
+    app.post(
+        "/api/v1/auth/register",
+        serde_json::json!({ "email": "jack@example.com", "password": "secret1234" }),
+    )
+    .await;
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({
+                "email": "jack@example.com",
+                "password": "secret1234",
+                "deviceLabel": "Gym kiosk"
+            }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let access_token = body["accessToken"].as_str().unwrap().to_string();
+
+    let resp = app.get_auth("/api/v1/auth/sessions", &access_token).await;
+    assert_eq!(resp.status(), 200);
+
+    let body = body_json(resp).await;
+    let sessions = body["sessions"].as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+
+    let current: Vec<_> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["deviceLabel"], "Gym kiosk");
+}
+
+#[tokio::test]
+async fn test_revoked_session_rejects_access_token() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "kate@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let phone_token = body["accessToken"].as_str().unwrap().to_string();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "kate@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let laptop_token = body["accessToken"].as_str().unwrap().to_string();
+
+    let resp = app.get_auth("/api/v1/auth/sessions", &laptop_token).await;
+    let body = body_json(resp).await;
+    let phone_session = body["sessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["current"] == false)
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app
+        .delete_auth(&format!("/api/v1/auth/sessions/{}", phone_session), &laptop_token)
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app.get_auth("/api/v1/auth/me", &phone_token).await;
+    assert_eq!(resp.status(), 401);
+
+    let resp = app.get_auth("/api/v1/auth/me", &laptop_token).await;
+    assert_eq!(resp.status(), 200);
+
+    // Already revoked
+    let resp = app
+        .delete_auth(&format!("/api/v1/auth/sessions/{}", phone_session), &laptop_token)
+        .await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_cannot_revoke_another_users_session() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "nina@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let victim_token = body["accessToken"].as_str().unwrap().to_string();
+    let refresh_token = body["refreshToken"].as_str().unwrap().to_string();
+    let attacker_token = register_and_login(&app, "otto@example.com", "secret1234").await;
+
+    let resp = app.get_auth("/api/v1/auth/sessions", &victim_token).await;
+    let victim_session = body_json(resp).await["sessions"][0]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app
+        .delete_auth(
+            &format!("/api/v1/auth/sessions/{}", victim_session),
+            &attacker_token,
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+
+    // The victim's session and refresh token are untouched
+    let resp = app.get_auth("/api/v1/auth/me", &victim_token).await;
+    assert_eq!(resp.status(), 200);
+    let resp = app
+        .post(
+            "/api/v1/auth/refresh",
+            serde_json::json!({ "refreshToken": refresh_token }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_forgot_password_does_not_reveal_accounts() {
+    let app = TestApp::new().await;
+    app.post(
+        "/api/v1/auth/register",
+        serde_json::json!({ "email": "liam@example.com", "password": "secret1234" }),
+    )
+    .await;
+
+    let resp = app
+        .post(
+            "/api/v1/auth/forgot-password",
+            serde_json::json!({ "email": "liam@example.com" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/forgot-password",
+            serde_json::json!({ "email": "nobody@example.com" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM password_reset_tokens")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn test_reset_password_token_is_single_use() {
+    let app = TestApp::new().await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "mia@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let user_id = uuid::Uuid::parse_str(body["user"]["id"].as_str().unwrap()).unwrap();
+
+    // The raw token only ever exists in the email, so plant a known one
+    let token = AuthService::generate_opaque_token();
+    PasswordResetTokenRepository::create(
+        &app.pool,
+        user_id,
+        &AuthService::hash_token(&token),
+        chrono::Utc::now() + chrono::Duration::minutes(30),
+    )
+    .await
+    .unwrap();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/reset-password",
+            serde_json::json!({ "token": token, "newPassword": "brandnew5678" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/reset-password",
+            serde_json::json!({ "token": token, "newPassword": "another5678" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "mia@example.com", "password": "brandnew5678" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_unverified_account_limited_to_workouts() {
+    let app = TestApp::with_settings(|s| s.auth.unverified_access = UnverifiedAccess::Workouts).await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "nina@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    assert_eq!(body["user"]["emailVerified"], false);
+    let token = body["accessToken"].as_str().unwrap().to_string();
+
+    let resp = app.get_auth("/api/v1/workouts", &token).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = app.get_auth("/api/v1/exercises", &token).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = app.get_auth("/api/v1/body-stats/measurements", &token).await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = app.get_auth("/api/v1/auth/me", &token).await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_verify_email_lifts_restriction() {
+    let app = TestApp::with_settings(|s| s.auth.unverified_access = UnverifiedAccess::None).await;
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "owen@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let access_token = body["accessToken"].as_str().unwrap().to_string();
+    let user_id = uuid::Uuid::parse_str(body["user"]["id"].as_str().unwrap()).unwrap();
+
+    let resp = app.get_auth("/api/v1/workouts", &access_token).await;
+    assert_eq!(resp.status(), 403);
+
+    // The raw token only ever exists in the email, so plant a known one
+    let token = AuthService::generate_opaque_token();
+    EmailVerificationTokenRepository::create(
+        &app.pool,
+        user_id,
+        &AuthService::hash_token(&token),
+        chrono::Utc::now() + chrono::Duration::hours(1),
+    )
+    .await
+    .unwrap();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/verify-email",
+            serde_json::json!({ "token": token }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app.get_auth("/api/v1/workouts", &access_token).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/verify-email/resend",
+            &access_token,
+            serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status(), 409);
+}
+
+/// Enrolls the user in TOTP and returns the secret and recovery codes.
+async fn enroll_totp(app: &TestApp, token: &str) -> (String, Vec<String>) {
+    let resp = app
+        .post_auth("/api/v1/auth/mfa/totp/setup", token, serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 200);
+    let secret = body_json(resp).await["secret"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let code = totp::code_at(&secret, chrono::Utc::now().timestamp()).unwrap();
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/mfa/totp/confirm",
+            token,
+            serde_json::json!({ "code": code }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let codes = body_json(resp).await["recoveryCodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+
+    (secret, codes)
+}
+
+#[tokio::test]
+async fn test_totp_login_requires_second_factor() {
+    let app = TestApp::new().await;
+    let token = common::register_and_login(&app, "paula@example.com", "secret1234").await;
+    let (secret, recovery_codes) = enroll_totp(&app, &token).await;
+    assert_eq!(recovery_codes.len(), 10);
+
+    let resp = app.get_auth("/api/v1/auth/mfa", &token).await;
+    let body = body_json(resp).await;
+    assert_eq!(body["enabled"], true);
+    assert_eq!(body["recoveryCodesRemaining"], 10);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "paula@example.com", "password": "secret1234" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["mfaRequired"], true);
+    assert!(body["accessToken"].is_null());
+    let mfa_token = body["mfaToken"].as_str().unwrap().to_string();
+
+    // The code used for enrollment belongs to an already consumed time step
+    let replayed = totp::code_at(&secret, chrono::Utc::now().timestamp()).unwrap();
+    let resp = app
+        .post(
+            "/api/v1/auth/mfa/verify",
+            serde_json::json!({ "mfaToken": mfa_token, "code": replayed }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/mfa/verify",
+            serde_json::json!({ "mfaToken": mfa_token, "code": recovery_codes[0] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert!(body["accessToken"].is_string());
+    assert!(body["refreshToken"].is_string());
+}
+
+#[tokio::test]
+async fn test_mfa_token_is_not_an_access_token() {
+    let app = TestApp::new().await;
+    let token = common::register_and_login(&app, "quinn@example.com", "secret1234").await;
+    enroll_totp(&app, &token).await;
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "quinn@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let mfa_token = body_json(resp).await["mfaToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let resp = app.get_auth("/api/v1/auth/me", &mfa_token).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_admin_routes_require_mfa_when_configured() {
+    let app = TestApp::with_settings(|s| s.auth.require_admin_mfa = true).await;
+    let token = common::register_and_login(&app, "rita@example.com", "secret1234").await;
+    sqlx::query("INSERT INTO user_roles (user_id, role) SELECT id, 'admin' FROM users WHERE email = $1")
+        .bind("rita@example.com")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let resp = app.get_auth("/api/v1/admin/metrics", &token).await;
+    assert_eq!(resp.status(), 403);
+
+    enroll_totp(&app, &token).await;
+
+    let resp = app.get_auth("/api/v1/admin/metrics", &token).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/mfa/totp/disable",
+            &token,
+            serde_json::json!({ "password": "secret1234", "code": "000000" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_mfa_optional_for_roles_without_permissions() {
+    let app = TestApp::with_settings(|s| s.auth.require_admin_mfa = true).await;
+    let token = common::register_and_login(&app, "ruth@example.com", "secret1234").await;
+    sqlx::query(
+        "INSERT INTO user_roles (user_id, role) SELECT id, 'coach' FROM users WHERE email = $1",
+    )
+    .bind("ruth@example.com")
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    let (_, recovery_codes) = enroll_totp(&app, &token).await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/mfa/totp/disable",
+            &token,
+            serde_json::json!({ "password": "secret1234", "code": recovery_codes[0] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+}
+
+#[tokio::test]
+async fn test_access_token_limited_to_scopes() {
+    let app = TestApp::new().await;
+    let session = common::register_and_login(&app, "sam@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/tokens",
+            &session,
+            serde_json::json!({ "name": "Dashboard", "scopes": ["workouts:read", "stats:read"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    let pat = body["token"].as_str().unwrap().to_string();
+    let token_id = body["id"].as_str().unwrap().to_string();
+    assert!(pat.starts_with(body["tokenPrefix"].as_str().unwrap()));
+
+    let resp = app.get_auth("/api/v1/workouts", &pat).await;
+    assert_eq!(resp.status(), 200);
+    let resp = app.get_auth("/api/v1/personal-records", &pat).await;
+    assert_eq!(resp.status(), 200);
+    let resp = app.get_auth("/api/v1/auth/me", &pat).await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = app
+        .post_auth("/api/v1/workouts", &pat, serde_json::json!({ "name": "Push" }))
+        .await;
+    assert_eq!(resp.status(), 403);
+    let resp = app.get_auth("/api/v1/body-stats/measurements", &pat).await;
+    assert_eq!(resp.status(), 403);
+    let resp = app.get_auth("/api/v1/auth/tokens", &pat).await;
+    assert_eq!(resp.status(), 403);
+    let resp = app.get_auth("/api/v1/admin/metrics", &pat).await;
+    assert_eq!(resp.status(), 403);
+
+    // The secret itself is never listed again
+    let resp = app.get_auth("/api/v1/auth/tokens", &session).await;
+    let body = body_json(resp).await;
+    assert_eq!(body["tokens"].as_array().unwrap().len(), 1);
+    assert!(body["tokens"][0]["token"].is_null());
+
+    let resp = app
+        .delete_auth(&format!("/api/v1/auth/tokens/{token_id}"), &session)
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app.get_auth("/api/v1/workouts", &pat).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_failed_logins_lock_account_until_admin_unlock() {
+    let app = TestApp::with_settings(|s| s.auth.max_failed_logins = 2).await;
+
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "admin@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let admin_token = body_json(resp).await["accessToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    sqlx::query("INSERT INTO user_roles (user_id, role) SELECT id, 'admin' FROM users WHERE email = 'admin@example.com'")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "tina@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let user_id = body_json(resp).await["user"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let wrong = serde_json::json!({ "email": "tina@example.com", "password": "wrong-password" });
+    let resp = app.post("/api/v1/auth/login", wrong.clone()).await;
+    assert_eq!(resp.status(), 401);
+    let resp = app.post("/api/v1/auth/login", wrong).await;
+    assert_eq!(resp.status(), 429);
+    assert!(resp.headers().contains_key("retry-after"));
+
+    let resp = app
+        .post_auth(
+            &format!("/api/v1/admin/users/{user_id}/unlock"),
+            &admin_token,
+            serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = app
+        .post(
+            "/api/v1/auth/login",
+            serde_json::json!({ "email": "tina@example.com", "password": "secret1234" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_export_and_scheduled_account_deletion() {
+    let app = TestApp::new().await;
+    let token = common::register_and_login(&app, "gdpr@example.com", "secret1234").await;
+
+    let resp = app.get_auth("/api/v1/auth/me/export", &token).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers()["content-disposition"]
+        .to_str()
+        .unwrap()
+        .starts_with("attachment"));
+    let export = body_json(resp).await;
+    assert_eq!(export["user"]["email"], "gdpr@example.com");
+    assert!(export["user"].get("password_hash").is_none());
+    assert!(export["workouts"].is_array());
+
+    let resp = app
+        .delete_auth_json(
+            "/api/v1/auth/me",
+            &token,
+            serde_json::json!({ "password": "wrong-password" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = app
+        .delete_auth_json(
+            "/api/v1/auth/me",
+            &token,
+            serde_json::json!({ "password": "secret1234" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(body_json(resp).await["scheduledDeletionAt"].is_string());
+
+    // Every session is signed out once deletion is scheduled
+    let resp = app.get_auth("/api/v1/auth/me", &token).await;
+    assert_eq!(resp.status(), 401);
+
+    sqlx::query(
+        "UPDATE users SET scheduled_deletion_at = NOW() - INTERVAL '1 minute' WHERE email = 'gdpr@example.com'",
+    )
+    .execute(&app.pool)
+    .await
+    .unwrap();
+    let purged = UserRepository::purge_scheduled_deletions(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(purged, 1);
+    assert!(UserRepository::find_by_email(&app.pool, "gdpr@example.com")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_jwks_is_empty_when_signing_with_shared_secret() {
+    let app = TestApp::new().await;
+
+    let resp = app.get("/.well-known/jwks.json").await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["keys"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_staff_roles_grant_only_their_permissions() {
+    let app = TestApp::new().await;
+    let admin = common::register_and_login(&app, "uma@example.com", "secret1234").await;
+    sqlx::query("INSERT INTO user_roles (user_id, role) SELECT id, 'admin' FROM users WHERE email = 'uma@example.com'")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let resp = app
+        .post(
+            "/api/v1/auth/register",
+            serde_json::json!({ "email": "vic@example.com", "password": "secret1234" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let staff = body["accessToken"].as_str().unwrap().to_string();
+    let staff_id = body["user"]["id"].as_str().unwrap().to_string();
+
+    let resp = app.get_auth("/api/v1/admin/users", &staff).await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = app
+        .put_auth(
+            &format!("/api/v1/admin/users/{staff_id}/roles"),
+            &admin,
+            serde_json::json!({ "roles": ["support"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["roles"], serde_json::json!(["support"]));
+    assert_eq!(body["isAdmin"], false);
+
+    let resp = app.get_auth("/api/v1/admin/users", &staff).await;
+    assert_eq!(resp.status(), 200);
+    let resp = app.get_auth("/api/v1/admin/metrics", &staff).await;
+    assert_eq!(resp.status(), 200);
+    let resp = app
+        .delete_auth(&format!("/api/v1/admin/users/{staff_id}"), &staff)
+        .await;
+    assert_eq!(resp.status(), 403);
+    let resp = app.get_auth("/api/v1/admin/roles", &staff).await;
+    assert_eq!(resp.status(), 403);
+    let resp = app.get_auth("/api/v1/admin/exercises", &staff).await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = app
+        .put_auth(
+            "/api/v1/admin/users/00000000-0000-0000-0000-000000000000/roles",
+            &admin,
+            serde_json::json!({ "roles": ["moderator"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+}
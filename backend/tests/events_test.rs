@@ -0,0 +1,198 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::body::Body;
+use common::{body_json, register_and_login, TestApp};
+use http_body_util::BodyExt;
+
+/// Reads the stream until an event with the given name arrives and returns
+/// its data.
+async fn next_event(body: &mut Body, name: &str) -> serde_json::Value {
+    let mut buffer = String::new();
+    loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await
+            .expect("no event before the timeout")
+            .expect("stream ended")
+            .unwrap();
+        let Ok(chunk) = frame.into_data() else {
+            continue;
+        };
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+
+        while let Some(end) = buffer.find("\n\n") {
+            let message: String = buffer.drain(..end + 2).collect();
+            let mut lines = message.lines();
+            if lines.next() == Some(&format!("event: {name}")) {
+                let data = lines.next().unwrap().trim_start_matches("data: ");
+                return serde_json::from_str(data).unwrap();
+            }
+        }
+    }
+}
+
+/// Reads the stream until it ends, failing if it is still open after the
+/// timeout.
+async fn stream_end(body: &mut Body) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while body.frame().await.is_some() {}
+    })
+    .await
+    .expect("stream still open after the timeout");
+}
+
+#[tokio::test]
+async fn test_stream_sends_own_changes_only() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "rosa@example.com", "secret1234").await;
+    let other = register_and_login(&app, "sam@example.com", "secret1234").await;
+
+    let resp = app.get_auth("/api/v1/events", &token).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/event-stream");
+    let mut stream = resp.into_body();
+
+    // Another user's change is not sent
+    app.post_auth(
+        "/api/v1/workouts",
+        &other,
+        serde_json::json!({ "name": "Not mine" }),
+    )
+    .await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Push Day" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let event = next_event(&mut stream, "workout.created").await;
+    assert_eq!(event["workoutId"], workout_id);
+
+    let resp = app
+        .post_auth(
+            &format!("/api/v1/workouts/{}/exercises", workout_id),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let resp = app
+        .post_auth(
+            &format!(
+                "/api/v1/workouts/{}/exercises/{}/sets",
+                workout_id, exercise_id
+            ),
+            &token,
+            serde_json::json!({ "targetReps": 5, "targetWeight": 100.0 }),
+        )
+        .await;
+    let set_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let event = next_event(&mut stream, "set.created").await;
+    assert_eq!(event["type"], "set");
+    assert_eq!(event["workoutId"], workout_id);
+    assert_eq!(event["exerciseId"], exercise_id);
+    assert_eq!(event["setId"], set_id);
+}
+
+#[tokio::test]
+async fn test_many_personal_records_are_sent_as_a_count() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "tara@example.com", "secret1234").await;
+
+    let resp = app.get_auth("/api/v1/events", &token).await;
+    let mut stream = resp.into_body();
+
+    // A rep max for every rep count on ten exercises: far more record IDs
+    // than would fit in one notification
+    let exercises: Vec<_> = [
+        "ex-bench-press",
+        "ex-cable-fly",
+        "ex-squat",
+        "ex-deadlift",
+        "ex-bent-row",
+        "ex-arnold-press",
+        "ex-cable-lateral-raise",
+        "ex-barbell-curl",
+        "ex-cable-curl",
+        "ex-leg-press",
+    ]
+    .into_iter()
+    .map(|exercise_id| {
+        let sets: Vec<_> = (1..=20)
+            .map(|reps| {
+                serde_json::json!({ "actualReps": reps, "actualWeight": 50.0, "isCompleted": true })
+            })
+            .collect();
+        serde_json::json!({ "exerciseTemplateId": exercise_id, "sets": sets })
+    })
+    .collect();
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts/import",
+            &token,
+            serde_json::json!({
+                "name": "Rep ladder",
+                "startedAt": "2024-03-01T17:00:00Z",
+                "completedAt": "2024-03-01T19:00:00Z",
+                "exercises": exercises,
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    assert_eq!(app.run_jobs().await, 1);
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let records = body_json(resp).await["records"].as_array().unwrap().len();
+    assert!(records >= 250);
+
+    let event = next_event(&mut stream, "personal-records.detected").await;
+    assert_eq!(event["workoutId"], workout_id);
+    assert_eq!(event["recordCount"], records);
+}
+
+#[tokio::test]
+async fn test_stream_ends_when_credentials_are_revoked() {
+    let app = TestApp::new().await;
+    let session = register_and_login(&app, "uri@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/auth/tokens",
+            &session,
+            serde_json::json!({ "name": "Watch", "scopes": ["workouts:read"] }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    let pat = body["token"].as_str().unwrap().to_string();
+    let token_id = body["id"].as_str().unwrap().to_string();
+
+    let mut session_stream = app.get_auth("/api/v1/events", &session).await.into_body();
+    let mut token_stream = app.get_auth("/api/v1/events", &pat).await.into_body();
+
+    let resp = app
+        .delete_auth(&format!("/api/v1/auth/tokens/{token_id}"), &session)
+        .await;
+    assert_eq!(resp.status(), 204);
+    stream_end(&mut token_stream).await;
+
+    // The session's stream is still open and delivering events
+    app.post_auth(
+        "/api/v1/workouts",
+        &session,
+        serde_json::json!({ "name": "Leg Day" }),
+    )
+    .await;
+    next_event(&mut session_stream, "workout.created").await;
+
+    let resp = app
+        .post_auth("/api/v1/auth/logout-all", &session, serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 204);
+    stream_end(&mut session_stream).await;
+}
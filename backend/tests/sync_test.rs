@@ -0,0 +1,210 @@
+mod common;
+
+use common::{body_json, register_and_login, TestApp};
+use fitness_tracker_api::cursor::encode_sync_token;
+
+async fn start_exercise(app: &TestApp, token: &str) -> (String, String) {
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            token,
+            serde_json::json!({ "name": "Session" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let resp = app
+        .post_auth(
+            &format!("/api/v1/workouts/{}/exercises", workout_id),
+            token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    (workout_id, exercise_id)
+}
+
+async fn set_count(app: &TestApp) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM workout_sets")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_retried_create_with_client_id_is_not_duplicated() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "olive@example.com", "secret1234").await;
+    let (workout_id, exercise_id) = start_exercise(&app, &token).await;
+
+    let set_id = uuid::Uuid::new_v4();
+    let path = format!(
+        "/api/v1/workouts/{}/exercises/{}/sets",
+        workout_id, exercise_id
+    );
+    for _ in 0..2 {
+        let resp = app
+            .post_auth(&path, &token, serde_json::json!({ "id": set_id }))
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(body_json(resp).await["id"], set_id.to_string());
+    }
+    assert_eq!(set_count(&app).await, 1);
+
+    // Another user can't claim the same ID
+    let other = register_and_login(&app, "pete@example.com", "secret1234").await;
+    let (workout_id, exercise_id) = start_exercise(&app, &other).await;
+    let resp = app
+        .post_auth(
+            &format!(
+                "/api/v1/workouts/{}/exercises/{}/sets",
+                workout_id, exercise_id
+            ),
+            &other,
+            serde_json::json!({ "id": set_id }),
+        )
+        .await;
+    assert_eq!(resp.status(), 409);
+}
+
+#[tokio::test]
+async fn test_idempotency_key_replays_first_response() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "quinn@example.com", "secret1234").await;
+    let (workout_id, exercise_id) = start_exercise(&app, &token).await;
+    let path = format!(
+        "/api/v1/workouts/{}/exercises/{}/sets",
+        workout_id, exercise_id
+    );
+
+    let resp = app
+        .post_auth_with_key(
+            &path,
+            &token,
+            "key-1",
+            serde_json::json!({ "targetReps": 5 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let first = body_json(resp).await;
+
+    let resp = app
+        .post_auth_with_key(
+            &path,
+            &token,
+            "key-1",
+            serde_json::json!({ "targetReps": 5 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["idempotent-replayed"], "true");
+    assert_eq!(body_json(resp).await, first);
+    assert_eq!(set_count(&app).await, 1);
+
+    // Reusing the key for a different request is refused
+    let resp = app
+        .post_auth_with_key(
+            &path,
+            &token,
+            "key-1",
+            serde_json::json!({ "targetReps": 8 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+
+    // Failed requests don't keep their key
+    let missing = format!(
+        "/api/v1/workouts/{}/exercises/{}/sets",
+        workout_id,
+        uuid::Uuid::new_v4()
+    );
+    let resp = app
+        .post_auth_with_key(&missing, &token, "key-2", serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 404);
+    let resp = app
+        .post_auth_with_key(&path, &token, "key-2", serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(set_count(&app).await, 2);
+}
+
+#[tokio::test]
+async fn test_sync_returns_snapshot_then_changes() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "rosa@example.com", "secret1234").await;
+
+    // Left out of the delta
+    start_exercise(&app, &token).await;
+    let (changed_id, exercise_id) = start_exercise(&app, &token).await;
+    let (deleted_id, _) = start_exercise(&app, &token).await;
+    let resp = app
+        .post_auth(
+            "/api/v1/body-stats/measurements",
+            &token,
+            serde_json::json!({ "date": "2024-03-01", "weight": 80.0 }),
+        )
+        .await;
+    let measurement_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let resp = app.get_auth("/api/v1/sync", &token).await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["full"], true);
+    assert_eq!(body["workouts"].as_array().unwrap().len(), 3);
+    assert_eq!(body["measurements"].as_array().unwrap().len(), 1);
+    assert!(body["settings"].is_object());
+
+    let since = sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>("SELECT NOW()")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+
+    // A new set bumps its workout
+    app.post_auth(
+        &format!(
+            "/api/v1/workouts/{}/exercises/{}/sets",
+            changed_id, exercise_id
+        ),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+    app.delete_auth(&format!("/api/v1/workouts/{}", deleted_id), &token)
+        .await;
+    app.delete_auth(
+        &format!("/api/v1/body-stats/measurements/{}", measurement_id),
+        &token,
+    )
+    .await;
+
+    let resp = app
+        .get_auth(
+            &format!("/api/v1/sync?since={}", encode_sync_token(since)),
+            &token,
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["full"], false);
+    let workouts = body["workouts"].as_array().unwrap();
+    assert_eq!(workouts.len(), 1);
+    assert_eq!(workouts[0]["id"], changed_id);
+    assert_eq!(
+        workouts[0]["exercises"][0]["sets"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(body["deleted"]["workouts"], serde_json::json!([deleted_id]));
+    assert_eq!(
+        body["deleted"]["measurements"],
+        serde_json::json!([measurement_id])
+    );
+    assert!(body["settings"].is_null());
+
+    let resp = app.get_auth("/api/v1/sync?since=garbage", &token).await;
+    assert_eq!(resp.status(), 400);
+}
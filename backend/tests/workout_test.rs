@@ -108,3 +108,1203 @@ async fn test_restore_workout() {
     let list_body = body_json(list_resp).await;
     assert_eq!(list_body["total"], 1);
 }
+
+/// Starts a workout with one exercise, logs the given (weight, reps) sets as
+/// completed and finishes it. Returns the workout ID.
+async fn log_completed_workout(
+    app: &TestApp,
+    token: &str,
+    exercise_template_id: &str,
+    sets: &[(f64, i32)],
+) -> String {
+    let sets: Vec<serde_json::Value> = sets
+        .iter()
+        .map(|(weight, reps)| serde_json::json!({ "actualWeight": weight, "actualReps": reps }))
+        .collect();
+    log_completed_sets(app, token, exercise_template_id, &sets).await
+}
+
+/// Logs and completes a workout with one exercise, applying each update to a new set.
+async fn log_completed_sets(
+    app: &TestApp,
+    token: &str,
+    exercise_template_id: &str,
+    sets: &[serde_json::Value],
+) -> String {
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            token,
+            serde_json::json!({ "name": "Session" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let resp = app
+        .post_auth(
+            &format!("/api/v1/workouts/{}/exercises", workout_id),
+            token,
+            serde_json::json!({ "exerciseTemplateId": exercise_template_id }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    for set in sets {
+        let resp = app
+            .post_auth(
+                &format!("/api/v1/workouts/{}/exercises/{}/sets", workout_id, exercise_id),
+                token,
+                serde_json::json!({}),
+            )
+            .await;
+        let set_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+        let mut update = set.clone();
+        update["isCompleted"] = serde_json::json!(true);
+        let resp = app
+            .patch_auth(
+                &format!(
+                    "/api/v1/workouts/{}/exercises/{}/sets/{}",
+                    workout_id, exercise_id, set_id
+                ),
+                token,
+                update,
+            )
+            .await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    let resp = app
+        .post_auth(
+            &format!("/api/v1/workouts/{}/complete", workout_id),
+            token,
+            serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    workout_id
+}
+
+#[tokio::test]
+async fn test_completing_workout_queues_pr_detection() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "jack@example.com", "secret1234").await;
+
+    let workout_id = log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+
+    // Completing again must not queue a second job
+    app.post_auth(
+        &format!("/api/v1/workouts/{}/complete", workout_id),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE kind = 'detect_personal_records'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(queued, 1);
+
+    assert_eq!(app.run_jobs().await, 1);
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let body = body_json(resp).await;
+    let records = body["records"].as_array().unwrap();
+    assert!(records.iter().any(|r| r["type"] == "max-weight" && r["value"] == 100.0));
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn test_undecodable_job_is_dead_lettered() {
+    let app = TestApp::new().await;
+
+    sqlx::query("INSERT INTO jobs (kind, payload) VALUES ('retired_job', '{}')")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(app.run_jobs().await, 1);
+
+    let (status, error): (String, Option<String>) =
+        sqlx::query_as("SELECT status, last_error FROM jobs")
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "dead");
+    assert!(error.unwrap().contains("retired_job"));
+}
+
+#[tokio::test]
+async fn test_lost_job_on_final_attempt_is_dead_lettered() {
+    let app = TestApp::new().await;
+    let max_attempts = app.settings.jobs.max_attempts;
+
+    sqlx::query(
+        r#"
+        INSERT INTO jobs (kind, payload, status, attempts, locked_at)
+        VALUES ('retired_job', '{"n": 1}', 'running', $1, NOW() - INTERVAL '1 day'),
+               ('retired_job', '{"n": 2}', 'running', 1, NOW() - INTERVAL '1 day'),
+               ('retired_job', '{"n": 3}', 'running', 1, NOW())
+        "#,
+    )
+    .bind(max_attempts)
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    // Only the lost job with attempts left is handed out again
+    assert_eq!(app.run_jobs().await, 1);
+
+    let jobs: Vec<(String, i32, Option<String>)> =
+        sqlx::query_as("SELECT status, attempts, last_error FROM jobs ORDER BY id")
+            .fetch_all(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(jobs[0].0, "dead");
+    assert_eq!(jobs[0].1, max_attempts);
+    assert!(jobs[0].2.as_deref().unwrap().contains("lost"));
+    assert_eq!(jobs[1].0, "dead");
+    assert_eq!(jobs[1].1, 2);
+    assert_eq!(jobs[2].0, "running");
+}
+
+#[tokio::test]
+async fn test_failing_job_is_retried_without_blocking_the_queue() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "zoe@example.com", "secret1234").await;
+
+    // Settings can't be created for a user that doesn't exist, so this job
+    // fails on every attempt
+    sqlx::query(
+        r#"
+        INSERT INTO jobs (kind, payload)
+        VALUES ('recompute_personal_records', jsonb_build_object('user_id', $1::uuid, 'exercise_template_id', 'ex-bench-press'))
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4())
+    .execute(&app.pool)
+    .await
+    .unwrap();
+    log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+
+    assert_eq!(app.run_jobs().await, 2);
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let body = body_json(resp).await;
+    assert!(!body["records"].as_array().unwrap().is_empty());
+
+    let (status, attempts, error): (String, i32, Option<String>) =
+        sqlx::query_as("SELECT status, attempts, last_error FROM jobs")
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(attempts, 1);
+    assert!(error.is_some());
+
+    // Skip the backoff until the attempts run out
+    for _ in 1..app.settings.jobs.max_attempts {
+        sqlx::query("UPDATE jobs SET run_at = NOW()")
+            .execute(&app.pool)
+            .await
+            .unwrap();
+        assert_eq!(app.run_jobs().await, 1);
+    }
+
+    let status: String = sqlx::query_scalar("SELECT status FROM jobs")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "dead");
+}
+
+#[tokio::test]
+async fn test_volume_records_detected() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "kate@example.com", "secret1234").await;
+
+    log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5), (80.0, 10)]).await;
+    // Heavier top set, but less volume than either existing record
+    log_completed_workout(&app, &token, "ex-bench-press", &[(110.0, 3)]).await;
+    app.run_jobs().await;
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let body = body_json(resp).await;
+    let records = body["records"].as_array().unwrap();
+    let of_type = |t: &str| -> Vec<&serde_json::Value> {
+        records.iter().filter(|r| r["type"] == t).collect()
+    };
+
+    let set_volume = of_type("max-volume");
+    assert_eq!(set_volume.len(), 1);
+    assert_eq!(set_volume[0]["value"], 800.0);
+    assert_eq!(set_volume[0]["reps"], 10);
+
+    let session_volume = of_type("max-session-volume");
+    assert_eq!(session_volume.len(), 1);
+    assert_eq!(session_volume[0]["value"], 1300.0);
+    assert_eq!(session_volume[0]["reps"], 15);
+
+    assert_eq!(of_type("max-weight").len(), 2);
+}
+
+fn max_weight_values(body: &serde_json::Value) -> Vec<f64> {
+    body["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["type"] == "max-weight")
+        .map(|r| r["value"].as_f64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_editing_completed_workout_recomputes_records() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "liam@example.com", "secret1234").await;
+
+    let workout_id = log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+    app.run_jobs().await;
+
+    let resp = app
+        .get_auth(&format!("/api/v1/workouts/{}", workout_id), &token)
+        .await;
+    let workout = body_json(resp).await;
+    let exercise = &workout["exercises"][0];
+    let set_path = format!(
+        "/api/v1/workouts/{}/exercises/{}/sets/{}",
+        workout_id,
+        exercise["id"].as_str().unwrap(),
+        exercise["sets"][0]["id"].as_str().unwrap()
+    );
+
+    // Typo fixed after the fact: the record follows the corrected weight
+    let resp = app
+        .patch_auth(&set_path, &token, serde_json::json!({ "actualWeight": 90.0 }))
+        .await;
+    assert_eq!(resp.status(), 200);
+    app.run_jobs().await;
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    assert_eq!(max_weight_values(&body_json(resp).await), vec![90.0]);
+
+    let resp = app
+        .get_auth(&format!("/api/v1/workouts/{}", workout_id), &token)
+        .await;
+    assert_eq!(body_json(resp).await["totalVolume"], 450.0);
+
+    app.delete_auth(&format!("/api/v1/workouts/{}", workout_id), &token)
+        .await;
+    app.run_jobs().await;
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    assert!(max_weight_values(&body_json(resp).await).is_empty());
+
+    app.post_auth(
+        &format!("/api/v1/workouts/{}/restore", workout_id),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+    app.run_jobs().await;
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    assert_eq!(max_weight_values(&body_json(resp).await), vec![90.0]);
+}
+
+#[tokio::test]
+async fn test_sets_of_other_users_cannot_be_edited() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "mia@example.com", "secret1234").await;
+    let other = register_and_login(&app, "noah@example.com", "secret1234").await;
+
+    let workout_id = log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+    let resp = app
+        .get_auth(&format!("/api/v1/workouts/{}", workout_id), &token)
+        .await;
+    let exercise = body_json(resp).await["exercises"][0].clone();
+    let exercise_path = format!(
+        "/api/v1/workouts/{}/exercises/{}",
+        workout_id,
+        exercise["id"].as_str().unwrap()
+    );
+    let set_path = format!(
+        "{}/sets/{}",
+        exercise_path,
+        exercise["sets"][0]["id"].as_str().unwrap()
+    );
+
+    let resp = app
+        .patch_auth(&set_path, &other, serde_json::json!({ "actualWeight": 1.0 }))
+        .await;
+    assert_eq!(resp.status(), 404);
+    // Replaying the victim's set id as an idempotent add must not read it back
+    let resp = app
+        .post_auth(
+            &format!("{}/sets", exercise_path),
+            &other,
+            serde_json::json!({ "id": exercise["sets"][0]["id"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+    let resp = app.delete_auth(&set_path, &other).await;
+    assert_eq!(resp.status(), 404);
+    let resp = app
+        .patch_auth_if_match(
+            &exercise_path,
+            &other,
+            "\"0\"",
+            serde_json::json!({ "notes": "mine now" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+    let resp = app.delete_auth(&exercise_path, &other).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_admin_recompute_rebuilds_records() {
+    let app = TestApp::new().await;
+    let admin = register_and_login(&app, "olga@example.com", "secret1234").await;
+    sqlx::query("INSERT INTO user_roles (user_id, role) SELECT id, 'admin' FROM users WHERE email = 'olga@example.com'")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let token = register_and_login(&app, "paul@example.com", "secret1234").await;
+
+    log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+    app.run_jobs().await;
+    sqlx::query("DELETE FROM personal_records")
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    let user_id: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM users WHERE email = 'paul@example.com'")
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    let path = format!("/api/v1/admin/users/{}/personal-records/recompute", user_id);
+
+    let resp = app.post_auth(&path, &token, serde_json::json!({})).await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = app.post_auth(&path, &admin, serde_json::json!({})).await;
+    assert_eq!(resp.status(), 202);
+    assert_eq!(body_json(resp).await["queued"], 1);
+    app.run_jobs().await;
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    assert_eq!(max_weight_values(&body_json(resp).await), vec![100.0]);
+}
+
+#[tokio::test]
+async fn test_rep_max_table() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "quinn@example.com", "secret1234").await;
+
+    let first = log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 3), (80.0, 8)]).await;
+    // Beats the 3RM only; a lighter 8 isn't a record
+    let second = log_completed_workout(&app, &token, "ex-bench-press", &[(105.0, 3), (75.0, 8)]).await;
+    app.run_jobs().await;
+
+    let resp = app
+        .get_auth("/api/v1/statistics/exercises/ex-bench-press/rep-maxes", &token)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    let rep_maxes = body["repMaxes"].as_array().unwrap();
+
+    assert_eq!(rep_maxes.len(), 2);
+    assert_eq!(rep_maxes[0]["reps"], 3);
+    assert_eq!(rep_maxes[0]["weight"], 105.0);
+    assert_eq!(rep_maxes[0]["workoutId"], second);
+    assert_eq!(rep_maxes[1]["reps"], 8);
+    assert_eq!(rep_maxes[1]["weight"], 80.0);
+    assert_eq!(rep_maxes[1]["workoutId"], first);
+
+    let resp = app
+        .get_auth("/api/v1/statistics/exercises/ex-unknown/rep-maxes", &token)
+        .await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_cardio_timed_and_carry_records() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "rosa@example.com", "secret1234").await;
+
+    log_completed_sets(
+        &app,
+        &token,
+        "ex-cycling",
+        &[serde_json::json!({ "distanceMeters": 10000.0, "durationSeconds": 1500 })],
+    )
+    .await;
+    // Shorter and slower: only the 1 km time is new, since the 10 km ride
+    // pro-rates to 2:30 per km
+    log_completed_sets(
+        &app,
+        &token,
+        "ex-cycling",
+        &[serde_json::json!({ "distanceMeters": 2000.0, "durationSeconds": 280 })],
+    )
+    .await;
+    log_completed_sets(
+        &app,
+        &token,
+        "ex-plank",
+        &[serde_json::json!({ "durationSeconds": 95 })],
+    )
+    .await;
+    log_completed_sets(
+        &app,
+        &token,
+        "ex-farmers-walk",
+        &[serde_json::json!({ "actualWeight": 40.0, "distanceMeters": 30.0 })],
+    )
+    .await;
+    app.run_jobs().await;
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let body = body_json(resp).await;
+    let records = body["records"].as_array().unwrap();
+    let values = |t: &str| -> Vec<f64> {
+        let mut values: Vec<f64> = records
+            .iter()
+            .filter(|r| r["type"] == t)
+            .map(|r| r["value"].as_f64().unwrap())
+            .collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values
+    };
+
+    assert_eq!(values("longest-distance"), vec![10000.0]);
+    assert_eq!(values("fastest-10k"), vec![1500.0]);
+    assert_eq!(values("fastest-5k"), vec![750.0]);
+    assert_eq!(values("fastest-1k"), vec![140.0, 150.0]);
+    assert_eq!(values("best-pace"), vec![140.0, 150.0]);
+    assert_eq!(values("longest-hold"), vec![95.0]);
+    assert_eq!(values("heaviest-carry"), vec![40.0]);
+    // Category-aware: no strength records for any of these
+    assert!(values("max-weight").is_empty());
+
+    let carry = records
+        .iter()
+        .find(|r| r["type"] == "heaviest-carry")
+        .unwrap();
+    assert_eq!(carry["distanceMeters"], 30.0);
+}
+
+#[tokio::test]
+async fn test_record_history_and_timeline() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "sam@example.com", "secret1234").await;
+
+    log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+    let second = log_completed_workout(&app, &token, "ex-bench-press", &[(110.0, 3)]).await;
+    app.run_jobs().await;
+
+    let resp = app
+        .get_auth("/api/v1/personal-records?current=true", &token)
+        .await;
+    let body = body_json(resp).await;
+    let current: Vec<&serde_json::Value> = body["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r["type"] == "max-weight")
+        .collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["value"], 110.0);
+    assert_eq!(current[0]["previousValue"], 100.0);
+    assert_eq!(current[0]["delta"], 10.0);
+    assert_eq!(current[0]["isCurrent"], true);
+    assert_eq!(current[0]["workoutId"], second);
+
+    let resp = app
+        .get_auth("/api/v1/personal-records?current=false", &token)
+        .await;
+    let body = body_json(resp).await;
+    let superseded = body["records"].as_array().unwrap();
+    assert!(superseded.iter().all(|r| r["isCurrent"] == false));
+    assert!(superseded
+        .iter()
+        .any(|r| r["type"] == "max-weight" && r["value"] == 100.0));
+
+    let resp = app
+        .get_auth(
+            "/api/v1/statistics/exercises/ex-bench-press/record-timeline",
+            &token,
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    let series = body["series"].as_array().unwrap();
+    let max_weight = series.iter().find(|s| s["type"] == "max-weight").unwrap();
+    let values: Vec<f64> = max_weight["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["value"].as_f64().unwrap())
+        .collect();
+    assert_eq!(values, vec![100.0, 110.0]);
+
+    // Rep maxes are one series per rep count
+    let rep_counts: Vec<i64> = series
+        .iter()
+        .filter(|s| s["type"] == "rep-max")
+        .map(|s| s["reps"].as_i64().unwrap())
+        .collect();
+    assert_eq!(rep_counts, vec![5, 3]);
+}
+
+/// Current estimated 1RM record for bench press.
+async fn current_e1rm(app: &TestApp, token: &str) -> f64 {
+    let resp = app
+        .get_auth("/api/v1/personal-records?current=true", token)
+        .await;
+    let body = body_json(resp).await;
+    body["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["type"] == "estimated-1rm")
+        .unwrap()["value"]
+        .as_f64()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_one_rep_max_formula_setting_rebuilds_records() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "tara@example.com", "secret1234").await;
+
+    log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 10)]).await;
+    app.run_jobs().await;
+    // Brzycki by default: 100 * 36 / 27
+    assert!((current_e1rm(&app, &token).await - 133.33).abs() < 0.01);
+
+    let resp = app
+        .put_auth(
+            "/api/v1/settings",
+            &token,
+            serde_json::json!({ "oneRepMaxFormula": "oconner" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(body_json(resp).await["oneRepMaxFormula"], "oconner");
+    app.run_jobs().await;
+    // O'Conner: 100 * (1 + 0.025 * 10)
+    assert!((current_e1rm(&app, &token).await - 125.0).abs() < 0.01);
+
+    // 5 reps at RPE 8 is 7 reps to failure, 81.1% on the RPE chart
+    log_completed_sets(
+        &app,
+        &token,
+        "ex-bench-press",
+        &[serde_json::json!({ "actualWeight": 105.0, "actualReps": 5, "rpe": 8 })],
+    )
+    .await;
+    app.run_jobs().await;
+    assert!((current_e1rm(&app, &token).await - 105.0 / 0.811).abs() < 0.01);
+}
+
+/// Week starts of the weekly volume buckets for January 2024.
+async fn january_week_starts(app: &TestApp, token: &str) -> Vec<String> {
+    let resp = app
+        .get_auth(
+            "/api/v1/statistics/volume/weekly?startDate=2024-01-01&endDate=2024-01-31",
+            token,
+        )
+        .await;
+    let body = body_json(resp).await;
+    body["weeks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["weekStart"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_weekly_volume_buckets_in_local_time() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "uma@example.com", "secret1234").await;
+
+    let workout_id = log_completed_workout(&app, &token, "ex-bench-press", &[(100.0, 5)]).await;
+    // Monday 03:00 UTC is still Sunday evening in Los Angeles
+    sqlx::query("UPDATE workouts SET started_at = '2024-01-15T03:00:00Z' WHERE id = $1::uuid")
+        .bind(&workout_id)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    assert_eq!(january_week_starts(&app, &token).await, vec!["2024-01-15"]);
+
+    let resp = app
+        .put_auth(
+            "/api/v1/settings",
+            &token,
+            serde_json::json!({ "timezone": "America/Los_Angeles" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(january_week_starts(&app, &token).await, vec!["2024-01-08"]);
+
+    let resp = app
+        .put_auth(
+            "/api/v1/settings",
+            &token,
+            serde_json::json!({ "weekStart": "sunday" }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    assert_eq!(body["timezone"], "America/Los_Angeles");
+    assert_eq!(body["weekStart"], "sunday");
+    assert_eq!(january_week_starts(&app, &token).await, vec!["2024-01-14"]);
+
+    let resp = app
+        .put_auth(
+            "/api/v1/settings",
+            &token,
+            serde_json::json!({ "timezone": "Mars/Olympus_Mons" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+}
+
+#[tokio::test]
+async fn test_import_completed_workout() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "vera@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts/import",
+            &token,
+            serde_json::json!({
+                "name": "Logged on paper",
+                "startedAt": "2024-03-01T17:00:00Z",
+                "completedAt": "2024-03-01T18:00:00Z",
+                "exercises": [{
+                    "exerciseTemplateId": "ex-bench-press",
+                    "sets": [
+                        { "actualReps": 5, "actualWeight": 100.0, "isCompleted": true },
+                        { "actualReps": 8, "actualWeight": 80.0, "isCompleted": true },
+                        { "targetReps": 8, "targetWeight": 80.0 }
+                    ]
+                }]
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["duration"], 3600);
+    assert_eq!(body["totalVolume"], 1140.0);
+    assert_eq!(body["totalSets"], 2);
+    assert_eq!(body["exercises"][0]["exerciseName"], "Barbell Bench Press");
+    assert_eq!(body["exercises"][0]["sets"].as_array().unwrap().len(), 3);
+
+    assert_eq!(app.run_jobs().await, 1);
+
+    let resp = app.get_auth("/api/v1/personal-records", &token).await;
+    let body = body_json(resp).await;
+    let records = body["records"].as_array().unwrap();
+    assert!(records.iter().any(|r| r["type"] == "max-weight" && r["value"] == 100.0));
+}
+
+#[tokio::test]
+async fn test_import_rejects_invalid_documents() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "walt@example.com", "secret1234").await;
+
+    let invalid = [
+        // Completed without completedAt
+        serde_json::json!({
+            "name": "No end",
+            "startedAt": "2024-03-01T17:00:00Z",
+            "exercises": []
+        }),
+        // Ends before it starts
+        serde_json::json!({
+            "name": "Backwards",
+            "startedAt": "2024-03-01T17:00:00Z",
+            "completedAt": "2024-03-01T16:00:00Z",
+            "exercises": []
+        }),
+        // Unknown exercise
+        serde_json::json!({
+            "name": "Mystery",
+            "startedAt": "2024-03-01T17:00:00Z",
+            "completedAt": "2024-03-01T18:00:00Z",
+            "exercises": [{ "exerciseTemplateId": "ex-nope", "sets": [] }]
+        }),
+        // Nested set out of range
+        serde_json::json!({
+            "name": "Bad RPE",
+            "startedAt": "2024-03-01T17:00:00Z",
+            "completedAt": "2024-03-01T18:00:00Z",
+            "exercises": [{
+                "exerciseTemplateId": "ex-bench-press",
+                "sets": [{ "actualReps": 5, "actualWeight": 100.0, "rpe": 11 }]
+            }]
+        }),
+    ];
+    for doc in invalid {
+        let resp = app.post_auth("/api/v1/workouts/import", &token, doc).await;
+        assert_eq!(resp.status(), 422);
+    }
+
+    let workouts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workouts")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(workouts, 0);
+}
+
+#[tokio::test]
+async fn test_stale_if_match_is_rejected() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "xena@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Session" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let path = format!("/api/v1/workouts/{}", workout_id);
+
+    let resp = app.get_auth(&path, &token).await;
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+
+    let resp = app
+        .patch_auth_if_match(&path, &token, &etag, serde_json::json!({ "name": "Phone" }))
+        .await;
+    assert_eq!(resp.status(), 200);
+    let fresh = resp.headers()["etag"].to_str().unwrap().to_string();
+    assert_ne!(fresh, etag);
+    assert_eq!(
+        body_json(resp).await["version"],
+        fresh.trim_matches('"').parse::<i64>().unwrap()
+    );
+
+    // A second device still holding the old ETag doesn't overwrite the first
+    let resp = app
+        .patch_auth_if_match(
+            &path,
+            &token,
+            &etag,
+            serde_json::json!({ "name": "Laptop" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 412);
+
+    // Adding an exercise changes the workout too
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises", path),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let resp = app
+        .patch_auth_if_match(&path, &token, &fresh, serde_json::json!({ "notes": "x" }))
+        .await;
+    assert_eq!(resp.status(), 412);
+
+    // Sets carry their own version
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises/{}/sets", path, exercise_id),
+            &token,
+            serde_json::json!({}),
+        )
+        .await;
+    let set = body_json(resp).await;
+    let set_path = format!(
+        "{}/exercises/{}/sets/{}",
+        path,
+        exercise_id,
+        set["id"].as_str().unwrap()
+    );
+    let set_etag = format!("\"{}\"", set["version"]);
+    let resp = app
+        .patch_auth_if_match(
+            &set_path,
+            &token,
+            &set_etag,
+            serde_json::json!({ "actualReps": 5 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let resp = app
+        .patch_auth_if_match(
+            &set_path,
+            &token,
+            &set_etag,
+            serde_json::json!({ "actualReps": 6 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 412);
+
+    let resp = app.get_auth(&path, &token).await;
+    let body = body_json(resp).await;
+    assert_eq!(body["name"], "Phone");
+    assert_eq!(body["exercises"][0]["sets"][0]["actualReps"], 5);
+}
+
+#[tokio::test]
+async fn test_rest_and_pauses_are_tracked() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "tara@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Legs" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let path = format!("/api/v1/workouts/{}", workout_id);
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises", path),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let mut set_paths = Vec::new();
+    for _ in 0..2 {
+        let resp = app
+            .post_auth(
+                &format!("{}/exercises/{}/sets", path, exercise_id),
+                &token,
+                serde_json::json!({}),
+            )
+            .await;
+        let set_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+        set_paths.push(format!(
+            "{}/exercises/{}/sets/{}",
+            path, exercise_id, set_id
+        ));
+    }
+    let done = serde_json::json!({ "actualReps": 5, "actualWeight": 100.0, "isCompleted": true });
+
+    let resp = app.patch_auth(&set_paths[0], &token, done.clone()).await;
+    assert!(body_json(resp).await["restSecondsActual"].is_null());
+
+    // The first set was finished 100 seconds ago, with a 30 second pause since
+    let workout_uuid: uuid::Uuid = workout_id.parse().unwrap();
+    sqlx::query(
+        "UPDATE workout_sets SET completed_at = NOW() - INTERVAL '100 seconds' WHERE set_number = 1",
+    )
+    .execute(&app.pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO workout_pauses (workout_id, started_at, ended_at)
+        VALUES ($1, NOW() - INTERVAL '80 seconds', NOW() - INTERVAL '50 seconds')
+        "#,
+    )
+    .bind(workout_uuid)
+    .execute(&app.pool)
+    .await
+    .unwrap();
+
+    let resp = app.patch_auth(&set_paths[1], &token, done).await;
+    let rest = body_json(resp).await["restSecondsActual"].as_i64().unwrap();
+    assert!((69..=71).contains(&rest), "rest was {rest}");
+
+    // An open pause counts until the workout resumes or ends
+    let resp = app
+        .post_auth(&format!("{}/pause", path), &token, serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert!(body["pausedAt"].is_string());
+    assert!(body["pausedSeconds"].as_i64().unwrap() >= 30);
+
+    let resp = app
+        .post_auth(&format!("{}/resume", path), &token, serde_json::json!({}))
+        .await;
+    assert!(body_json(resp).await["pausedAt"].is_null());
+
+    sqlx::query("UPDATE workouts SET started_at = NOW() - INTERVAL '600 seconds' WHERE id = $1")
+        .bind(workout_uuid)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let resp = app
+        .post_auth(&format!("{}/complete", path), &token, serde_json::json!({}))
+        .await;
+    let duration = body_json(resp).await["duration"].as_i64().unwrap();
+    assert!((568..=571).contains(&duration), "duration was {duration}");
+
+    let resp = app
+        .post_auth(&format!("{}/pause", path), &token, serde_json::json!({}))
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = app.get_auth("/api/v1/workouts", &token).await;
+    let rest = body_json(resp).await["workouts"][0]["avgRestSeconds"]
+        .as_i64()
+        .unwrap();
+    assert!((69..=71).contains(&rest), "average rest was {rest}");
+}
+
+#[tokio::test]
+async fn test_resending_completion_keeps_rest() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "will@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Push" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let path = format!("/api/v1/workouts/{}", workout_id);
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises", path),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    let mut set_paths = Vec::new();
+    for _ in 0..2 {
+        let resp = app
+            .post_auth(
+                &format!("{}/exercises/{}/sets", path, exercise_id),
+                &token,
+                serde_json::json!({}),
+            )
+            .await;
+        let set_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+        set_paths.push(format!(
+            "{}/exercises/{}/sets/{}",
+            path, exercise_id, set_id
+        ));
+    }
+    let done = serde_json::json!({ "actualReps": 5, "actualWeight": 100.0, "isCompleted": true });
+
+    app.patch_auth(&set_paths[0], &token, done.clone()).await;
+    sqlx::query(
+        "UPDATE workout_sets SET completed_at = NOW() - INTERVAL '100 seconds' WHERE set_number = 1",
+    )
+    .execute(&app.pool)
+    .await
+    .unwrap();
+    let resp = app.patch_auth(&set_paths[1], &token, done.clone()).await;
+    let rest = body_json(resp).await["restSecondsActual"].as_i64().unwrap();
+    assert!((99..=101).contains(&rest), "rest was {rest}");
+
+    // Sending the completion again, before or after finishing the workout,
+    // keeps when each set was done
+    let resp = app.patch_auth(&set_paths[0], &token, done.clone()).await;
+    assert!(body_json(resp).await["restSecondsActual"].is_null());
+    app.post_auth(&format!("{}/complete", path), &token, serde_json::json!({}))
+        .await;
+    let resp = app.patch_auth(&set_paths[1], &token, done).await;
+    assert_eq!(body_json(resp).await["restSecondsActual"], rest);
+}
+
+#[tokio::test]
+async fn test_drop_set_sub_sets_add_volume_only() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "uma@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Arms" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let path = format!("/api/v1/workouts/{}", workout_id);
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises", path),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let sets_path = format!("{}/exercises/{}/sets", path, exercise_id);
+
+    let resp = app
+        .post_auth(&sets_path, &token, serde_json::json!({ "setType": "drop" }))
+        .await;
+    let top_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+    // A drop takes its main set's type
+    let resp = app
+        .post_auth(
+            &sets_path,
+            &token,
+            serde_json::json!({ "parentSetId": top_id }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["setType"], "drop");
+    assert_eq!(body["parentSetId"], top_id);
+    let drop_id = body["id"].as_str().unwrap().to_string();
+
+    // Neither a drop nor a normal set can have sub-sets
+    let resp = app
+        .post_auth(
+            &sets_path,
+            &token,
+            serde_json::json!({ "parentSetId": drop_id }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+    let resp = app
+        .post_auth(&sets_path, &token, serde_json::json!({}))
+        .await;
+    let normal_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let resp = app
+        .post_auth(
+            &sets_path,
+            &token,
+            serde_json::json!({ "parentSetId": normal_id }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+    let resp = app
+        .patch_auth(
+            &format!("{}/{}", sets_path, drop_id),
+            &token,
+            serde_json::json!({ "setType": "normal" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+
+    for (set_id, weight, reps) in [(&top_id, 100.0, 5), (&drop_id, 70.0, 8)] {
+        app.patch_auth(
+            &format!("{}/{}", sets_path, set_id),
+            &token,
+            serde_json::json!({ "actualReps": reps, "actualWeight": weight, "isCompleted": true }),
+        )
+        .await;
+    }
+
+    let resp = app
+        .post_auth(&format!("{}/complete", path), &token, serde_json::json!({}))
+        .await;
+    let body = body_json(resp).await;
+    assert_eq!(body["totalSets"], 1);
+    assert_eq!(body["totalVolume"], 1060.0);
+    assert_eq!(body["totalReps"], 13);
+
+    let resp = app
+        .get_auth("/api/v1/statistics/muscle-heatmap", &token)
+        .await;
+    let rows = body_json(resp).await["rows"].as_array().unwrap().clone();
+    assert!(!rows.is_empty());
+    assert!(rows.iter().all(|row| row["setCount"] == 1));
+}
+
+#[tokio::test]
+async fn test_unilateral_sets_count_both_sides() {
+    let app = TestApp::new().await;
+    let token = register_and_login(&app, "vera@example.com", "secret1234").await;
+
+    let resp = app
+        .post_auth(
+            "/api/v1/workouts",
+            &token,
+            serde_json::json!({ "name": "Legs" }),
+        )
+        .await;
+    let workout_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let path = format!("/api/v1/workouts/{}", workout_id);
+    let resp = app
+        .post_auth(
+            &format!("{}/exercises", path),
+            &token,
+            serde_json::json!({ "exerciseTemplateId": "ex-bench-press" }),
+        )
+        .await;
+    let exercise_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let sets_path = format!("{}/exercises/{}/sets", path, exercise_id);
+
+    let resp = app
+        .post_auth(&sets_path, &token, serde_json::json!({ "tempo": "slow" }))
+        .await;
+    assert_eq!(resp.status(), 422);
+
+    let resp = app
+        .post_auth(
+            &sets_path,
+            &token,
+            serde_json::json!({ "isUnilateral": true, "targetRir": 2, "tempo": "3-1-1-0" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body = body_json(resp).await;
+    assert_eq!(body["isUnilateral"], true);
+    assert_eq!(body["targetRir"], 2);
+    assert_eq!(body["tempo"], "3-1-1-0");
+    let set_id = body["id"].as_str().unwrap().to_string();
+
+    // Without actualReps, the weaker side's reps are used
+    let resp = app
+        .patch_auth(
+            &format!("{}/{}", sets_path, set_id),
+            &token,
+            serde_json::json!({
+                "actualRepsLeft": 10,
+                "actualRepsRight": 9,
+                "actualWeight": 20.0,
+                "rir": 1,
+                "isCompleted": true
+            }),
+        )
+        .await;
+    let body = body_json(resp).await;
+    assert_eq!(body["actualReps"], 9);
+    assert_eq!(body["rir"], 1);
+
+    // Sides only apply to unilateral sets
+    let resp = app
+        .post_auth(&sets_path, &token, serde_json::json!({}))
+        .await;
+    let other_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+    let resp = app
+        .patch_auth(
+            &format!("{}/{}", sets_path, other_id),
+            &token,
+            serde_json::json!({ "actualRepsLeft": 5 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 422);
+
+    let resp = app
+        .post_auth(&format!("{}/complete", path), &token, serde_json::json!({}))
+        .await;
+    let body = body_json(resp).await;
+    assert_eq!(body["totalVolume"], 380.0);
+    assert_eq!(body["totalReps"], 19);
+}